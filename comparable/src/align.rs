@@ -0,0 +1,309 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{Patch, PatchError};
+use crate::render::{ChangePath, Leaf, LeafValue, PathSegment, Render};
+use crate::set::{
+	apply_vec_changes, compose_vec_changes, invert_vec_changes, merge_vec_changes, render_vec_changes, VecChange,
+};
+use crate::types::{Changed, Comparable};
+
+/// A sequence whose changes are computed by aligning the two sides, rather
+/// than by comparing them index by index as `Vec` does.
+///
+/// Elements are matched up using a shortest edit script (Myers' algorithm), so
+/// an insertion or deletion in the middle of a sequence is reported as a single
+/// `VecChange::Added` or `VecChange::Removed` instead of a cascade of
+/// `VecChange::Changed` entries. Two elements match when
+/// [`Comparable::same_identity`] holds for them, which by default means that
+/// they compare as unchanged; matched elements that differ nonetheless are
+/// reported as `VecChange::Changed`.
+///
+/// An element that was removed in one place and added in another is reported
+/// as `VecChange::Moved`, along with any change made to it. Of the remaining
+/// removed and added elements, those that sit at the same place are paired up,
+/// and each pair is reported as a `VecChange::Changed`.
+///
+/// Indices refer to the original sequence for `Removed` and `Changed`, and to
+/// the new sequence for `Added`.
+///
+/// The same comparison can be selected for a single field of a derived type
+/// with the `#[comparable_aligned]` attribute.
+#[repr(transparent)]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Aligned<T: ?Sized>(pub T);
+
+impl<T: ?Sized> Aligned<T> {
+	/// View a reference to a value as a reference to an `Aligned` value.
+	pub fn from_ref(value: &T) -> &Self {
+		// SAFETY: `Aligned<T>` is `repr(transparent)` over `T`.
+		unsafe { &*(value as *const T as *const Self) }
+	}
+
+	/// View a mutable reference to a value as a mutable reference to an
+	/// `Aligned` value.
+	pub fn from_mut(value: &mut T) -> &mut Self {
+		// SAFETY: `Aligned<T>` is `repr(transparent)` over `T`.
+		unsafe { &mut *(value as *mut T as *mut Self) }
+	}
+}
+
+impl<T> From<T> for Aligned<T> {
+	fn from(value: T) -> Self {
+		Aligned(value)
+	}
+}
+
+impl<T: ?Sized> Deref for Aligned<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T: ?Sized> DerefMut for Aligned<T> {
+	fn deref_mut(&mut self) -> &mut T {
+		&mut self.0
+	}
+}
+
+impl<Value: Comparable> Comparable for Aligned<Vec<Value>> {
+	type Desc = Vec<Value::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		self.0.iter().map(|x| x.describe()).collect()
+	}
+
+	type Change = Vec<VecChange<Value::Desc, Value::Change>>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		aligned_comparison(&self.0, &other.0, options)
+	}
+}
+
+impl<Value: Patch> Patch for Aligned<Vec<Value>> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		apply_vec_changes(&mut self.0, change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(Value::from_desc).collect::<Result<_, _>>().map(Aligned)
+	}
+}
+
+impl<Value: Invert> Invert for Aligned<Vec<Value>> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_vec_changes::<Value>(change)
+	}
+}
+
+impl<Value: Merge> Merge for Aligned<Vec<Value>> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_vec_changes::<Value>(left, right)
+	}
+}
+
+impl<Value: Compose> Compose for Aligned<Vec<Value>> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_vec_changes::<Value>(first, second)
+	}
+}
+
+impl<Value: Render> Render for Aligned<Vec<Value>> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_vec_changes::<Value, _>(change, path, leaves)
+	}
+}
+
+/// Compare two slices by aligning them, comparing their elements under the
+/// given options; see [`Aligned`] for the shape of the resulting changes.
+pub fn aligned_comparison<Value: Comparable>(
+	this: &[Value],
+	other: &[Value],
+	options: &CompareOptions,
+) -> Changed<Vec<VecChange<Value::Desc, Value::Change>>> {
+	let script = edit_script(this.len(), other.len(), |i, j| this[i].same_identity(&other[j]));
+
+	// An element removed in one place and added in another has moved. Elements
+	// can only be matched with `same_identity`, so each removed element is
+	// looked for among the added ones that are not yet matched, and `move_to`
+	// then finds where it went without searching again.
+	let mut unmatched: Vec<usize> = script
+		.iter()
+		.filter_map(|edit| match *edit {
+			Edit::Insert(j) => Some(j),
+			_ => None,
+		})
+		.collect();
+	let mut move_to: Vec<Option<usize>> = vec![None; this.len()];
+	let mut moved_to = vec![false; other.len()];
+	for edit in &script {
+		if let Edit::Delete(i) = *edit {
+			if let Some(pos) = unmatched.iter().position(|&j| this[i].same_identity(&other[j])) {
+				let j = unmatched.remove(pos);
+				moved_to[j] = true;
+				move_to[i] = Some(j);
+			}
+		}
+	}
+
+	let mut changes = Vec::new();
+	let mut removed: Vec<usize> = Vec::new();
+	let mut added: Vec<usize> = Vec::new();
+	let flush = |changes: &mut Vec<_>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+		let paired = removed.len().min(added.len());
+		for (&i, &j) in removed.iter().zip(added.iter()) {
+			if let Changed::Changed(change) = options.compare_at(PathSegment::Index(i), &this[i], &other[j]) {
+				changes.push(VecChange::Changed(i, change));
+			}
+		}
+		for &i in removed[paired..].iter().filter(|&&i| !options.ignores(PathSegment::Index(i))) {
+			changes.push(VecChange::Removed(i, this[i].describe()));
+		}
+		for &j in added[paired..].iter().filter(|&&j| !options.ignores(PathSegment::Index(j))) {
+			changes.push(VecChange::Added(j, other[j].describe()));
+		}
+		removed.clear();
+		added.clear();
+	};
+	for edit in &script {
+		match *edit {
+			Edit::Keep(i, j) => {
+				flush(&mut changes, &mut removed, &mut added);
+				if let Changed::Changed(change) = options.compare_at(PathSegment::Index(i), &this[i], &other[j]) {
+					changes.push(VecChange::Changed(i, change));
+				}
+			}
+			Edit::Delete(from) => match move_to[from] {
+				Some(to) => {
+					if !options.ignores(PathSegment::Index(from)) {
+						let change = options.compare_at(PathSegment::Index(from), &this[from], &other[to]);
+						changes.push(VecChange::Moved { from, to, change })
+					}
+				}
+				None => removed.push(from),
+			},
+			Edit::Insert(j) => {
+				if !moved_to[j] {
+					added.push(j)
+				}
+			}
+		}
+	}
+	flush(&mut changes, &mut removed, &mut added);
+
+	if changes.is_empty() {
+		Changed::Unchanged
+	} else {
+		Changed::Changed(changes)
+	}
+}
+
+#[derive(PartialEq, Debug)]
+pub(crate) enum Edit {
+	Keep(usize, usize),
+	Delete(usize),
+	Insert(usize),
+}
+
+/// Compute a shortest edit script between two sequences of the given lengths,
+/// where `same(i, j)` tells whether the `i`th element of the first matches the
+/// `j`th element of the second.
+pub(crate) fn edit_script(n: usize, m: usize, same: impl Fn(usize, usize) -> bool) -> Vec<Edit> {
+	// Common prefixes and suffixes are cheap to find and need no search.
+	let mut prefix = 0;
+	while prefix < n && prefix < m && same(prefix, prefix) {
+		prefix += 1;
+	}
+	let mut suffix = 0;
+	while suffix < n - prefix && suffix < m - prefix && same(n - 1 - suffix, m - 1 - suffix) {
+		suffix += 1;
+	}
+
+	let mut script: Vec<Edit> = (0..prefix).map(|i| Edit::Keep(i, i)).collect();
+	script.extend(
+		myers(n - prefix - suffix, m - prefix - suffix, |i, j| same(prefix + i, prefix + j)).into_iter().map(|edit| {
+			match edit {
+				Edit::Keep(i, j) => Edit::Keep(prefix + i, prefix + j),
+				Edit::Delete(i) => Edit::Delete(prefix + i),
+				Edit::Insert(j) => Edit::Insert(prefix + j),
+			}
+		}),
+	);
+	script.extend((0..suffix).rev().map(|k| Edit::Keep(n - 1 - k, m - 1 - k)));
+	script
+}
+
+// Myers' O(ND) difference algorithm, keeping the furthest-reaching path of
+// every round so that the edit script can be recovered by walking backwards.
+// Round d reaches only the 2d + 1 diagonals from -d to d, so only those are
+// kept, and the trace takes O(D²) space rather than O(D·(N + M)).
+fn myers(n: usize, m: usize, same: impl Fn(usize, usize) -> bool) -> Vec<Edit> {
+	let max = n + m;
+	let offset = max as isize + 1;
+	let mut v = vec![0usize; 2 * max + 3];
+	let mut trace: Vec<Vec<usize>> = Vec::new();
+
+	for d in 0..=max as isize {
+		let mut done = false;
+		let mut k = -d;
+		while k <= d {
+			let idx = (k + offset) as usize;
+			let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) { v[idx + 1] } else { v[idx - 1] + 1 };
+			let mut y = (x as isize - k) as usize;
+			while x < n && y < m && same(x, y) {
+				x += 1;
+				y += 1;
+			}
+			v[idx] = x;
+			if x >= n && y >= m {
+				done = true;
+				break;
+			}
+			k += 2;
+		}
+		trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+		if done {
+			break;
+		}
+	}
+
+	let mut script = Vec::new();
+	let (mut x, mut y) = (n as isize, m as isize);
+	for d in (1..trace.len() as isize).rev() {
+		// The previous round reached the diagonals from -(d - 1) to d - 1.
+		let prev = &trace[d as usize - 1];
+		let reached = |k: isize| prev[(k + d - 1) as usize] as isize;
+		let k = x - y;
+		let prev_k = if k == -d || (k != d && reached(k - 1) < reached(k + 1)) { k + 1 } else { k - 1 };
+		let prev_x = reached(prev_k);
+		let prev_y = prev_x - prev_k;
+		while x > prev_x && y > prev_y {
+			x -= 1;
+			y -= 1;
+			script.push(Edit::Keep(x as usize, y as usize));
+		}
+		if x == prev_x {
+			y -= 1;
+			script.push(Edit::Insert(y as usize));
+		} else {
+			x -= 1;
+			script.push(Edit::Delete(x as usize));
+		}
+	}
+	while x > 0 && y > 0 {
+		x -= 1;
+		y -= 1;
+		script.push(Edit::Keep(x as usize, y as usize));
+	}
+	script.reverse();
+	script
+}
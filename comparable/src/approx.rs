@@ -0,0 +1,238 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{Conflicts, Merge};
+use crate::patch::{Patch, PatchError};
+use crate::render::{ChangePath, Leaf, LeafValue, Render};
+use crate::scalar::{F32Change, F64Change};
+use crate::types::{Changed, Comparable};
+
+/// How far apart two floating-point numbers may be and still compare as
+/// equal when wrapped in [`Approx`]. Two numbers are equal if they are within
+/// any one of the absolute, relative or ULP tolerances given; a tolerance of
+/// zero allows no difference at all. Infinities only equal themselves, and
+/// NaN equals NaN only if `nan_equal` is set.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FloatTolerance {
+	/// The largest difference allowed between the numbers.
+	pub abs: f64,
+	/// The largest difference allowed, as a fraction of the larger of the
+	/// magnitudes of the numbers.
+	pub rel: f64,
+	/// The most representable values of the numbers' type that may lie
+	/// between them, counting one of them, for numbers of the same sign.
+	pub ulps: u64,
+	/// Whether NaN compares as equal to NaN.
+	pub nan_equal: bool,
+}
+
+impl FloatTolerance {
+	/// No tolerance: numbers are equal if `==` says so.
+	pub const EXACT: FloatTolerance = FloatTolerance { abs: 0.0, rel: 0.0, ulps: 0, nan_equal: false };
+
+	/// Whether two `f64`s are within this tolerance of each other.
+	pub fn eq_f64(&self, a: f64, b: f64) -> bool {
+		self.close(a, b, || {
+			a.is_sign_negative() == b.is_sign_negative() && a.to_bits().abs_diff(b.to_bits()) <= self.ulps
+		})
+	}
+
+	/// Whether two `f32`s are within this tolerance of each other.
+	pub fn eq_f32(&self, a: f32, b: f32) -> bool {
+		self.close(a as f64, b as f64, || {
+			a.is_sign_negative() == b.is_sign_negative() && u64::from(a.to_bits().abs_diff(b.to_bits())) <= self.ulps
+		})
+	}
+
+	fn close(&self, a: f64, b: f64, within_ulps: impl FnOnce() -> bool) -> bool {
+		if a == b {
+			return true;
+		}
+		if a.is_nan() || b.is_nan() {
+			return self.nan_equal && a.is_nan() && b.is_nan();
+		}
+		if a.is_infinite() || b.is_infinite() {
+			return false;
+		}
+		let difference = (a - b).abs();
+		difference <= self.abs || difference <= self.rel * a.abs().max(b.abs()) || within_ulps()
+	}
+}
+
+impl Default for FloatTolerance {
+	fn default() -> Self {
+		FloatTolerance::EXACT
+	}
+}
+
+/// Gives the tolerance with which an [`Approx`] number is compared.
+pub trait Tolerance {
+	const TOLERANCE: FloatTolerance;
+}
+
+/// A floating-point number compared with the tolerance given by `Tol`, so that
+/// numbers close enough to each other are unchanged. Changes are reported
+/// using `F32Change` or `F64Change`, exactly as for the number itself:
+///
+/// ```
+/// # use comparable::*;
+/// struct Close;
+///
+/// impl Tolerance for Close {
+///     const TOLERANCE: FloatTolerance = FloatTolerance { abs: 1e-9, nan_equal: true, ..FloatTolerance::EXACT };
+/// }
+///
+/// let approx = Approx::<f64, Close>::new;
+/// assert_changes!(approx(0.1 + 0.2), approx(0.3), Changed::Unchanged);
+/// assert_changes!(approx(f64::NAN), approx(f64::NAN), Changed::Unchanged);
+/// assert_changes!(approx(0.3), approx(0.4), Changed::Changed(F64Change(0.3, 0.4)));
+/// ```
+///
+/// The same comparison can be selected for a single field of a derived type
+/// with the `#[comparable_tolerance(...)]` attribute.
+#[repr(transparent)]
+pub struct Approx<T, Tol>(pub T, PhantomData<fn() -> Tol>);
+
+impl<T, Tol> Approx<T, Tol> {
+	pub fn new(value: T) -> Self {
+		Approx(value, PhantomData)
+	}
+
+	/// View a reference to a value as a reference to an `Approx` value.
+	pub fn from_ref(value: &T) -> &Self {
+		// SAFETY: `Approx<T, Tol>` is `repr(transparent)` over `T`.
+		unsafe { &*(value as *const T as *const Self) }
+	}
+
+	/// View a mutable reference to a value as a mutable reference to an
+	/// `Approx` value.
+	pub fn from_mut(value: &mut T) -> &mut Self {
+		// SAFETY: `Approx<T, Tol>` is `repr(transparent)` over `T`.
+		unsafe { &mut *(value as *mut T as *mut Self) }
+	}
+}
+
+impl<T: Clone, Tol> Clone for Approx<T, Tol> {
+	fn clone(&self) -> Self {
+		Approx::new(self.0.clone())
+	}
+}
+
+impl<T: Copy, Tol> Copy for Approx<T, Tol> {}
+
+impl<T: Default, Tol> Default for Approx<T, Tol> {
+	fn default() -> Self {
+		Approx::new(T::default())
+	}
+}
+
+impl<T: PartialEq, Tol> PartialEq for Approx<T, Tol> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<T: Debug, Tol> Debug for Approx<T, Tol> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("Approx").field(&self.0).finish()
+	}
+}
+
+impl<T, Tol> From<T> for Approx<T, Tol> {
+	fn from(value: T) -> Self {
+		Approx::new(value)
+	}
+}
+
+impl<T, Tol> Deref for Approx<T, Tol> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T, Tol> DerefMut for Approx<T, Tol> {
+	fn deref_mut(&mut self) -> &mut T {
+		&mut self.0
+	}
+}
+
+macro_rules! impl_approx_for_float {
+	($t:ty, $change:ident, $eq:ident) => {
+		impl<Tol: Tolerance> Comparable for Approx<$t, Tol> {
+			type Desc = $t;
+
+			fn describe(&self) -> Self::Desc {
+				self.0
+			}
+
+			type Change = $change;
+
+			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				if Tol::TOLERANCE.$eq(self.0, other.0) {
+					Changed::Unchanged
+				} else {
+					Changed::Changed($change(self.0, other.0))
+				}
+			}
+		}
+
+		/// The old value recorded in a change need only be within the
+		/// tolerance of the current one.
+		impl<Tol: Tolerance> Patch for Approx<$t, Tol> {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				let $change(old, new) = change;
+				if !Tol::TOLERANCE.$eq(self.0, old) {
+					return Err(PatchError::Mismatch(format!("expected {:?}, found {:?}", old, self.0)));
+				}
+				self.0 = new;
+				Ok(())
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				Ok(Approx::new(desc))
+			}
+		}
+
+		impl<Tol: Tolerance> Invert for Approx<$t, Tol> {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				<$t>::invert(change)
+			}
+		}
+
+		/// Changes to values within the tolerance of each other merge into the
+		/// left one.
+		impl<Tol: Tolerance> Merge for Approx<$t, Tol> {
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				if Tol::TOLERANCE.$eq(left.1, right.1) {
+					Ok(left)
+				} else {
+					Err(Conflicts::between(left, right))
+				}
+			}
+		}
+
+		impl<Tol: Tolerance> Compose for Approx<$t, Tol> {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				let ($change(old, middle), $change(expected, new)) = (first, second);
+				if !Tol::TOLERANCE.$eq(middle, expected) {
+					return Err(PatchError::Mismatch(format!("expected {:?}, found {:?}", expected, middle)));
+				}
+				Ok(Self::new(old).comparison(&Self::new(new)))
+			}
+		}
+
+		impl<Tol: Tolerance> Render for Approx<$t, Tol> {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				<$t>::render_change(change, path, leaves)
+			}
+		}
+	};
+}
+
+impl_approx_for_float!(f32, F32Change, eq_f32);
+impl_approx_for_float!(f64, F64Change, eq_f64);
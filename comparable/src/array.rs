@@ -1,3 +1,9 @@
+use crate::compose::{compose_changed, Compose};
+use crate::invert::{invert_changed, Invert};
+use crate::merge::{merge_part, Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{Patch, PatchError};
+use crate::render::{render_changed, ChangePath, Leaf, LeafValue, PathSegment, Render};
 use crate::types::{Changed, Comparable, MaybeSerde};
 use std::convert::TryInto;
 
@@ -27,10 +33,14 @@ where
 	type Change = [Changed<T::Change>; N];
 
 	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
 		let mut result: Self::Change = [(); N].map(|_| Changed::Unchanged);
 		let mut has_change = false;
 		for i in 0..N {
-			match self[i].comparison(&other[i]) {
+			match options.compare_at(PathSegment::Index(i), &self[i], &other[i]) {
 				Changed::Unchanged => (),
 				Changed::Changed(v) => {
 					has_change = true;
@@ -45,3 +55,92 @@ where
 		}
 	}
 }
+
+impl<T: Patch, const N: usize> Patch for [T; N]
+where
+	[T::Desc; N]: MaybeSerde,
+	[Changed<T::Change>; N]: MaybeSerde,
+{
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		for (value, change) in self.iter_mut().zip(change) {
+			if let Changed::Changed(change) = change {
+				value.apply(change)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		let v = IntoIterator::into_iter(desc).map(T::from_desc).collect::<Result<Vec<_>, _>>()?;
+		Ok(convert_vec_to_array(v))
+	}
+}
+
+impl<T: Invert, const N: usize> Invert for [T; N]
+where
+	[T::Desc; N]: MaybeSerde,
+	[Changed<T::Change>; N]: MaybeSerde,
+{
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		let v = IntoIterator::into_iter(change).map(invert_changed::<T>).collect::<Result<Vec<_>, _>>()?;
+		Ok(convert_vec_to_array(v))
+	}
+}
+
+impl<T: Merge, const N: usize> Merge for [T; N]
+where
+	[T::Desc; N]: MaybeSerde,
+	[Changed<T::Change>; N]: MaybeSerde,
+{
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		let mut conflicts = Vec::new();
+		let merged = IntoIterator::into_iter(left)
+			.zip(IntoIterator::into_iter(right))
+			.enumerate()
+			.map(|(i, (left, right))| {
+				merge_part::<T, _>(left, right, &mut conflicts, |change| {
+					let mut changes: Self::Change = [(); N].map(|_| Changed::Unchanged);
+					changes[i] = Changed::Changed(change);
+					changes
+				})
+			})
+			.collect::<Vec<_>>();
+		if conflicts.is_empty() {
+			Ok(convert_vec_to_array(merged))
+		} else {
+			Err(Conflicts(conflicts))
+		}
+	}
+}
+
+impl<T: Compose, const N: usize> Compose for [T; N]
+where
+	[T::Desc; N]: MaybeSerde,
+	[Changed<T::Change>; N]: MaybeSerde,
+{
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		let v = IntoIterator::into_iter(first)
+			.zip(IntoIterator::into_iter(second))
+			.map(|(first, second)| compose_changed::<T>(first, second))
+			.collect::<Result<Vec<_>, _>>()?;
+		if v.iter().all(Changed::is_unchanged) {
+			Ok(Changed::Unchanged)
+		} else {
+			Ok(Changed::Changed(convert_vec_to_array(v)))
+		}
+	}
+}
+
+impl<T: Render, const N: usize> Render for [T; N]
+where
+	[T::Desc; N]: MaybeSerde,
+	[Changed<T::Change>; N]: MaybeSerde,
+{
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		for (i, change) in change.iter().enumerate() {
+			path.push(PathSegment::Index(i));
+			render_changed::<T, _>(change, path, leaves);
+			path.pop();
+		}
+	}
+}
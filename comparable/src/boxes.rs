@@ -1,3 +1,13 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{Patch, PatchError};
+use crate::render::{ChangePath, Leaf, LeafValue, Render};
 use crate::types::{Changed, Comparable};
 
 impl<T: Comparable> Comparable for Box<T> {
@@ -10,6 +20,180 @@ impl<T: Comparable> Comparable for Box<T> {
 	type Change = T::Change;
 
 	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
-		self.as_ref().comparison(&**other)
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		self.as_ref().comparison_with(&**other, options)
+	}
+
+	fn same_identity(&self, other: &Self) -> bool {
+		self.as_ref().same_identity(&**other)
+	}
+}
+
+impl<T: Patch> Patch for Box<T> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		self.as_mut().apply(change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		T::from_desc(desc).map(Box::new)
+	}
+}
+
+impl<T: Invert> Invert for Box<T> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		T::invert(change)
+	}
+}
+
+impl<T: Merge> Merge for Box<T> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		T::merge_changes(left, right)
+	}
+}
+
+impl<T: Compose> Compose for Box<T> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		T::compose(first, second)
+	}
+}
+
+impl<T: Render> Render for Box<T> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		T::render_change(change, path, leaves)
+	}
+}
+
+// `Rc` and `Arc` compare the values they point to, unless the options allow
+// two pointers to the same value to be taken as unchanged without looking.
+macro_rules! impl_shared {
+	($($ptr:ident),*) => {$(
+		impl<T: Comparable + ?Sized> Comparable for $ptr<T> {
+			type Desc = T::Desc;
+
+			fn describe(&self) -> Self::Desc {
+				self.as_ref().describe()
+			}
+
+			type Change = T::Change;
+
+			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				self.comparison_with(other, &CompareOptions::default())
+			}
+
+			fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+				if options.pointer_identity && $ptr::ptr_eq(self, other) {
+					return Changed::Unchanged;
+				}
+				self.as_ref().comparison_with(&**other, options)
+			}
+
+			fn same_identity(&self, other: &Self) -> bool {
+				self.as_ref().same_identity(&**other)
+			}
+		}
+
+		// A shared value is cloned before it is changed, as `make_mut` does.
+		impl<T: Patch + Clone> Patch for $ptr<T> {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				$ptr::make_mut(self).apply(change)
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				T::from_desc(desc).map($ptr::new)
+			}
+		}
+
+		impl<T: Invert + ?Sized> Invert for $ptr<T> {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				T::invert(change)
+			}
+		}
+
+		impl<T: Merge + ?Sized> Merge for $ptr<T> {
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				T::merge_changes(left, right)
+			}
+		}
+
+		impl<T: Compose + Clone> Compose for $ptr<T> {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				T::compose(first, second)
+			}
+		}
+
+		impl<T: Render + ?Sized> Render for $ptr<T> {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				T::render_change(change, path, leaves)
+			}
+		}
+	)*};
+}
+
+impl_shared!(Rc, Arc);
+
+// A `Cow` compares the value it borrows or owns, and is changed by changing
+// an owned copy, so the owned type must change as the borrowed one does.
+impl<T: Comparable + ToOwned + ?Sized> Comparable for Cow<'_, T> {
+	type Desc = T::Desc;
+
+	fn describe(&self) -> Self::Desc {
+		self.as_ref().describe()
+	}
+
+	type Change = T::Change;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		self.as_ref().comparison_with(&**other, options)
+	}
+
+	fn same_identity(&self, other: &Self) -> bool {
+		self.as_ref().same_identity(&**other)
+	}
+}
+
+impl<T: Comparable + ToOwned + ?Sized> Patch for Cow<'_, T>
+where
+	T::Owned: Patch<Desc = T::Desc, Change = T::Change>,
+{
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		self.to_mut().apply(change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		T::Owned::from_desc(desc).map(Cow::Owned)
+	}
+}
+
+impl<T: Invert + ToOwned + ?Sized> Invert for Cow<'_, T> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		T::invert(change)
+	}
+}
+
+impl<T: Merge + ToOwned + ?Sized> Merge for Cow<'_, T> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		T::merge_changes(left, right)
+	}
+}
+
+impl<T: Invert + ToOwned + ?Sized> Compose for Cow<'_, T>
+where
+	T::Owned: Compose<Desc = T::Desc, Change = T::Change>,
+{
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		T::Owned::compose(first, second)
+	}
+}
+
+impl<T: Render + ToOwned + ?Sized> Render for Cow<'_, T> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		T::render_change(change, path, leaves)
 	}
 }
@@ -0,0 +1,230 @@
+use std::cell::{Cell, Ref, RefCell};
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard, TryLockError};
+
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{Patch, PatchError};
+use crate::render::{ChangePath, Leaf, LeafValue, Render};
+use crate::types::{Changed, Comparable};
+
+// The types with interior mutability compare the values they hold at the time
+// of the comparison. A poisoned lock still holds its value, and a comparison
+// reads it regardless, but a change is not applied to it, since the panic that
+// poisoned the lock may have left it half changed.
+
+fn borrow<T>(cell: &RefCell<T>) -> Ref<'_, T> {
+	cell.try_borrow().unwrap_or_else(|_| panic!("cannot compare a RefCell that is mutably borrowed"))
+}
+
+/// Describing or comparing a `RefCell` borrows the value it holds, and panics
+/// if the cell is mutably borrowed, since the value cannot then be read.
+impl<T: Comparable> Comparable for RefCell<T> {
+	type Desc = T::Desc;
+
+	fn describe(&self) -> Self::Desc {
+		borrow(self).describe()
+	}
+
+	type Change = T::Change;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		borrow(self).comparison_with(&*borrow(other), options)
+	}
+
+	fn same_identity(&self, other: &Self) -> bool {
+		borrow(self).same_identity(&*borrow(other))
+	}
+}
+
+impl<T: Patch> Patch for RefCell<T> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		self.get_mut().apply(change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		T::from_desc(desc).map(RefCell::new)
+	}
+}
+
+impl<T: Comparable + Copy> Comparable for Cell<T> {
+	type Desc = T::Desc;
+
+	fn describe(&self) -> Self::Desc {
+		self.get().describe()
+	}
+
+	type Change = T::Change;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		self.get().comparison_with(&other.get(), options)
+	}
+
+	fn same_identity(&self, other: &Self) -> bool {
+		self.get().same_identity(&other.get())
+	}
+}
+
+impl<T: Patch + Copy> Patch for Cell<T> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		self.get_mut().apply(change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		T::from_desc(desc).map(Cell::new)
+	}
+}
+
+// A lock is only tried, never waited for: the thread comparing may be the one
+// that holds it, and would then wait forever.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+	match mutex.try_lock() {
+		Ok(guard) => guard,
+		Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+		Err(TryLockError::WouldBlock) => panic!("cannot compare a Mutex that is locked"),
+	}
+}
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+	match lock.try_read() {
+		Ok(guard) => guard,
+		Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+		Err(TryLockError::WouldBlock) => panic!("cannot compare an RwLock that is locked for writing"),
+	}
+}
+
+// Two locks are taken in the order of their addresses, so that two threads
+// comparing the same pair of values the other way around cannot deadlock, and
+// a value compared with itself is not locked twice.
+fn in_order<'a, T, G>(this: &'a T, other: &'a T, take: impl Fn(&'a T) -> G) -> Option<(G, G)> {
+	if std::ptr::eq(this, other) {
+		None
+	} else if (this as *const T) < (other as *const T) {
+		let this = take(this);
+		Some((this, take(other)))
+	} else {
+		let other = take(other);
+		Some((take(this), other))
+	}
+}
+
+fn poisoned() -> PatchError {
+	PatchError::Mismatch("the lock is poisoned".to_string())
+}
+
+/// Describing or comparing a `Mutex` locks it, and panics if it is already
+/// locked, whether by this thread or another, rather than waiting.
+impl<T: Comparable> Comparable for Mutex<T> {
+	type Desc = T::Desc;
+
+	fn describe(&self) -> Self::Desc {
+		lock(self).describe()
+	}
+
+	type Change = T::Change;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		match in_order(self, other, lock) {
+			Some((this, other)) => this.comparison_with(&*other, options),
+			None => Changed::Unchanged,
+		}
+	}
+
+	#[allow(clippy::unnecessary_map_or)] // `Option::is_none_or` needs Rust 1.82
+	fn same_identity(&self, other: &Self) -> bool {
+		in_order(self, other, lock).map_or(true, |(this, other)| this.same_identity(&*other))
+	}
+}
+
+impl<T: Patch> Patch for Mutex<T> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		self.get_mut().map_err(|_| poisoned())?.apply(change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		T::from_desc(desc).map(Mutex::new)
+	}
+}
+
+/// Describing or comparing an `RwLock` takes a read lock on it, and panics if it
+/// is locked for writing, whether by this thread or another, rather than
+/// waiting.
+impl<T: Comparable> Comparable for RwLock<T> {
+	type Desc = T::Desc;
+
+	fn describe(&self) -> Self::Desc {
+		read(self).describe()
+	}
+
+	type Change = T::Change;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		match in_order(self, other, read) {
+			Some((this, other)) => this.comparison_with(&*other, options),
+			None => Changed::Unchanged,
+		}
+	}
+
+	#[allow(clippy::unnecessary_map_or)] // `Option::is_none_or` needs Rust 1.82
+	fn same_identity(&self, other: &Self) -> bool {
+		in_order(self, other, read).map_or(true, |(this, other)| this.same_identity(&*other))
+	}
+}
+
+impl<T: Patch> Patch for RwLock<T> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		self.get_mut().map_err(|_| poisoned())?.apply(change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		T::from_desc(desc).map(RwLock::new)
+	}
+}
+
+// The changes to each of these types are those to the value it holds.
+macro_rules! impl_cell {
+	($($cell:ident: $($bound:ident)?),*) => {$(
+		impl<T: Invert $(+ $bound)?> Invert for $cell<T> {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				T::invert(change)
+			}
+		}
+
+		impl<T: Merge $(+ $bound)?> Merge for $cell<T> {
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				T::merge_changes(left, right)
+			}
+		}
+
+		impl<T: Compose $(+ $bound)?> Compose for $cell<T> {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				T::compose(first, second)
+			}
+		}
+
+		impl<T: Render $(+ $bound)?> Render for $cell<T> {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				T::render_change(change, path, leaves)
+			}
+		}
+	)*};
+}
+
+impl_cell!(RefCell:, Cell: Copy, Mutex:, RwLock:);
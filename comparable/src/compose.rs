@@ -0,0 +1,108 @@
+use std::fmt::Debug;
+
+use crate::invert::Invert;
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::types::Changed;
+
+/// Values whose changes can be composed: given the change from one value to a
+/// second, and the change from the second value to a third,
+/// [`Compose::compose`] yields the change from the first value straight to the
+/// third, without needing any of the values:
+///
+/// ```
+/// # use comparable::*;
+/// assert_eq!(i32::compose(I32Change(1, 2), I32Change(2, 3)), Ok(Changed::Changed(I32Change(1, 3))));
+/// assert_eq!(i32::compose(I32Change(1, 2), I32Change(2, 1)), Ok(Changed::Unchanged));
+/// ```
+///
+/// Deriving `Comparable` for a type marked `#[comparable_compose]` also derives
+/// `Compose`, along with `Patch` and `Invert`, which requires that the type of
+/// every compared field implements `Compose` too.
+pub trait Compose: Patch + Invert {
+	/// Compose two changes made one after the other. The result is
+	/// `Changed::Unchanged` if the second change undoes the first. This fails
+	/// with `PatchError::Mismatch` if the second change does not start from
+	/// the value the first one leads to, as far as the changes record it, and
+	/// with `PatchError::Unsupported` if composing them would require
+	/// rebuilding a value that cannot be rebuilt from its description.
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError>;
+}
+
+/// Compose two changes that may be `Changed::Unchanged`, as found in the
+/// changes of tuples, arrays and enum variants.
+pub fn compose_changed<T: Compose>(
+	first: Changed<T::Change>,
+	second: Changed<T::Change>,
+) -> Result<Changed<T::Change>, PatchError> {
+	match (first, second) {
+		(Changed::Unchanged, Changed::Unchanged) => Ok(Changed::Unchanged),
+		(Changed::Changed(change), Changed::Unchanged) | (Changed::Unchanged, Changed::Changed(change)) => {
+			Ok(Changed::Changed(change))
+		}
+		(Changed::Changed(first), Changed::Changed(second)) => T::compose(first, second),
+	}
+}
+
+impl<T: Compose> Compose for &T {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		T::compose(first, second)
+	}
+}
+
+/// Compose a series of changes, such as those between successive snapshots of
+/// a value, into the change from the first snapshot to the last.
+///
+/// ```
+/// # use comparable::*;
+/// let snapshots = vec![vec![1, 2], vec![1, 2, 3], vec![4, 2, 3], vec![4, 2]];
+/// let changes = snapshots.windows(2).map(|pair| pair[0].comparison(&pair[1]));
+/// assert_eq!(compose_all::<Vec<i32>>(changes), Ok(snapshots[0].comparison(&snapshots[3])));
+/// ```
+pub fn compose_all<T: Compose>(
+	changes: impl IntoIterator<Item = Changed<T::Change>>,
+) -> Result<Changed<T::Change>, PatchError> {
+	changes.into_iter().try_fold(Changed::Unchanged, compose_changed::<T>)
+}
+
+/// Compose two changes at least one of which replaces the value outright, like
+/// `OptionChange::Different` and the `Different` variant of the changes of
+/// derived enums. `replacement` takes such a change apart into the
+/// descriptions of the values before and after it, and hands any other change
+/// back. The value in between is rebuilt from its description, and the other
+/// change is applied to it, or undone, to find the value at the far end; the
+/// result is then the comparison of the two ends.
+pub fn compose_replacing<T: Compose>(
+	first: T::Change,
+	second: T::Change,
+	replacement: impl Fn(T::Change) -> Result<(T::Desc, T::Desc), T::Change>,
+) -> Result<Changed<T::Change>, PatchError> {
+	let (old, new) = match (replacement(first), replacement(second)) {
+		(Ok((old, middle)), Ok((expected, new))) => {
+			expect_value(&middle, &expected)?;
+			(old, new)
+		}
+		(Ok((old, middle)), Err(second)) => (old, apply_to_desc::<T>(middle, second)?),
+		(Err(first), Ok((middle, new))) => (revert_desc::<T>(middle, first)?, new),
+		(Err(first), Err(second)) => return Err(out_of_sequence(&first, &second)),
+	};
+	Ok(T::from_desc(old)?.comparison(&T::from_desc(new)?))
+}
+
+/// Apply a change to the value with the given description, returning the
+/// description of the result.
+pub(crate) fn apply_to_desc<T: Patch>(desc: T::Desc, change: T::Change) -> Result<T::Desc, PatchError> {
+	let mut value = T::from_desc(desc)?;
+	value.apply(change)?;
+	Ok(value.describe())
+}
+
+/// Undo a change that led to the value with the given description, returning
+/// the description of the value the change started from.
+pub(crate) fn revert_desc<T: Patch + Invert>(desc: T::Desc, change: T::Change) -> Result<T::Desc, PatchError> {
+	apply_to_desc::<T>(desc, T::invert(change)?)
+}
+
+/// The error for two changes that cannot have been made one after the other.
+pub(crate) fn out_of_sequence<Change: Debug>(first: &Change, second: &Change) -> PatchError {
+	PatchError::Mismatch(format!("{:?} cannot follow {:?}", second, first))
+}
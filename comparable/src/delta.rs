@@ -0,0 +1,331 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Deref, DerefMut, Neg};
+
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{Conflicts, Merge};
+use crate::patch::{Patch, PatchError};
+use crate::render::{push_leaf, ChangePath, Leaf, LeafChange, LeafValue, Render};
+use crate::types::{Changed, Comparable};
+
+/// A number whose changes are computed as the difference between the old and
+/// the new value, rather than as the pair of them that the number's own type
+/// reports. This suits counters and metrics, whose changes are known while
+/// their values depend on what happened before:
+///
+/// ```
+/// # use comparable::*;
+/// assert_changes!(Delta(7u8), Delta(4u8), Changed::Changed(IntDelta::from(-3)));
+/// assert_delta!(&7u8, &4u8, -3);
+/// assert_delta!(&0.5f32, &2.0f32, 1.5);
+/// ```
+///
+/// The differences of integers are [`IntDelta`]s, which hold that of any two
+/// integers of the same type without overflow, and those of floating-point
+/// numbers are `f64`s. Two changes to the same number merge into the sum of
+/// their differences, as do two changes in a row.
+///
+/// Applying a difference to a floating-point number rounds the sum, which may
+/// then differ slightly from the new value the difference was computed from.
+///
+/// The same comparison can be selected for a single field of a derived type
+/// with the `#[comparable_delta]` attribute.
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Delta<T: ?Sized>(pub T);
+
+impl<T: ?Sized> Delta<T> {
+	/// View a reference to a value as a reference to a `Delta` value.
+	pub fn from_ref(value: &T) -> &Self {
+		// SAFETY: `Delta<T>` is `repr(transparent)` over `T`.
+		unsafe { &*(value as *const T as *const Self) }
+	}
+
+	/// View a mutable reference to a value as a mutable reference to a
+	/// `Delta` value.
+	pub fn from_mut(value: &mut T) -> &mut Self {
+		// SAFETY: `Delta<T>` is `repr(transparent)` over `T`.
+		unsafe { &mut *(value as *mut T as *mut Self) }
+	}
+}
+
+impl<T> From<T> for Delta<T> {
+	fn from(value: T) -> Self {
+		Delta(value)
+	}
+}
+
+impl<T: ?Sized> Deref for Delta<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T: ?Sized> DerefMut for Delta<T> {
+	fn deref_mut(&mut self) -> &mut T {
+		&mut self.0
+	}
+}
+
+/// The signed difference between two integers, which is made from any
+/// integer with `From`. It displays with its sign, as `+3` or `-3`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct IntDelta {
+	negative: bool,
+	magnitude: u128,
+}
+
+impl IntDelta {
+	/// Whether the difference is below zero.
+	pub fn is_negative(&self) -> bool {
+		self.negative
+	}
+
+	/// The absolute value of the difference.
+	pub fn magnitude(&self) -> u128 {
+		self.magnitude
+	}
+
+	/// Add two differences, or return `None` if the magnitude of the sum does
+	/// not fit in a `u128`.
+	pub fn checked_add(self, other: IntDelta) -> Option<IntDelta> {
+		let (negative, magnitude) = if self.negative == other.negative {
+			(self.negative, self.magnitude.checked_add(other.magnitude)?)
+		} else if self.magnitude >= other.magnitude {
+			(self.negative, self.magnitude - other.magnitude)
+		} else {
+			(other.negative, other.magnitude - self.magnitude)
+		};
+		Some(IntDelta { negative: negative && magnitude != 0, magnitude })
+	}
+
+	// The integer of the given type with this value, if it is in range.
+	fn to_int<T: TryFrom<i128> + TryFrom<u128>>(self) -> Option<T> {
+		if self.negative {
+			// The magnitude is at least one, and `-i128::MIN` is out of range.
+			i128::try_from(self.magnitude - 1).ok().and_then(|m| T::try_from(-m - 1).ok())
+		} else {
+			T::try_from(self.magnitude).ok()
+		}
+	}
+}
+
+impl Neg for IntDelta {
+	type Output = IntDelta;
+
+	fn neg(self) -> IntDelta {
+		IntDelta { negative: !self.negative && self.magnitude != 0, magnitude: self.magnitude }
+	}
+}
+
+impl fmt::Display for IntDelta {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}{}", if self.negative { '-' } else { '+' }, self.magnitude)
+	}
+}
+
+impl fmt::Debug for IntDelta {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "IntDelta({})", self)
+	}
+}
+
+macro_rules! impl_int_delta_from_signed {
+	($($t:ty),*) => {$(
+		impl From<$t> for IntDelta {
+			fn from(value: $t) -> Self {
+				IntDelta { negative: value < 0, magnitude: (value as i128).unsigned_abs() }
+			}
+		}
+	)*};
+}
+
+macro_rules! impl_int_delta_from_unsigned {
+	($($t:ty),*) => {$(
+		impl From<$t> for IntDelta {
+			fn from(value: $t) -> Self {
+				IntDelta { negative: false, magnitude: value as u128 }
+			}
+		}
+	)*};
+}
+
+impl_int_delta_from_signed!(i8, i16, i32, i64, i128, isize);
+impl_int_delta_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_delta_for_int {
+	($($t:ty),*) => {$(
+		impl Comparable for Delta<$t> {
+			type Desc = $t;
+
+			fn describe(&self) -> Self::Desc {
+				self.0
+			}
+
+			type Change = IntDelta;
+
+			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				if self.0 != other.0 {
+					// Two integers of the same type are never further apart
+					// than `u128::MAX`.
+					Changed::Changed(IntDelta::from(other.0).checked_add(-IntDelta::from(self.0)).unwrap())
+				} else {
+					Changed::Unchanged
+				}
+			}
+		}
+
+		impl Patch for Delta<$t> {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				self.0 = IntDelta::from(self.0).checked_add(change).and_then(IntDelta::to_int).ok_or_else(|| {
+					PatchError::Mismatch(format!("{} {} is out of range for {}", self.0, change, stringify!($t)))
+				})?;
+				Ok(())
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				Ok(Delta(desc))
+			}
+		}
+
+		impl Invert for Delta<$t> {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				Ok(-change)
+			}
+		}
+
+		impl Merge for Delta<$t> {
+			/// Both differences are added to the number, unless their sum
+			/// is out of range.
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				left.checked_add(right).ok_or_else(|| Conflicts::between(left, right))
+			}
+		}
+
+		impl Compose for Delta<$t> {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				match first.checked_add(second) {
+					Some(sum) if sum == IntDelta::default() => Ok(Changed::Unchanged),
+					Some(sum) => Ok(Changed::Changed(sum)),
+					None => Err(PatchError::Mismatch(format!("{} {} is out of range", first, second))),
+				}
+			}
+		}
+
+		impl Render for Delta<$t> {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				push_leaf(None, LeafChange::Diff(change.to_string()), path, leaves);
+			}
+		}
+
+		impl ExpectedDelta for Delta<$t> {
+			fn is_expected(&self, _other: &Self, actual: &Self::Change, expected: &Self::Change) -> bool {
+				actual == expected
+			}
+		}
+	)*};
+}
+
+impl_delta_for_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_delta_for_float {
+	($($t:ty),*) => {$(
+		impl Comparable for Delta<$t> {
+			type Desc = $t;
+
+			fn describe(&self) -> Self::Desc {
+				self.0
+			}
+
+			type Change = f64;
+
+			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				if self.0 != other.0 {
+					Changed::Changed(other.0 as f64 - self.0 as f64)
+				} else {
+					Changed::Unchanged
+				}
+			}
+		}
+
+		impl Patch for Delta<$t> {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				self.0 = (self.0 as f64 + change) as $t;
+				Ok(())
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				Ok(Delta(desc))
+			}
+		}
+
+		impl Invert for Delta<$t> {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				Ok(-change)
+			}
+		}
+
+		impl Merge for Delta<$t> {
+			/// Both differences are added to the number.
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				Ok(left + right)
+			}
+		}
+
+		impl Compose for Delta<$t> {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				let sum = first + second;
+				Ok(if sum == 0.0 { Changed::Unchanged } else { Changed::Changed(sum) })
+			}
+		}
+
+		impl Render for Delta<$t> {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				push_leaf(None, LeafChange::Diff(format!("{:+}", change)), path, leaves);
+			}
+		}
+
+		impl ExpectedDelta for Delta<$t> {
+			// The difference and the number it is checked against are both
+			// rounded, so they need only agree to within a few units in the
+			// last place of the largest number involved.
+			fn is_expected(&self, other: &Self, actual: &Self::Change, expected: &Self::Change) -> bool {
+				let scale = (self.0 as f64).abs().max((other.0 as f64).abs()).max(expected.abs());
+				actual == expected || (actual - expected).abs() <= 4.0 * <$t>::EPSILON as f64 * scale
+			}
+		}
+	)*};
+}
+
+impl_delta_for_float!(f32, f64);
+
+/// How `assert_delta!` decides whether the difference between two numbers is
+/// the one expected: exactly for integers, and within a few units in the last
+/// place for floating-point numbers, whose differences are rounded.
+#[doc(hidden)]
+pub trait ExpectedDelta: Comparable {
+	fn is_expected(&self, other: &Self, actual: &Self::Change, expected: &Self::Change) -> bool;
+}
+
+/// Check the difference between two numbers against the one expected,
+/// returning a description of how they differ if they do. Used by
+/// `assert_delta!`.
+#[doc(hidden)]
+pub fn check_delta<T: fmt::Debug, E>(old: &T, new: &T, expected: E) -> Result<(), String>
+where
+	Delta<T>: ExpectedDelta,
+	<Delta<T> as Comparable>::Change: From<E> + Default + fmt::Display,
+{
+	let expected = <Delta<T> as Comparable>::Change::from(expected);
+	let (old_delta, new_delta) = (Delta::from_ref(old), Delta::from_ref(new));
+	let actual = old_delta.comparison(new_delta).unwrap_or_default();
+	if old_delta.is_expected(new_delta, &actual, &expected) {
+		Ok(())
+	} else {
+		Err(format!("expected a change of {:+}, found {:+} from {:?} to {:?}", expected, actual, old, new))
+	}
+}
@@ -1,13 +1,60 @@
+use std::iter::Empty;
+use std::marker::PhantomData;
+
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{Conflicts, Merge};
+use crate::patch::{Patch, PatchError};
+use crate::render::{ChangePath, Leaf, LeafValue, Render};
 use crate::types::{Changed, Comparable};
 
-impl<T> Comparable for std::iter::Empty<T> {
-	type Desc = ();
+// Neither type holds a value, so there is never a change between two of them.
+macro_rules! impl_empty {
+	($($ty:ident: $new:expr),*) => {$(
+		impl<T> Comparable for $ty<T> {
+			type Desc = ();
+
+			fn describe(&self) -> Self::Desc {}
+
+			type Change = ();
+
+			fn comparison(&self, _other: &Self) -> Changed<Self::Change> {
+				Changed::Unchanged
+			}
+		}
 
-	fn describe(&self) -> Self::Desc {}
+		impl<T> Patch for $ty<T> {
+			fn apply(&mut self, _change: Self::Change) -> Result<(), PatchError> {
+				Ok(())
+			}
 
-	type Change = ();
+			fn from_desc(_desc: Self::Desc) -> Result<Self, PatchError> {
+				Ok($new)
+			}
+		}
 
-	fn comparison(&self, _other: &Self) -> Changed<Self::Change> {
-		Changed::Unchanged
-	}
+		impl<T> Invert for $ty<T> {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				Ok(change)
+			}
+		}
+
+		impl<T> Merge for $ty<T> {
+			fn merge_changes(left: Self::Change, _right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				Ok(left)
+			}
+		}
+
+		impl<T> Compose for $ty<T> {
+			fn compose(_first: Self::Change, _second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				Ok(Changed::Unchanged)
+			}
+		}
+
+		impl<T> Render for $ty<T> {
+			fn render_change<V: LeafValue>(_change: &Self::Change, _path: &mut ChangePath<V>, _leaves: &mut Vec<Leaf<V>>) {}
+		}
+	)*};
 }
+
+impl_empty!(Empty: std::iter::empty(), PhantomData: PhantomData);
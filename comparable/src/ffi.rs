@@ -0,0 +1,106 @@
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{merge_whole, Conflicts, Merge};
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{render_replaced, ChangePath, Leaf, LeafValue, Render};
+use crate::types::{Changed, Comparable};
+use std::ffi::{OsStr, OsString};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug)]
+pub struct OsStringChange(pub OsString, pub OsString);
+
+impl Comparable for OsString {
+	type Desc = OsString;
+
+	fn describe(&self) -> Self::Desc {
+		self.clone()
+	}
+
+	type Change = OsStringChange;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		if self != other {
+			Changed::Changed(OsStringChange(self.clone(), other.clone()))
+		} else {
+			Changed::Unchanged
+		}
+	}
+}
+
+impl Patch for OsString {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let OsStringChange(old, new) = change;
+		expect_value(self, &old)?;
+		*self = new;
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		Ok(desc)
+	}
+}
+
+impl Invert for OsString {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		let OsStringChange(old, new) = change;
+		Ok(OsStringChange(new, old))
+	}
+}
+
+impl Merge for OsString {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_whole(left, right)
+	}
+}
+
+impl Compose for OsString {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		let (OsStringChange(old, middle), OsStringChange(expected, new)) = (first, second);
+		expect_value(&middle, &expected)?;
+		Ok(old.comparison(&new))
+	}
+}
+
+impl Render for OsString {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		let OsStringChange(old, new) = change;
+		render_replaced(old, new, path, leaves);
+	}
+}
+
+impl Comparable for OsStr {
+	type Desc = OsString;
+
+	fn describe(&self) -> Self::Desc {
+		self.to_os_string()
+	}
+
+	type Change = OsStringChange;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		if self != other {
+			Changed::Changed(OsStringChange(self.to_os_string(), other.to_os_string()))
+		} else {
+			Changed::Unchanged
+		}
+	}
+}
+
+impl Invert for OsStr {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		OsString::invert(change)
+	}
+}
+
+impl Merge for OsStr {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_whole(left, right)
+	}
+}
+
+impl Render for OsStr {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		OsString::render_change(change, path, leaves)
+	}
+}
@@ -0,0 +1,43 @@
+use crate::patch::PatchError;
+use crate::types::{Changed, Comparable};
+
+/// Values whose changes can be inverted: given the change from one value to
+/// another, as computed by [`Comparable::comparison`], [`Invert::invert`]
+/// yields the change from the second value back to the first, without needing
+/// either value:
+///
+/// ```
+/// # use comparable::*;
+/// let a = vec![1, 2, 3];
+/// let mut b = vec![1, 4];
+/// if let Changed::Changed(change) = a.comparison(&b) {
+///     b.apply(Vec::<i32>::invert(change).unwrap()).unwrap();
+/// }
+/// assert_eq!(a, b);
+/// ```
+///
+/// Deriving `Comparable` for a type marked `#[comparable_invert]` also derives
+/// `Invert`, which requires that the type of every compared field implements
+/// `Invert` too.
+pub trait Invert: Comparable {
+	/// Invert a change. This fails with `PatchError::Unsupported` if the change
+	/// does not record a value it takes away, which is the case for the
+	/// removal of an entry from a map; see `Reversible` for a way to compare
+	/// maps that does record it.
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError>;
+}
+
+/// Invert a change that may be `Changed::Unchanged`, as found in the changes of
+/// tuples, arrays and enum variants.
+pub fn invert_changed<T: Invert + ?Sized>(change: Changed<T::Change>) -> Result<Changed<T::Change>, PatchError> {
+	Ok(match change {
+		Changed::Unchanged => Changed::Unchanged,
+		Changed::Changed(change) => Changed::Changed(T::invert(change)?),
+	})
+}
+
+impl<T: Invert> Invert for &T {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		T::invert(change)
+	}
+}
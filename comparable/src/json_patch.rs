@@ -0,0 +1,344 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug};
+
+use serde_json::{json, Value};
+
+use crate::render::{render_changed, ChangePath, Leaf, LeafChange, LeafValue, PathSegment, Render};
+use crate::types::{Changed, MaybeSerde};
+
+/// The reason a change cannot be written as a JSON Patch, or as a JSON Merge
+/// Patch.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct JsonPatchError(pub String);
+
+impl fmt::Display for JsonPatchError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "change cannot be expressed as a JSON patch: {}", self.0)
+	}
+}
+
+impl std::error::Error for JsonPatchError {}
+
+// A value found in a change, as JSON, or the reason it could not be
+// serialized.
+#[derive(Clone, PartialEq, Debug)]
+struct Json(Result<Value, String>);
+
+impl LeafValue for Json {
+	fn of<T: Debug + MaybeSerde>(value: &T) -> Self {
+		Json(serde_json::to_value(value).map_err(|err| format!("cannot serialize {:?}: {}", value, err)))
+	}
+}
+
+impl Json {
+	fn value(&self) -> Result<Value, JsonPatchError> {
+		self.0.clone().map_err(JsonPatchError)
+	}
+}
+
+/// Write a change as an [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902)
+/// JSON Patch document, which turns the JSON serialization of the first value
+/// compared into that of the second:
+///
+/// ```
+/// # use comparable::*;
+/// # use serde_json::json;
+/// let a = vec![1, 2, 3];
+/// let b = vec![1, 5];
+/// assert_eq!(
+///     json_patch::<Vec<i32>>(&a.comparison(&b)),
+///     Ok(json!([
+///         { "op": "replace", "path": "/1", "value": 5 },
+///         { "op": "remove", "path": "/2" },
+///     ]))
+/// );
+/// ```
+///
+/// Paths are those of [`render`](crate::render), written as JSON Pointers,
+/// which assumes the types involved serialize the way `serde` derives it by
+/// default. Changes that cannot be pointed at are reported as errors: the
+/// removal of a member of a set, which has no index of its own, and any change
+/// to an element of a `#[comparable_key]` vector, which is known only by its
+/// key.
+pub fn json_patch<T: Render + ?Sized>(change: &Changed<T::Change>) -> Result<Value, JsonPatchError> {
+	let mut leaves = Vec::new();
+	render_changed::<T, Json>(change, &mut ChangePath::default(), &mut leaves);
+	let leaves: Vec<_> = leaves.iter().map(|Leaf { path, change }| (&path.0[..], change)).collect();
+	let mut ops = Vec::new();
+	write_ops(&leaves, "", &mut ops)?;
+	Ok(Value::Array(ops))
+}
+
+type LeafRef<'a> = (&'a [PathSegment<Json>], &'a LeafChange<Json>);
+
+// Write the operations for the leaves of a change to the value at `pointer`,
+// their paths being relative to that value.
+fn write_ops(leaves: &[LeafRef<'_>], pointer: &str, ops: &mut Vec<Value>) -> Result<(), JsonPatchError> {
+	let mut indexed = Vec::new();
+	let mut groups: Vec<(String, Vec<LeafRef<'_>>)> = Vec::new();
+	for &(path, change) in leaves {
+		let (segment, rest) = match path.split_first() {
+			None => {
+				write_set_op(change, pointer, ops)?;
+				continue;
+			}
+			Some(split) => split,
+		};
+		let child = match segment {
+			PathSegment::Field(name) | PathSegment::Variant(name) => child_pointer(pointer, name),
+			PathSegment::Key(key) => child_pointer(pointer, &key_token(key)?),
+			PathSegment::Index(_) => {
+				indexed.push((path, change));
+				continue;
+			}
+			PathSegment::Element(key) => {
+				return Err(JsonPatchError(format!(
+					"the element keyed by {} at {:?} has no known index",
+					key.value()?,
+					pointer
+				)))
+			}
+		};
+		match groups.iter_mut().find(|(pointer, _)| *pointer == child) {
+			Some((_, group)) => group.push((rest, change)),
+			None => groups.push((child, vec![(rest, change)])),
+		}
+	}
+	for (child, group) in groups {
+		// Entries of a map are added and removed as a whole.
+		let mut nested = Vec::new();
+		for (rest, change) in group {
+			match (rest.is_empty(), change) {
+				(true, LeafChange::Added(value)) => {
+					ops.push(json!({ "op": "add", "path": child, "value": value.value()? }))
+				}
+				(true, LeafChange::Removed(_)) => ops.push(json!({ "op": "remove", "path": child })),
+				_ => nested.push((rest, change)),
+			}
+		}
+		write_ops(&nested, &child, ops)?;
+	}
+	if !indexed.is_empty() {
+		write_sequence_ops(&indexed, pointer, ops)?;
+	}
+	Ok(())
+}
+
+// Changes at the path of a value itself replace it, unless the value is a set
+// whose members were added or removed.
+fn write_set_op(change: &LeafChange<Json>, pointer: &str, ops: &mut Vec<Value>) -> Result<(), JsonPatchError> {
+	match change {
+		LeafChange::Changed(_, new) => ops.push(json!({ "op": "replace", "path": pointer, "value": new.value()? })),
+		LeafChange::Added(value) => {
+			ops.push(json!({ "op": "add", "path": child_pointer(pointer, "-"), "value": value.value()? }))
+		}
+		LeafChange::Removed(value) => {
+			let value = value.as_ref().map(Json::value).transpose()?.unwrap_or(Value::Null);
+			return Err(JsonPatchError(format!("the member {} of the set at {:?} has no index", value, pointer)));
+		}
+		LeafChange::Moved(..) => unreachable!("moves are rendered at the index of the element moved"),
+		LeafChange::Diff(_) => return Err(diff_error(pointer)),
+	}
+	Ok(())
+}
+
+enum Slot {
+	Old(usize),
+	New,
+}
+
+enum Placed {
+	Added(Value),
+	Moved(usize),
+}
+
+// Write the operations for changes to the elements of a sequence. As with
+// `VecChange`, elements that changed or were removed or moved are found at
+// their old indices, and those added or moved at their new ones. JSON Patch
+// applies each operation in turn, so the changes in place are written first,
+// then the removals from the back, and last the elements are put where they
+// belong from the front; moved elements are set aside at the front meanwhile.
+fn write_sequence_ops(leaves: &[LeafRef<'_>], pointer: &str, ops: &mut Vec<Value>) -> Result<(), JsonPatchError> {
+	let mut in_place: BTreeMap<usize, Vec<LeafRef<'_>>> = BTreeMap::new();
+	let mut removed = Vec::new();
+	let mut moved = Vec::new();
+	let mut placed = BTreeMap::new();
+	for &(path, change) in leaves {
+		let (index, rest) = match path.split_first() {
+			Some((PathSegment::Index(index), rest)) => (*index, rest),
+			_ => unreachable!("only indexed leaves are written as a sequence"),
+		};
+		match (rest.is_empty(), change) {
+			(true, LeafChange::Added(value)) => {
+				placed.insert(index, Placed::Added(value.value()?));
+			}
+			(true, LeafChange::Removed(_)) => removed.push(index),
+			(true, LeafChange::Moved(from, to)) => {
+				moved.push(*from);
+				placed.insert(*to, Placed::Moved(*from));
+			}
+			_ => in_place.entry(index).or_default().push((rest, change)),
+		}
+	}
+	for (index, group) in in_place {
+		write_ops(&group, &child_pointer(pointer, &index.to_string()), ops)?;
+	}
+
+	// The elements up to the last one touched, by where they are now.
+	let last = removed.iter().chain(&moved).max().map_or(0, |last| last + 1);
+	let mut slots: Vec<Slot> = (0..last).map(Slot::Old).collect();
+	let position = |slots: &[Slot], old: usize| slots.iter().position(|slot| matches!(slot, Slot::Old(i) if *i == old));
+	let index_pointer = |index: usize| child_pointer(pointer, &index.to_string());
+
+	removed.sort_unstable();
+	for old in removed.into_iter().rev() {
+		let at = position(&slots, old).expect("removed elements are modeled");
+		slots.remove(at);
+		ops.push(json!({ "op": "remove", "path": index_pointer(at) }));
+	}
+	moved.sort_unstable();
+	for &old in &moved {
+		let at = position(&slots, old).expect("moved elements are modeled");
+		let slot = slots.remove(at);
+		slots.insert(0, slot);
+		if at != 0 {
+			ops.push(json!({ "op": "move", "from": index_pointer(at), "path": index_pointer(0) }));
+		}
+	}
+	let mut set_aside = moved.len();
+	for (index, placed) in placed {
+		match placed {
+			Placed::Added(value) => {
+				let at = set_aside + index;
+				if at <= slots.len() {
+					slots.insert(at, Slot::New);
+				}
+				ops.push(json!({ "op": "add", "path": index_pointer(at), "value": value }));
+			}
+			Placed::Moved(old) => {
+				let from = position(&slots, old).expect("moved elements are modeled");
+				set_aside -= 1;
+				let at = set_aside + index;
+				let slot = slots.remove(from);
+				if at <= slots.len() {
+					slots.insert(at, slot);
+				}
+				if from != at {
+					ops.push(json!({ "op": "move", "from": index_pointer(from), "path": index_pointer(at) }));
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Write a change as an [RFC 7386](https://www.rfc-editor.org/rfc/rfc7386)
+/// JSON Merge Patch document, which turns the JSON serialization of the first
+/// value compared into that of the second. Requires the `json_merge_patch`
+/// feature.
+///
+/// ```
+/// # use comparable::*;
+/// # use serde_json::json;
+/// # use std::collections::BTreeMap;
+/// let a: BTreeMap<String, u8> = vec![("x".to_string(), 1), ("y".to_string(), 2)].into_iter().collect();
+/// let b: BTreeMap<String, u8> = vec![("x".to_string(), 3)].into_iter().collect();
+/// assert_eq!(
+///     json_merge_patch::<BTreeMap<String, u8>>(&a.comparison(&b)),
+///     Ok(json!({ "x": 3, "y": null }))
+/// );
+/// ```
+///
+/// Struct fields, enum variants and map keys become members of nested
+/// objects, and removed entries become `null`, so a field that changed to
+/// `null`, such as an `Option` that became `None`, is removed as well, which
+/// `serde` reads back as `None`. A merge patch can only replace arrays as a
+/// whole, so changes to the elements of sequences and sets are reported as
+/// errors.
+#[cfg(feature = "json_merge_patch")]
+pub fn json_merge_patch<T: Render + ?Sized>(change: &Changed<T::Change>) -> Result<Value, JsonPatchError> {
+	let mut leaves = Vec::new();
+	render_changed::<T, Json>(change, &mut ChangePath::default(), &mut leaves);
+	let mut patch = Value::Object(serde_json::Map::new());
+	for Leaf { path, change } in &leaves {
+		let mut target = &mut patch;
+		let mut pointer = String::new();
+		for segment in &path.0 {
+			let member = match segment {
+				PathSegment::Field(name) | PathSegment::Variant(name) => name.to_string(),
+				PathSegment::Key(key) => key_token(key)?,
+				PathSegment::Index(index) => {
+					let pointer = child_pointer(&pointer, &index.to_string());
+					return Err(JsonPatchError(format!("the element at {:?} of an array cannot be merged", pointer)));
+				}
+				PathSegment::Element(key) => {
+					return Err(JsonPatchError(format!(
+						"the element keyed by {} at {:?} of an array cannot be merged",
+						key.value()?,
+						pointer
+					)))
+				}
+			};
+			pointer = child_pointer(&pointer, &member);
+			target = target
+				.as_object_mut()
+				.expect("the members on the path to a change are objects")
+				.entry(member)
+				.or_insert_with(|| Value::Object(serde_json::Map::new()));
+		}
+		*target = match change {
+			LeafChange::Changed(old, new) => replacement(&old.value()?, new.value()?),
+			LeafChange::Added(value) if !path.is_empty() => value.value()?,
+			LeafChange::Removed(_) if !path.is_empty() => Value::Null,
+			LeafChange::Added(_) | LeafChange::Removed(_) => {
+				return Err(JsonPatchError("the members of a set cannot be merged".to_string()))
+			}
+			LeafChange::Moved(..) => unreachable!("moves are rendered at the index of the element moved"),
+			LeafChange::Diff(_) => return Err(diff_error(&pointer)),
+		};
+	}
+	Ok(patch)
+}
+
+// The merge patch that replaces one value by another. Merging an object into
+// an object keeps the members of the old one that the new one lacks, so these
+// are removed explicitly.
+#[cfg(feature = "json_merge_patch")]
+fn replacement(old: &Value, new: Value) -> Value {
+	match (old, new) {
+		(Value::Object(old), Value::Object(mut new)) => {
+			for (member, old) in old {
+				match new.get_mut(member) {
+					None => {
+						new.insert(member.clone(), Value::Null);
+					}
+					Some(value) if value == old => {
+						new.remove(member);
+					}
+					Some(value) => *value = replacement(old, value.take()),
+				}
+			}
+			Value::Object(new)
+		}
+		(_, new) => new,
+	}
+}
+
+// A diff of a string only holds the parts of it that changed, while JSON
+// patches can only replace a string as a whole.
+fn diff_error(pointer: &str) -> JsonPatchError {
+	JsonPatchError(format!("the string at {:?} is changed by a diff, which does not hold all of it", pointer))
+}
+
+fn child_pointer(pointer: &str, token: &str) -> String {
+	format!("{}/{}", pointer, token.replace('~', "~0").replace('/', "~1"))
+}
+
+// Map keys are written as the strings `serde_json` uses for them.
+fn key_token(key: &Json) -> Result<String, JsonPatchError> {
+	match key.value()? {
+		Value::String(key) => Ok(key),
+		key @ (Value::Number(_) | Value::Bool(_)) => Ok(key.to_string()),
+		key => Err(JsonPatchError(format!("the map key {} is not a string", key))),
+	}
+}
@@ -0,0 +1,296 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use crate::align::{edit_script, Edit};
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::map::{
+	compose_map_changes, invert_map_changes, merge_map_changes, missing_key, present_key, render_map_changes, MapChange,
+};
+use crate::merge::{Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{Patch, PatchError};
+use crate::render::{ChangePath, Leaf, LeafValue, PathSegment, Render};
+use crate::types::{Changed, Comparable, MaybeSerde};
+
+/// Extracts the key that identifies an element of a [`Keyed`] sequence.
+pub trait KeyFn<T> {
+	type Key: Ord + Clone + Debug + MaybeSerde;
+
+	/// Whether to report elements whose position changed relative to the
+	/// others, using `MapChange::Moved`.
+	const REPORT_MOVES: bool = false;
+
+	fn key(value: &T) -> Self::Key;
+}
+
+/// A sequence of entities, compared as though it were a map from the key of
+/// each element (as given by `F`) to the element itself. Changes are reported
+/// using `MapChange`, exactly as for a `BTreeMap`, and if `F` asks for it,
+/// elements that were reordered are also reported as `MapChange::Moved`.
+///
+/// Keys are expected to be unique within a sequence. Should a key occur more
+/// than once, its occurrences are matched up by position, the first in one
+/// sequence with the first in the other and so on, and the changes to them are
+/// reported under the same key. Such a change is ambiguous, and so applying it
+/// to a sequence in which the key is not unique is a `PatchError::Mismatch`.
+///
+/// The same comparison can be selected for a single field of a derived type
+/// with the `#[comparable_key(...)]` attribute.
+#[repr(transparent)]
+pub struct Keyed<T, F>(pub T, PhantomData<fn() -> F>);
+
+impl<T, F> Keyed<T, F> {
+	pub fn new(value: T) -> Self {
+		Keyed(value, PhantomData)
+	}
+
+	/// View a reference to a value as a reference to a `Keyed` value.
+	pub fn from_ref(value: &T) -> &Self {
+		// SAFETY: `Keyed<T, F>` is `repr(transparent)` over `T`.
+		unsafe { &*(value as *const T as *const Self) }
+	}
+
+	/// View a mutable reference to a value as a mutable reference to a
+	/// `Keyed` value.
+	pub fn from_mut(value: &mut T) -> &mut Self {
+		// SAFETY: `Keyed<T, F>` is `repr(transparent)` over `T`.
+		unsafe { &mut *(value as *mut T as *mut Self) }
+	}
+}
+
+impl<T: Clone, F> Clone for Keyed<T, F> {
+	fn clone(&self) -> Self {
+		Keyed::new(self.0.clone())
+	}
+}
+
+impl<T: Default, F> Default for Keyed<T, F> {
+	fn default() -> Self {
+		Keyed::new(T::default())
+	}
+}
+
+impl<T: PartialEq, F> PartialEq for Keyed<T, F> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<T: Debug, F> Debug for Keyed<T, F> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("Keyed").field(&self.0).finish()
+	}
+}
+
+impl<T, F> From<T> for Keyed<T, F> {
+	fn from(value: T) -> Self {
+		Keyed::new(value)
+	}
+}
+
+impl<T, F> Deref for Keyed<T, F> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T, F> DerefMut for Keyed<T, F> {
+	fn deref_mut(&mut self) -> &mut T {
+		&mut self.0
+	}
+}
+
+impl<Value: Comparable, F: KeyFn<Value>> Comparable for Keyed<Vec<Value>, F> {
+	type Desc = Vec<Value::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		self.0.iter().map(|x| x.describe()).collect()
+	}
+
+	type Change = Vec<MapChange<F::Key, Value::Desc, Value::Change>>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		keyed_comparison::<Value, F>(&self.0, &other.0, options)
+	}
+}
+
+/// Since `MapChange::Added` does not record where an element was added, added
+/// elements are appended to the sequence, and so the sequence a change is
+/// applied to ends up with the same elements as the one the change was
+/// computed against, but possibly in a different order.
+impl<Value: Patch, F: KeyFn<Value>> Patch for Keyed<Vec<Value>, F> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let index = index_by_key::<Value, F>(&self.0);
+		let mut removals = Vec::new();
+		let mut moves = Vec::new();
+		for change in change {
+			match change {
+				MapChange::Added(key, desc) => {
+					if index.contains_key(&key) {
+						return Err(present_key(&key));
+					}
+					self.0.push(Value::from_desc(desc)?);
+				}
+				MapChange::Changed(key, change) => {
+					self.0[unique_index(&index, &key)?.ok_or_else(|| missing_key(&key))?].apply(change)?
+				}
+				MapChange::Removed(key) => {
+					removals.push((unique_index(&index, &key)?.ok_or_else(|| missing_key(&key))?, None))
+				}
+				MapChange::Moved { key, from, to } => {
+					if unique_index(&index, &key)? != Some(from) {
+						return Err(PatchError::Mismatch(format!("key {:?} is not at index {}", key, from)));
+					}
+					removals.push((from, Some(to)));
+				}
+			}
+		}
+
+		removals.sort_by_key(|&(i, _)| i);
+		if removals.windows(2).any(|w| w[0].0 == w[1].0) {
+			return Err(PatchError::Mismatch("an element is removed more than once".to_string()));
+		}
+		for (i, to) in removals.into_iter().rev() {
+			let value = self.0.remove(i);
+			if let Some(to) = to {
+				moves.push((to, value));
+			}
+		}
+		moves.sort_by_key(|&(to, _)| to);
+		for (to, value) in moves {
+			let to = to.min(self.0.len());
+			self.0.insert(to, value);
+		}
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(Value::from_desc).collect::<Result<_, _>>().map(Keyed::new)
+	}
+}
+
+impl<Value: Invert, F: KeyFn<Value>> Invert for Keyed<Vec<Value>, F> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_map_changes::<F::Key, Value>(change)
+	}
+}
+
+impl<Value: Merge, F: KeyFn<Value>> Merge for Keyed<Vec<Value>, F> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_map_changes::<F::Key, Value>(left, right)
+	}
+}
+
+impl<Value: Compose, F: KeyFn<Value>> Compose for Keyed<Vec<Value>, F> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_map_changes::<F::Key, Value>(first, second, true)
+	}
+}
+
+impl<Value: Render, F: KeyFn<Value>> Render for Keyed<Vec<Value>, F> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_map_changes::<F::Key, Value, V>(change, true, path, leaves)
+	}
+}
+
+// The positions at which each key occurs, in order.
+fn index_by_key<Value, F: KeyFn<Value>>(values: &[Value]) -> BTreeMap<F::Key, Vec<usize>> {
+	let mut index: BTreeMap<F::Key, Vec<usize>> = BTreeMap::new();
+	for (i, value) in values.iter().enumerate() {
+		index.entry(F::key(value)).or_default().push(i);
+	}
+	index
+}
+
+// The position of the element with a key, which must not be ambiguous.
+fn unique_index<Key: Ord + Debug>(index: &BTreeMap<Key, Vec<usize>>, key: &Key) -> Result<Option<usize>, PatchError> {
+	match index.get(key).map(Vec::as_slice) {
+		None => Ok(None),
+		Some([i]) => Ok(Some(*i)),
+		Some(_) => Err(PatchError::Mismatch(format!("key {:?} occurs more than once", key))),
+	}
+}
+
+/// Compare two slices as maps from the key of each element, comparing their
+/// elements under the given options; see [`Keyed`] for the shape of the
+/// resulting changes.
+pub fn keyed_comparison<Value: Comparable, F: KeyFn<Value>>(
+	this: &[Value],
+	other: &[Value],
+	options: &CompareOptions,
+) -> Changed<<Keyed<Vec<Value>, F> as Comparable>::Change> {
+	let this_index = index_by_key::<Value, F>(this);
+	let other_index = index_by_key::<Value, F>(other);
+	let count = |index: &BTreeMap<F::Key, Vec<usize>>, key: &F::Key| index.get(key).map_or(0, Vec::len);
+
+	let mut changes = Vec::new();
+	for (key, js) in &other_index {
+		let is = this_index.get(key).map_or(&[][..], Vec::as_slice);
+		for (n, &j) in js.iter().enumerate() {
+			match is.get(n) {
+				Some(&i) => {
+					if let Changed::Changed(change) = options.compare_at(PathSegment::Element(key), &this[i], &other[j])
+					{
+						changes.push(MapChange::Changed(key.clone(), change));
+					}
+				}
+				None if options.ignores(PathSegment::Element(key)) => (),
+				None => changes.push(MapChange::Added(key.clone(), other[j].describe())),
+			}
+		}
+	}
+	for (key, is) in &this_index {
+		if !options.ignores(PathSegment::Element(key)) {
+			for _ in count(&other_index, key)..is.len() {
+				changes.push(MapChange::Removed(key.clone()));
+			}
+		}
+	}
+
+	if F::REPORT_MOVES {
+		// Of the elements present on both sides, those that keep their order
+		// relative to one another stayed put; everything else moved. The
+		// occurrences of a key are told apart by their number.
+		let survivors = |values: &[Value], index: &BTreeMap<F::Key, Vec<usize>>| -> Vec<(F::Key, usize, usize)> {
+			let mut seen: BTreeMap<F::Key, usize> = BTreeMap::new();
+			let mut survivors = Vec::new();
+			for (i, value) in values.iter().enumerate() {
+				let key = F::key(value);
+				let n = seen.entry(key.clone()).or_default();
+				if *n < count(index, &key) {
+					survivors.push((key, *n, i));
+				}
+				*n += 1;
+			}
+			survivors
+		};
+		let before = survivors(this, &other_index);
+		let after = survivors(other, &this_index);
+		let same = |i: usize, j: usize| before[i].0 == after[j].0 && before[i].1 == after[j].1;
+		for edit in edit_script(before.len(), after.len(), same) {
+			if let Edit::Delete(i) = edit {
+				let (key, n, from) = before[i].clone();
+				if options.ignores(PathSegment::Element(&key)) {
+					continue;
+				}
+				let to = other_index[&key][n];
+				changes.push(MapChange::Moved { key, from, to });
+			}
+		}
+	}
+
+	if changes.is_empty() {
+		Changed::Unchanged
+	} else {
+		Changed::Changed(changes)
+	}
+}
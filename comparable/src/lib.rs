@@ -19,7 +19,10 @@
 //! returned by `foo.comparison(&bar)`. This function uses the
 //! [`pretty_assertions`](https://crates.io/crates/pretty_assertions) crate under
 //! the hood so that minute differences within deep structures can be easily seen
-//! in the failure output.
+//! in the failure output. For types implementing [`Render`],
+//! [`assert_changes_rendered!`] reports a failure change by change instead,
+//! giving the path to each part of the value that changed and listing the
+//! expected changes that are missing and the actual ones that were not expected.
 //!
 //! # Quickstart
 //!
@@ -147,16 +150,193 @@
 //! );
 //! ```
 //!
+//! For counters and other numbers whose changes matter more than their values,
+//! wrap them in `Delta` for their changes to be the difference between the old and
+//! the new value: an `IntDelta` for integers, which holds the difference between
+//! any two integers of a type, or an `f64` for floating-point numbers. The
+//! `assert_delta!` macro checks this difference against a plain number, within a
+//! few units in the last place for floating-point numbers, since their differences
+//! are rounded:
+//!
+//! ```
+//! # use comparable::*;
+//! assert_changes!(Delta(100u32), Delta(103u32), Changed::Changed(IntDelta::from(3)));
+//! assert_delta!(&100u32, &103u32, 3);
+//! assert_delta!(&1.5f64, &1.0f64, -0.5);
+//! assert_delta!(&0.1f64, &0.3f64, 0.2);
+//! ```
+//!
+//! `Duration`, `SystemTime` and `Instant` report their changes as a
+//! `DurationChange`, `SystemTimeChange` or `InstantChange`, which hold the old and
+//! the new value and the `TimeDelta` between them, a `Duration` with a sign. The
+//! `time_tolerance` of the comparison options lets times that are close enough
+//! count as unchanged:
+//!
+//! ```
+//! # use comparable::*;
+//! # use std::time::Duration;
+//! let ms = Duration::from_millis;
+//! assert_changes!(&ms(100), &ms(97), Changed::Changed(DurationChange(ms(100), ms(97), TimeDelta::negative(ms(3)))));
+//!
+//! let within_5ms = CompareOptions { time_tolerance: ms(5), ..CompareOptions::default() };
+//! assert_eq!(ms(100).comparison_with(&ms(97), &within_5ms), Changed::Unchanged);
+//! ```
+//!
+//! The `NonZero` integer types, `Ipv4Addr`, `Ipv6Addr` and `OsString` are scalars
+//! too, with changes such as `NonZeroU32Change` and `Ipv4AddrChange`. An `IpAddr`
+//! reports an `IpAddrChange`, which is `BothV4` or `BothV6` when the address
+//! changed within its family, and `Different` when the family changed. A
+//! `SocketAddr` is compared as if it were a struct, with the fields `ip`, `port`,
+//! `flowinfo` and `scope_id`:
+//!
+//! ```
+//! # use comparable::*;
+//! # use std::net::SocketAddr;
+//! let old: SocketAddr = "10.0.0.1:80".parse().unwrap();
+//! let new: SocketAddr = "[::1]:80".parse().unwrap();
+//! assert_eq!(
+//!     render::<SocketAddr>(&old.comparison(&new)).to_string(),
+//!     ".ip: 10.0.0.1 -> ::1",
+//! );
+//! ```
+//!
+//! A `Range` or `RangeInclusive` is compared in the same way, as a struct with the
+//! fields `start` and `end`, and reports a list of `RangeChange::Start` and
+//! `RangeChange::End`. An `Ordering` is described by its value as a number, -1, 0
+//! or 1, and reports an `OrderingChange`. A `Wrapping` number is compared as the
+//! number it wraps, and a `PhantomData`, like `std::iter::Empty`, never changes:
+//!
+//! ```
+//! # use comparable::*;
+//! # use std::ops::Range;
+//! assert_eq!(render::<Range<u32>>(&(2u32..8).comparison(&(2..10))).to_string(), ".end: 8 -> 10");
+//! ```
+//!
+//! A long string that changes in one place is better compared line by line. Wrap
+//! it in `Lines` for its changes to be a list of `LineHunk`s, each of which holds
+//! the lines removed and added at some line number, and which render as the hunks
+//! of a unified diff:
+//!
+//! ```
+//! # use comparable::*;
+//! let old: String = (1..=500).map(|i| format!("line {}\n", i)).collect();
+//! let new = old.replace("line 250\n", "line 250, edited\n");
+//! assert_eq!(
+//!     render::<Lines<String>>(&Lines(old).comparison(&Lines(new))).to_string(),
+//!     "@@ -250 +250 @@\n-line 250\n+line 250, edited",
+//! );
+//! ```
+//!
+//! For short strings such as names and identifiers, wrap them in `Graphemes` or
+//! `Words` instead, for their changes to be a list of `StringSpan`s: runs of
+//! graphemes or words that were kept, deleted or inserted. These render with the
+//! deleted text marked by `[-` and `-]`, and the inserted text by `{+` and `+}`:
+//!
+//! ```
+//! # use comparable::*;
+//! let old = Words("user_id = 10".to_string());
+//! let new = Words("user_name = 10".to_string());
+//! assert_changes!(
+//!     &old,
+//!     &new,
+//!     Changed::Changed(vec![
+//!         StringSpan::Delete("user_id".to_string()),
+//!         StringSpan::Insert("user_name".to_string()),
+//!         StringSpan::Equal(" = 10".to_string()),
+//!     ]),
+//! );
+//! assert_eq!(render::<Words<String>>(&old.comparison(&new)).to_string(), "[-user_id-]{+user_name+} = 10");
+//! ```
+//!
+//! # Option and Result
+//!
+//! An `Option` reports an `OptionChange`: `BothSome` with the change to the value
+//! when both sides are `Some`, or `Different` with the descriptions of both sides
+//! when one of them is `None`. A `Result` reports a `ResultChange` the same way,
+//! with `BothOk` and `BothErr` for the changes to the values when both sides are
+//! `Ok` or both are `Err`, rendered under the paths `::Ok` and `::Err`:
+//!
+//! ```
+//! # use comparable::*;
+//! let old: Result<u32, String> = Ok(1);
+//! assert_changes!(&old, &Ok(2), Changed::Changed(ResultChange::BothOk(U32Change(1, 2))));
+//! assert_changes!(&old, &Err("timeout".to_string()), Changed::Changed(ResultChange::Different(Ok(1), Err("timeout".to_string()))));
+//! ```
+//!
+//! # Pointers and cells
+//!
+//! `Box`, `Rc`, `Arc` and `Cow` compare the values they point to, as do
+//! `RefCell`, `Cell`, `Mutex` and `RwLock` with the values they hold, so they
+//! report the same changes as those values would. A change is applied to an `Rc`
+//! or an `Arc` by changing its own copy of a shared value. A `Mutex` or `RwLock`
+//! is locked for the comparison; if the lock is poisoned its value is compared
+//! regardless, but [`Patch::apply`] refuses to change it. A comparison never
+//! waits: it panics if a `RefCell` is mutably borrowed, a `Mutex` is already
+//! locked, or an `RwLock` is locked for writing, rather than report no change
+//! for a value it could not read.
+//!
+//! ```
+//! # use comparable::*;
+//! # use std::cell::RefCell;
+//! # use std::rc::Rc;
+//! #[derive(Comparable)]
+//! struct Counter {
+//!     name: Rc<String>,
+//!     count: RefCell<u32>,
+//! }
+//!
+//! let old = Counter { name: Rc::new("hits".to_string()), count: RefCell::new(1) };
+//! let new = Counter { name: old.name.clone(), count: RefCell::new(2) };
+//! assert_changes!(&old, &new, Changed::Changed(vec![CounterChange::Count(U32Change(1, 2))]));
+//! ```
+//!
 //! # Vec and Set Collections
 //!
 //! The set collections for which [`Comparable`] has been implemented are: `Vec`,
-//! `HashSet`, and `BTreeSet`.
+//! `VecDeque`, `LinkedList`, `BinaryHeap`, `HashSet`, and `BTreeSet`.
 //!
 //! The `Vec` uses `Vec<VecChange>` to report all of the indices at which changes
 //! happened. Note that it cannot detect insertions in the middle, and so will
 //! likely report every item as changed from there until the end of the vector, at
 //! which point it will report an added member.
 //!
+//! If insertions and deletions in the middle matter, wrap the vector in `Aligned`
+//! (or mark the field with `#[comparable_aligned]` when deriving). This aligns the
+//! two vectors using a shortest edit script, so that elements which did not change
+//! are matched up, and only the elements that were really added or removed are
+//! reported. An element that was removed in one place and added in another is
+//! reported as a `VecChange::Moved`, and a removal and an addition at the same
+//! place are reported together as a `VecChange::Changed`. `Removed` and `Changed`
+//! carry the element's index in the original vector, and `Added` its index in the
+//! new vector:
+//!
+//! ```
+//! # use comparable::*;
+//! assert_changes!(
+//!     &Aligned(vec![1 as i32, 2, 3]),
+//!     &Aligned(vec![0 as i32, 1, 2, 3]),
+//!     Changed::Changed(vec![VecChange::Added(0, 0)]),
+//! );
+//! assert_changes!(
+//!     &Aligned(vec![1 as i32, 2, 3, 4]),
+//!     &Aligned(vec![1 as i32, 20, 4]),
+//!     Changed::Changed(vec![
+//!         VecChange::Changed(1, I32Change(2, 20)),
+//!         VecChange::Removed(2, 3),
+//!     ]),
+//! );
+//! assert_changes!(
+//!     &Aligned(vec![1 as i32, 2, 3]),
+//!     &Aligned(vec![3 as i32, 1, 2]),
+//!     Changed::Changed(vec![VecChange::Moved { from: 2, to: 0, change: Changed::Unchanged }]),
+//! );
+//! ```
+//!
+//! By default elements are only matched up when they compare as unchanged, so an
+//! element that was edited as well as moved is reported as removed and added. If
+//! some fields identify an element, mark them with `#[comparable_identity]` (see
+//! below), and a moved element's `VecChange::Moved` will carry its changes too.
+//!
 //! `HashSet` and `BTreeSet` types both report changes the same way, using the
 //! `SetChange` type. Note that in order for `HashSet` change results to be
 //! deterministic, the values in a `HashSet` must support the `Ord` trait so they
@@ -164,6 +344,14 @@
 //! change, and so only report changes in terms of `SetChange::Added` and
 //! `SetChange::Removed`.
 //!
+//! `VecDeque` and `LinkedList` are compared just as a `Vec` is, and report their
+//! changes as a `Vec<VecChange>` too. A `BinaryHeap` is compared as a sorted
+//! multiset: each occurrence of a value that one heap holds more of than the other
+//! is reported as a `SetChange`, whatever order the heap keeps its members in.
+//! Slices, whether `[T]` or `&[T]`, are also compared as a `Vec` is. A field that
+//! borrows a slice can be derived, but since a borrowed slice cannot be changed,
+//! applying a change to one is a `PatchError::Unsupported`.
+//!
 //! Here are a few examples, taken from the `comparable_test` test suite:
 //!
 //! ```
@@ -298,6 +486,434 @@
 //! );
 //! ```
 //!
+//! A vector of entities that each carry a unique key, such as an id, can be
+//! compared like a map as well, by wrapping it in `Keyed` along with a type that
+//! implements `KeyFn` to extract the key of each element (or by marking the field
+//! with `#[comparable_key]` when deriving). Elements are then matched up by key,
+//! wherever they are in the vector, and changes are reported using `MapChange`
+//! just as for a `BTreeMap`. If the key function sets `REPORT_MOVES`, elements
+//! that changed position relative to the others are additionally reported as
+//! `MapChange::Moved`, carrying their index in the original and new vectors. Should
+//! a key occur more than once, its occurrences are matched up by position, and a
+//! change to one of them cannot be applied to a vector in which the key is not
+//! unique:
+//!
+//! ```
+//! # use comparable::*;
+//! struct Id;
+//!
+//! impl KeyFn<(u32, char)> for Id {
+//!     type Key = u32;
+//!     const REPORT_MOVES: bool = true;
+//!     fn key(value: &(u32, char)) -> u32 {
+//!         value.0
+//!     }
+//! }
+//!
+//! let keyed = |v: Vec<(u32, char)>| Keyed::<_, Id>::new(v);
+//! assert_changes!(
+//!     &keyed(vec![(1, 'a'), (2, 'b'), (3, 'c')]),
+//!     &keyed(vec![(3, 'c'), (1, 'a'), (2, 'x')]),
+//!     Changed::Changed(vec![
+//!         MapChange::Changed(2, (Changed::Unchanged, Changed::Changed(CharChange('b', 'x')))),
+//!         MapChange::Moved { key: 3, from: 2, to: 0 },
+//!     ]),
+//! );
+//! ```
+//!
+//! # Matching changes
+//!
+//! [`assert_changes!`] needs the exact change expected, which is a burden when
+//! part of it cannot be known in advance, like a timestamp or a generated
+//! identifier. [`assert_changes_match!`] instead takes a [`Matcher`] for the
+//! change, built from placeholders such as any value, any change, an exact value,
+//! an increase of a number, a string matching a regular expression (with the
+//! `regex` feature), or any predicate:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Comparable)]
+//! struct Job {
+//!     id: String,
+//!     attempts: u32,
+//!     started_at: u64,
+//! }
+//!
+//! let old = Job { id: "job-1".to_string(), attempts: 1, started_at: 1_700_000_000 };
+//! let new = Job { id: "job-1".to_string(), attempts: 2, started_at: 1_700_000_042 };
+//! assert_changes_match!(
+//!     &old,
+//!     &new,
+//!     Matcher::changed(Matcher::elements(vec![
+//!         Matcher::equals(JobChange::Attempts(U32Change(1, 2))),
+//!         Matcher::satisfies(|change| match change {
+//!             JobChange::StartedAt(change) => Matcher::increased().matches(change),
+//!             _ => false,
+//!         }),
+//!     ]))
+//! );
+//! ```
+//!
+//! When the change does not match, the failure shows what the matcher expected
+//! alongside the actual change.
+//!
+//! The changes to structs with several fields, and to collections, are lists, and
+//! often a test cares only about some entries of them. [`assert_changes_include!`]
+//! checks that each of a list of matchers matches a different change, in any
+//! order, and ignores the rest. [`assert_changes_only!`] also checks that nothing
+//! else changed. Their failures list the matchers that matched no change, and
+//! the changes that no matcher matched, separately:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Comparable)]
+//! struct Account {
+//!     balance: i64,
+//!     audit_id: String,
+//!     note: String,
+//! }
+//!
+//! let old = Account { balance: 10, audit_id: "a-1".to_string(), note: String::new() };
+//! let new = Account { balance: 15, audit_id: "a-2".to_string(), note: String::new() };
+//! assert_changes_include!(&old, &new, [Matcher::equals(AccountChange::Balance(I64Change(10, 15)))]);
+//! assert_changes_only!(
+//!     &old,
+//!     &new,
+//!     [
+//!         Matcher::equals(AccountChange::Balance(I64Change(10, 15))),
+//!         Matcher::satisfies(|change| matches!(change, AccountChange::AuditId(_))),
+//!     ]
+//! );
+//! ```
+//!
+//! # Comparison options
+//!
+//! Some choices about how values are compared are better made at run time than
+//! when a type is defined, such as being strict about floating-point numbers in CI
+//! but lenient when running locally. [`Comparable::comparison_with`] takes a
+//! [`CompareOptions`] value, which every type in this crate and every derived type
+//! passes on to the values it is made of. The default options compare exactly as
+//! [`Comparable::comparison`] does, and set the tolerances with which `f32` and
+//! `f64` values, and durations and points in time, are compared:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Comparable)]
+//! struct Sample {
+//!     name: String,
+//!     readings: Vec<Option<f64>>,
+//! }
+//!
+//! let old = Sample { name: "a".to_string(), readings: vec![Some(0.1 + 0.2), None] };
+//! let new = Sample { name: "a".to_string(), readings: vec![Some(0.3), None] };
+//! assert!(!old.comparison(&new).is_unchanged());
+//!
+//! let lenient = CompareOptions {
+//!     float_tolerance: FloatTolerance { rel: 1e-12, ..FloatTolerance::EXACT },
+//!     ..CompareOptions::default()
+//! };
+//! assert_eq!(old.comparison_with(&new, &lenient), Changed::Unchanged);
+//! ```
+//!
+//! Fields marked with `#[comparable_tolerance(...)]` keep their own tolerance
+//! whatever the options say.
+//!
+//! The options also say which parts of a value to leave out of a comparison. This
+//! is like marking fields with `#[comparable_ignore]`, but decided anew for each
+//! comparison, so that one test may ignore a timestamp that another checks. Each
+//! [`PathPattern`] is written as the paths to changes are rendered, with `*`
+//! matching any field name, index or key, and leaves out every change at or
+//! beneath the paths it matches:
+//!
+//! ```
+//! # use comparable::*;
+//! # use std::collections::BTreeMap;
+//! #[derive(Clone, PartialEq, Comparable)]
+//! struct Item {
+//!     name: String,
+//!     etag: String,
+//! }
+//!
+//! #[derive(Clone, Comparable)]
+//! struct Record {
+//!     updated_at: u64,
+//!     items: Vec<Item>,
+//!     attributes: BTreeMap<String, String>,
+//! }
+//!
+//! let old = Record {
+//!     updated_at: 1,
+//!     items: vec![Item { name: "a".to_string(), etag: "x".to_string() }],
+//!     attributes: BTreeMap::from([("trace_id".to_string(), "t1".to_string())]),
+//! };
+//! let mut new = old.clone();
+//! new.updated_at = 2;
+//! new.items[0].etag = "y".to_string();
+//! new.attributes.insert("trace_id".to_string(), "t2".to_string());
+//!
+//! let options = CompareOptions::default()
+//!     .ignoring(".updated_at")
+//!     .ignoring(".items[*].etag")
+//!     .ignoring(r#".attributes["trace_id"]"#);
+//! assert_eq!(old.comparison_with(&new, &options), Changed::Unchanged);
+//! ```
+//!
+//! Setting `pointer_identity` reports an `Rc` or an `Arc` as unchanged when it
+//! points to the same value as the one it is compared with, without comparing the
+//! value, which saves walking large values shared between the two sides.
+//!
+//! # Applying changes: the Patch trait
+//!
+//! Every type that implements [`Comparable`] in this crate also implements
+//! [`Patch`], whose method [`Patch::apply`] applies a change, as returned by
+//! `foo.comparison(&bar)`, to `foo`, turning it into `bar`. Deriving
+//! `Comparable` for a type marked `#[comparable_patch]` derives `Patch` as well.
+//! Changes are checked against the value as they are applied: if the value is not
+//! the one the change was computed from, [`PatchError::Mismatch`] is returned.
+//!
+//! ```
+//! # use comparable::*;
+//! # use std::collections::BTreeMap;
+//! let mut a: BTreeMap<u8, String> = vec![(1, "one".to_string())].into_iter().collect();
+//! let b: BTreeMap<u8, String> = vec![(2, "two".to_string())].into_iter().collect();
+//! if let Changed::Changed(change) = a.comparison(&b) {
+//!     a.apply(change).unwrap();
+//! }
+//! assert_eq!(a, b);
+//! ```
+//!
+//! Applying a change that adds a value, or replaces one with a different enum
+//! variant, requires rebuilding that value from its description, using
+//! [`Patch::from_desc`]. This is not possible for types whose description leaves
+//! out part of the value, such as structs with `#[comparable_ignore]` fields or a
+//! custom `describe_type`, and applying such changes fails with
+//! [`PatchError::Unsupported`]. Since `Keyed` does not record where added
+//! elements belong, they are appended, so the order of the result may differ.
+//!
+//! Changes can also be inverted, which is the basis for undoing them: every type
+//! this crate implements [`Patch`] for also implements [`Invert`], whose function
+//! [`Invert::invert`] turns the change from `foo` to `bar` into the change from
+//! `bar` back to `foo`, without needing either value. Deriving `Comparable` for a
+//! type marked `#[comparable_invert]` derives `Invert` as well.
+//!
+//! ```
+//! # use comparable::*;
+//! let foo = vec![1, 2, 3];
+//! let mut bar = vec![1, 4];
+//! if let Changed::Changed(change) = foo.comparison(&bar) {
+//!     let undo = Vec::<i32>::invert(change).unwrap();
+//!     bar.apply(undo).unwrap();
+//! }
+//! assert_eq!(bar, foo);
+//! ```
+//!
+//! Most changes hold everything needed to invert them, with one exception: the
+//! removal of an entry from a map, which `MapChange::Removed` reports by its key
+//! alone. Inverting such a change fails with [`PatchError::Unsupported`]. To
+//! make map changes invertible, compare the map wrapped in `Reversible` (or mark
+//! the field with `#[comparable_reversible]` when deriving); its changes are
+//! `ReversibleMapChange`s, whose `Removed` variant also carries the description of
+//! the value removed:
+//!
+//! ```
+//! # use comparable::*;
+//! # use std::collections::BTreeMap;
+//! let map = |v: Vec<(u8, char)>| Reversible(v.into_iter().collect::<BTreeMap<_, _>>());
+//! let foo = map(vec![(1, 'a'), (2, 'b')]);
+//! let mut bar = map(vec![(2, 'c')]);
+//! let change = foo.comparison(&bar).unwrap_or_default();
+//! assert_eq!(
+//!     change,
+//!     vec![
+//!         ReversibleMapChange::Changed(2, CharChange('b', 'c')),
+//!         ReversibleMapChange::Removed(1, 'a'),
+//!     ]
+//! );
+//! bar.apply(Reversible::<BTreeMap<u8, char>>::invert(change).unwrap()).unwrap();
+//! assert_eq!(bar, foo);
+//! ```
+//!
+//! # Merging changes
+//!
+//! Two values that were both changed from the same original can be combined with
+//! [`merge`], which performs a three-way merge: the changes made on each side are
+//! merged with [`Merge::merge_changes`] and applied to the original. Changes to
+//! different fields of a struct, different entries of a map, or different members
+//! of a set are combined; changes to the same part are merged in turn, and where
+//! they cannot be (for example, the same scalar set to two different values) the
+//! merge fails with [`MergeError::Conflicts`] holding the [`Conflicts`] found.
+//! Each [`Conflict`] holds the change from each side, limited to the part of the
+//! value in conflict. If the merged change cannot be applied to the original, the
+//! merge fails with [`MergeError::Patch`] instead. Deriving
+//! `Comparable` for a type marked `#[comparable_merge]` derives `Merge` as well;
+//! [`merge`] also needs `#[comparable_patch]`, to apply the merged change.
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Clone, PartialEq, Debug, Comparable)]
+//! #[comparable_patch]
+//! #[comparable_merge]
+//! struct Window {
+//!     title: String,
+//!     width: u32,
+//!     height: u32,
+//! }
+//!
+//! let base = Window { title: "main".to_string(), width: 80, height: 24 };
+//! let left = Window { width: 100, ..base.clone() };
+//! let right = Window { height: 40, ..base.clone() };
+//! assert_eq!(merge(&base, &left, &right), Ok(Window { width: 100, height: 40, ..base.clone() }));
+//!
+//! let narrow = Window { width: 60, ..base.clone() };
+//! assert_eq!(
+//!     merge(&base, &left, &narrow),
+//!     Err(MergeError::Conflicts(Conflicts::between(
+//!         vec![WindowChange::Width(U32Change(80, 100))],
+//!         vec![WindowChange::Width(U32Change(80, 60))],
+//!     )))
+//! );
+//! ```
+//!
+//! The elements of a `Vec` changed in place on each side are merged by index, as
+//! long as at most one side adds, removes or moves elements; otherwise those
+//! changes to its structure conflict as a whole, unless they are the same.
+//!
+//! # Composing changes
+//!
+//! Two changes made one after the other — from a value to a second one, and from
+//! that to a third — can be composed with [`Compose::compose`] into the single
+//! change from the first value to the third. Scalars changed twice become one
+//! change from the original value to the final one, an element added and then
+//! removed leaves no trace, and a change that is undone by the next composes to
+//! `Changed::Unchanged`. [`compose_all`] composes a whole series of changes, such
+//! as those between successive snapshots of a value. Deriving `Comparable` for a
+//! type marked `#[comparable_compose]` derives `Compose` as well, along with
+//! `Patch` and `Invert`.
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Clone, PartialEq, Debug, Comparable)]
+//! #[comparable_compose]
+//! struct Window {
+//!     title: String,
+//!     width: u32,
+//! }
+//!
+//! let first = Window { title: "main".to_string(), width: 80 };
+//! let second = Window { title: "main".to_string(), width: 100 };
+//! let third = Window { title: "other".to_string(), width: 120 };
+//! assert_eq!(
+//!     compose_changed::<Window>(first.comparison(&second), second.comparison(&third)),
+//!     Ok(Changed::Changed(vec![
+//!         WindowChange::Title(StringChange("main".to_string(), "other".to_string())),
+//!         WindowChange::Width(U32Change(80, 120)),
+//!     ]))
+//! );
+//! assert_eq!(
+//!     compose_changed::<Window>(first.comparison(&second), second.comparison(&first)),
+//!     Ok(Changed::Unchanged)
+//! );
+//! ```
+//!
+//! Composing fails with `PatchError::Mismatch` if the second change does not
+//! start where the first one ends. Changes that replace a value outright, like
+//! `OptionChange::Different`, are composed by rebuilding the values involved from
+//! their descriptions, which fails with `PatchError::Unsupported` for types that
+//! cannot be rebuilt. For the same reason, an element of a `#[comparable_key]`
+//! vector cannot be removed and then added back, since that would need its old
+//! value.
+//!
+//! # Rendering changes
+//!
+//! The `Debug` output of a change to a nested structure is a deep tree of change
+//! types, which is hard to read. [`render`] flattens a change into [`Leaf`]s
+//! instead, one for each part of the value that changed, with the path to that
+//! part and what happened to it; displayed, each leaf takes one line. Struct
+//! fields and tuple elements show up in paths as `.name` and `.0`, the variant
+//! of an enum whose fields changed as `::Variant`, and the indices and keys of
+//! collections as `[3]` and `["k"]`; like `serde`, the sole field of a tuple
+//! struct or variant is taken to be the value itself, and has no segment of its
+//! own. Values are rendered with `Debug`. Deriving `Comparable` for a type
+//! marked `#[comparable_render]` derives [`Render`] as well, which is what
+//! supplies the field names.
+//!
+//! ```
+//! # use comparable::*;
+//! # use std::collections::BTreeMap;
+//! #[derive(Comparable)]
+//! #[comparable_render]
+//! struct Inner {
+//!     flags: Vec<bool>,
+//! }
+//!
+//! #[derive(Comparable)]
+//! #[comparable_render]
+//! struct Outer {
+//!     inner: Inner,
+//!     attributes: BTreeMap<String, String>,
+//! }
+//!
+//! let before = Outer { inner: Inner { flags: vec![true, true] }, attributes: BTreeMap::new() };
+//! let mut after = Outer { inner: Inner { flags: vec![true, false] }, attributes: BTreeMap::new() };
+//! after.attributes.insert("k".to_string(), "v".to_string());
+//! assert_eq!(
+//!     render::<Outer>(&before.comparison(&after)).to_string(),
+//!     ".inner.flags[1]: true -> false\n.attributes[\"k\"]: added \"v\""
+//! );
+//! ```
+//!
+//! # JSON Patch
+//!
+//! With the `serde` feature, `json_patch` writes a change as an
+//! [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902) JSON Patch document: a
+//! list of `add`, `remove`, `replace` and `move` operations that turn the JSON
+//! serialization of the first value compared into that of the second, for tools
+//! and services that speak JSON rather than Rust. The paths of the operations
+//! are those that [`render`] finds, written as JSON Pointers: struct fields and
+//! enum variants by name, as `serde` serializes them by default, elements of
+//! sequences by index, and entries of maps by key.
+//!
+//! ```
+//! # #[cfg(feature = "serde")]
+//! # {
+//! # use comparable::*;
+//! # use serde_json::json;
+//! #[derive(serde::Serialize, Comparable)]
+//! #[comparable_render]
+//! struct Config {
+//!     name: String,
+//!     ports: Vec<u16>,
+//! }
+//!
+//! let before = Config { name: "web".to_string(), ports: vec![80, 443] };
+//! let after = Config { name: "api".to_string(), ports: vec![443] };
+//! assert_eq!(
+//!     json_patch::<Config>(&before.comparison(&after)),
+//!     Ok(json!([
+//!         { "op": "replace", "path": "/name", "value": "api" },
+//!         { "op": "replace", "path": "/ports/0", "value": 443 },
+//!         { "op": "remove", "path": "/ports/1" },
+//!     ]))
+//! );
+//! # }
+//! ```
+//!
+//! JSON Patch applies its operations one after another, so the operations for a
+//! sequence are ordered, and their indices adjusted, to match: elements are
+//! changed in place first, then removed from the back, and then added or moved
+//! into place from the front. Some changes cannot be pointed at, and are reported
+//! as a `JsonPatchError`: the removal of a member of a set, which has no index
+//! in the JSON array it serializes to, and changes to the elements of a
+//! `#[comparable_key]` vector, which are known only by their keys.
+//!
+//! With the `json_merge_patch` feature, `json_merge_patch` writes a change as
+//! an [RFC 7386](https://www.rfc-editor.org/rfc/rfc7386) JSON Merge Patch
+//! instead, which is what many services accept for partial updates. Changed
+//! fields, variants and map entries become members of nested objects, and
+//! removed entries become `null`. A merge patch can only replace an array as a
+//! whole, so changes to the elements of sequences and sets are reported as
+//! errors.
+//!
 //! # <a name="structs"></a>Structures
 //!
 //! Differencing arbitrary structures was the original motive for creating
@@ -400,6 +1016,223 @@
 //! the original field (`&Self`), and yields a value of some type for which
 //! [`Comparable`] has been implemented or derived.
 //!
+//! ## Field attribute: `comparable_aligned`
+//!
+//! The `#[comparable_aligned]` attribute compares a `Vec` field as though it were
+//! wrapped in `Aligned`, so that insertions and deletions in the middle of the
+//! vector are reported as such, rather than as a change to every element that
+//! follows them. The field's `Change` type is the same as the `Vec`'s own.
+//!
+//! ```
+//! # use comparable_derive::*;
+//! #[derive(Comparable)]
+//! struct Schedule {
+//!     #[comparable_aligned]
+//!     jobs: Vec<String>,
+//! }
+//! ```
+//!
+//! ## Field attribute: `comparable_delta`
+//!
+//! The `#[comparable_delta]` attribute compares a numeric field as though it were
+//! wrapped in `Delta`, reporting how much it changed by. Changes made to such a
+//! field by two sides merge into their sum:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Clone, PartialEq, Debug, Comparable)]
+//! #[comparable_patch]
+//! #[comparable_merge]
+//! #[comparable_render]
+//! struct Stats {
+//!     #[comparable_delta]
+//!     requests: u64,
+//! }
+//!
+//! let stats = |requests| Stats { requests };
+//! assert_eq!(render::<Stats>(&stats(100).comparison(&stats(103))).to_string(), ".requests: +3");
+//! assert_eq!(merge(&stats(100), &stats(103), &stats(110)), Ok(stats(113)));
+//! ```
+//!
+//! ## Field attributes: `comparable_graphemes` and `comparable_words`
+//!
+//! The `#[comparable_graphemes]` and `#[comparable_words]` attributes compare a
+//! `String` field as though it were wrapped in `Graphemes` or `Words`, reporting
+//! the spans of text that changed rather than the whole of the old and the new
+//! string:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Comparable)]
+//! #[comparable_render]
+//! struct Person {
+//!     #[comparable_graphemes]
+//!     name: String,
+//! }
+//!
+//! assert_eq!(
+//!     render::<Person>(&Person { name: "Jon".to_string() }
+//!         .comparison(&Person { name: "John".to_string() }))
+//!     .to_string(),
+//!     ".name: Jo{+h+}n",
+//! );
+//! ```
+//!
+//! ## Field attribute: `comparable_identity`
+//!
+//! Fields marked with `#[comparable_identity]` make up the identity of a struct:
+//! two values whose identity fields compare as unchanged are taken to be the same
+//! entity, even when their other fields differ. This is what
+//! [`Comparable::same_identity`] reports, and it is how aligned comparisons follow
+//! an element that was edited, moved, or both:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Comparable)]
+//! struct Job {
+//!     #[comparable_identity]
+//!     id: u32,
+//!     priority: u8,
+//! }
+//!
+//! let job = |id, priority| Job { id, priority };
+//! assert_changes!(
+//!     &Aligned(vec![job(1, 0), job(2, 0), job(3, 0)]),
+//!     &Aligned(vec![job(3, 5), job(1, 0), job(2, 0)]),
+//!     Changed::Changed(vec![VecChange::Moved {
+//!         from: 2,
+//!         to: 0,
+//!         change: Changed::Changed(vec![JobChange::Priority(U8Change(0, 5))]),
+//!     }]),
+//! );
+//! ```
+//!
+//! ## Field attribute: `comparable_key`
+//!
+//! The `#[comparable_key(...)]` attribute compares a `Vec` field as though it were
+//! wrapped in `Keyed`, using the given closure to extract the key of each element.
+//! The closure must declare its return type, which becomes the key type of the
+//! resulting `MapChange`s and cannot be inferred, so write `|u| -> u64 { u.id }`
+//! rather than `|u| u.id`. Follow the closure with `moves` to also report elements
+//! that were reordered:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Clone, PartialEq, Comparable)]
+//! struct User {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! #[derive(Comparable)]
+//! struct Team {
+//!     #[comparable_key(|u| -> u64 { u.id }, moves)]
+//!     members: Vec<User>,
+//! }
+//!
+//! let user = |id, name: &str| User { id, name: name.to_string() };
+//! assert_changes!(
+//!     &Team { members: vec![user(1, "Alice"), user(2, "Bob")] },
+//!     &Team { members: vec![user(3, "Carol"), user(1, "Alice")] },
+//!     Changed::Changed(TeamChange {
+//!         members: vec![
+//!             MapChange::Added(3, UserDesc { id: 3, name: "Carol".to_string() }),
+//!             MapChange::Removed(2),
+//!         ],
+//!     }),
+//! );
+//! ```
+//!
+//! Key functions are generated as types named after the field, such as
+//! `TeamMembersKey` above. For that reason the attribute is not supported on the
+//! fields of generic types; wrap such a field in `Keyed` instead, with a type that
+//! implements `KeyFn` for the element type.
+//!
+//! ## Field attribute: `comparable_lines`
+//!
+//! The `#[comparable_lines]` attribute compares a `String` field as though it were
+//! wrapped in `Lines`, reporting the lines that changed rather than the whole of
+//! the old and the new string:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Comparable)]
+//! #[comparable_render]
+//! struct Page {
+//!     #[comparable_lines]
+//!     body: String,
+//! }
+//!
+//! assert_eq!(
+//!     render::<Page>(&Page { body: "a\nb\nc\n".to_string() }
+//!         .comparison(&Page { body: "a\nc\n".to_string() }))
+//!     .to_string(),
+//!     ".body: @@ -2 +1,0 @@\n-b",
+//! );
+//! ```
+//!
+//! ## Field attribute: `comparable_reversible`
+//!
+//! The `#[comparable_reversible]` attribute compares a `BTreeMap` or `HashMap`
+//! field as though it were wrapped in `Reversible`, so that its changes record
+//! the value of every entry removed, and can be inverted (see the section on
+//! [`Invert`] above):
+//!
+//! ```
+//! # use comparable::*;
+//! # use std::collections::BTreeMap;
+//! #[derive(Comparable)]
+//! struct Settings {
+//!     #[comparable_reversible]
+//!     values: BTreeMap<String, u32>,
+//! }
+//!
+//! let settings = |v: Vec<(&str, u32)>| Settings {
+//!     values: v.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+//! };
+//! assert_changes!(
+//!     &settings(vec![("width", 80), ("height", 24)]),
+//!     &settings(vec![("width", 100)]),
+//!     Changed::Changed(SettingsChange {
+//!         values: vec![
+//!             ReversibleMapChange::Changed("width".to_string(), U32Change(80, 100)),
+//!             ReversibleMapChange::Removed("height".to_string(), 24),
+//!         ],
+//!     }),
+//! );
+//! ```
+//!
+//! ## Field attribute: `comparable_tolerance`
+//!
+//! Floating-point fields are normally compared exactly, so that `0.1 + 0.2` differs
+//! from `0.3`, and a NaN always differs from itself. The
+//! `#[comparable_tolerance(...)]` attribute compares an `f32` or `f64` field as
+//! though it were wrapped in `Approx`, treating numbers as unchanged if they are
+//! within any one of the tolerances given: an absolute one, `abs = ...`; a
+//! relative one, `rel = ...`; or a number of units in the last place, `ulps = ...`.
+//! Add `nan_equal` to treat NaN as equal to NaN:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Comparable)]
+//! struct Reading {
+//!     #[comparable_tolerance(abs = 1e-9, nan_equal)]
+//!     value: f64,
+//! }
+//!
+//! assert_changes!(&Reading { value: 0.1 + 0.2 }, &Reading { value: 0.3 }, Changed::Unchanged);
+//! assert_changes!(&Reading { value: f64::NAN }, &Reading { value: f64::NAN }, Changed::Unchanged);
+//! ```
+//!
+//! Like those of `comparable_key`, the tolerances are generated as types named
+//! after the field, such as `ReadingValueTolerance` above.
+//!
+//! The attribute only applies to fields written as `f32` or `f64`; on any other
+//! type, including a type alias for one, or a container of floating-point numbers
+//! such as `Option<f64>` or `Vec<f64>`, deriving fails with an error naming the
+//! attribute. Wrap the numbers in such a field in `Approx` instead, with a
+//! [`Tolerance`] of your own.
+//!
 //! ## Deriving `Comparable` for structs: the `Desc` type
 //!
 //! By default, deriving [`Comparable`] for a structure will create a "mirror" of
@@ -514,6 +1347,35 @@
 //! changes to be reported separately for each field. More on this in the
 //! following section.
 //!
+//! ## Macro attributes: `comparable_patch`, `comparable_invert`, `comparable_merge`, `comparable_compose` and `comparable_render`
+//!
+//! Deriving [`Comparable`] does not by itself derive [`Patch`], [`Invert`],
+//! [`Merge`], [`Compose`] or [`Render`], since these require the type of every
+//! compared field to implement them too, which references and types with a
+//! hand-written implementation of [`Comparable`] alone do not. Mark the type with
+//! `#[comparable_patch]` to derive [`Patch`] as well, with `#[comparable_invert]`
+//! to derive [`Invert`], with `#[comparable_merge]` to derive [`Merge`], with
+//! `#[comparable_compose]` to derive [`Compose`] along with the two it builds on,
+//! and with `#[comparable_render]` to derive [`Render`]:
+//!
+//! ```
+//! # use comparable::*;
+//! #[derive(Clone, PartialEq, Debug, Comparable)]
+//! #[comparable_patch]
+//! #[comparable_invert]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! let a = Point { x: 1, y: 2 };
+//! let mut b = Point { x: 1, y: 3 };
+//! if let Changed::Changed(change) = a.comparison(&b) {
+//!     b.apply(Point::invert(change).unwrap()).unwrap();
+//! }
+//! assert_eq!(a, b);
+//! ```
+//!
 //! ## Macro attribute: `comparable_public` and `comparable_private`
 //!
 //! By default, the auto-generated [`Comparable::Desc`] and [`Comparable::Change`]
@@ -911,18 +1773,61 @@
 //!
 //! Unions cannot derive [`Comparable`] instances at the present time.
 #[doc(hidden)]
+pub mod align;
+#[doc(hidden)]
+pub mod approx;
+#[doc(hidden)]
 pub mod array;
 #[doc(hidden)]
 pub mod boxes;
 #[doc(hidden)]
+pub mod cell;
+#[doc(hidden)]
+pub mod compose;
+#[doc(hidden)]
+pub mod delta;
+#[doc(hidden)]
 pub mod empty;
 #[doc(hidden)]
+pub mod ffi;
+#[doc(hidden)]
+pub mod invert;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub mod json_patch;
+#[doc(hidden)]
+pub mod keyed;
+#[doc(hidden)]
+pub mod lines;
+#[doc(hidden)]
 pub mod map;
 #[doc(hidden)]
+pub mod matcher;
+#[doc(hidden)]
+pub mod merge;
+#[doc(hidden)]
+pub mod net;
+#[doc(hidden)]
 pub mod opt;
 #[doc(hidden)]
+pub mod options;
+#[doc(hidden)]
+pub mod patch;
+#[doc(hidden)]
 pub mod path;
 #[doc(hidden)]
+pub mod queue;
+#[doc(hidden)]
+pub mod range;
+#[doc(hidden)]
+pub mod render;
+#[doc(hidden)]
+pub mod report;
+#[doc(hidden)]
+pub mod result;
+#[doc(hidden)]
+pub mod reversible;
+#[doc(hidden)]
 pub mod scalar;
 #[doc(hidden)]
 pub mod set;
@@ -933,19 +1838,53 @@ pub mod tuple;
 #[doc(hidden)]
 pub mod types;
 
+#[doc(hidden)]
+pub use crate::align::*;
+#[doc(hidden)]
+pub use crate::approx::*;
+#[doc(hidden)]
+pub use crate::ffi::*;
+#[doc(hidden)]
+pub use crate::keyed::*;
+#[doc(hidden)]
+pub use crate::lines::*;
 #[doc(hidden)]
 pub use crate::map::*;
 #[doc(hidden)]
+pub use crate::net::*;
+#[doc(hidden)]
 pub use crate::opt::*;
 #[doc(hidden)]
 pub use crate::path::*;
 #[doc(hidden)]
+pub use crate::range::*;
+#[doc(hidden)]
+pub use crate::result::*;
+#[doc(hidden)]
+pub use crate::reversible::*;
+#[doc(hidden)]
 pub use crate::scalar::*;
 #[doc(hidden)]
 pub use crate::set::*;
 #[doc(hidden)]
 pub use crate::string::*;
 
+pub use crate::compose::{compose_all, compose_changed, compose_replacing, Compose};
+pub use crate::delta::{check_delta, Delta, IntDelta};
+pub use crate::invert::{invert_changed, Invert};
+#[cfg(feature = "json_merge_patch")]
+pub use crate::json_patch::json_merge_patch;
+#[cfg(feature = "serde")]
+pub use crate::json_patch::{json_patch, JsonPatchError};
+pub use crate::matcher::{check_changes_include, check_changes_match, Matcher, ScalarChange};
+pub use crate::merge::{merge, merge_part, Conflict, Conflicts, Merge, MergeError};
+pub use crate::options::{CompareOptions, PathPattern, PathPatternError};
+pub use crate::patch::{Patch, PatchError};
+pub use crate::render::{
+	render, render_at, render_changed, render_replaced, ChangePath, Leaf, LeafChange, LeafValue, PathSegment, Render,
+	Rendered,
+};
+pub use crate::report::{check_changes, ChangeReport};
 pub use crate::types::{Changed, Comparable};
 
 /// Assert that two values of a type have changed in the way described.
@@ -975,6 +1914,23 @@ macro_rules! pretty_assert_changes {
 	}};
 }
 
+/// Assert that two values of a type have changed in the way described,
+/// reporting a failure change by change. Rather than comparing the `Debug`
+/// output of the expected and actual changes, a failure renders both into
+/// their leaves, as [`render`] does, and reports the actual leaves, the
+/// expected leaves that are missing, and the actual leaves that were not
+/// expected; see [`ChangeReport`]. The report is colored when standard error
+/// is a terminal and `NO_COLOR` is not set. This requires the type compared
+/// to implement [`Render`].
+#[macro_export]
+macro_rules! assert_changes_rendered {
+	($left:expr, $right:expr, $expected:expr$(,)?) => {{
+		if let Err(report) = comparable::check_changes(&$left, &$right, &$expected) {
+			panic!("assert_changes_rendered! failed\n{}", report);
+		}
+	}};
+}
+
 /// Assert that two values of a type have changed in the way described,
 /// stopping at the first failure if this occurs in a proptest block.
 #[macro_export]
@@ -1004,6 +1960,127 @@ macro_rules! prop_pretty_assert_changes {
 	}};
 }
 
+/// Assert that two values of a type have changed in the way described,
+/// stopping at the first failure if this occurs in a proptest block. Failures
+/// are reported as by `assert_changes_rendered!`.
+#[macro_export]
+macro_rules! prop_assert_changes_rendered {
+	($left:expr, $right:expr, $expected:expr$(,)?) => {{
+		if let Err(report) = comparable::check_changes(&$left, &$right, &$expected) {
+			return Err(proptest::test_runner::TestCaseError::fail(format!(
+				"prop_assert_changes_rendered! failed\n{}",
+				report
+			)));
+		}
+	}};
+}
+
+/// Assert that the change between two values of a type is matched by a
+/// [`Matcher`], rather than equal to an exact change, so that the parts of it
+/// that are not of interest can be left out.
+#[macro_export]
+macro_rules! assert_changes_match {
+	($left:expr, $right:expr, $matcher:expr$(,)?) => {{
+		if let Err(report) = comparable::check_changes_match(&$left, &$right, &$matcher) {
+			panic!("assert_changes_match! failed: {}", report);
+		}
+	}};
+}
+
+/// Assert that the change between two values of a type is matched by a
+/// [`Matcher`], stopping at the first failure if this occurs in a proptest
+/// block.
+#[macro_export]
+macro_rules! prop_assert_changes_match {
+	($left:expr, $right:expr, $matcher:expr$(,)?) => {{
+		if let Err(report) = comparable::check_changes_match(&$left, &$right, &$matcher) {
+			return Err(proptest::test_runner::TestCaseError::fail(format!(
+				"prop_assert_changes_match! failed: {}",
+				report
+			)));
+		}
+	}};
+}
+
+/// Assert that the changes between two values of a type, such as those to
+/// the fields of a struct or to the elements of a collection, include one
+/// matched by each of the given [`Matcher`]s, in any order, whatever else
+/// changed. A failure lists the matchers that matched no change.
+#[macro_export]
+macro_rules! assert_changes_include {
+	($left:expr, $right:expr, $expected:expr$(,)?) => {{
+		if let Err(report) = comparable::check_changes_include(&$left, &$right, &$expected, false) {
+			panic!("assert_changes_include! failed\n{}", report);
+		}
+	}};
+}
+
+/// Assert that the changes between two values of a type are exactly those
+/// matched by the given [`Matcher`]s, one each, in any order. A failure lists
+/// the matchers that matched no change, and the changes that no matcher
+/// matched, separately.
+#[macro_export]
+macro_rules! assert_changes_only {
+	($left:expr, $right:expr, $expected:expr$(,)?) => {{
+		if let Err(report) = comparable::check_changes_include(&$left, &$right, &$expected, true) {
+			panic!("assert_changes_only! failed\n{}", report);
+		}
+	}};
+}
+
+/// Assert that the changes between two values of a type include those
+/// matched, stopping at the first failure if this occurs in a proptest block.
+#[macro_export]
+macro_rules! prop_assert_changes_include {
+	($left:expr, $right:expr, $expected:expr$(,)?) => {{
+		if let Err(report) = comparable::check_changes_include(&$left, &$right, &$expected, false) {
+			return Err(proptest::test_runner::TestCaseError::fail(format!(
+				"prop_assert_changes_include! failed\n{}",
+				report
+			)));
+		}
+	}};
+}
+
+/// Assert that the changes between two values of a type are exactly those
+/// matched, stopping at the first failure if this occurs in a proptest block.
+#[macro_export]
+macro_rules! prop_assert_changes_only {
+	($left:expr, $right:expr, $expected:expr$(,)?) => {{
+		if let Err(report) = comparable::check_changes_include(&$left, &$right, &$expected, true) {
+			return Err(proptest::test_runner::TestCaseError::fail(format!(
+				"prop_assert_changes_only! failed\n{}",
+				report
+			)));
+		}
+	}};
+}
+
+/// Assert that two numbers differ by the amount given, as computed by
+/// [`Delta`]: an integer of any type for integers, or a floating-point number
+/// for those, with `0` meaning no change. The difference between two
+/// floating-point numbers is rounded, and only needs to match the amount
+/// given within a few units in the last place of the largest number involved.
+#[macro_export]
+macro_rules! assert_delta {
+	($left:expr, $right:expr, $expected:expr$(,)?) => {{
+		if let Err(report) = comparable::check_delta($left, $right, $expected) {
+			panic!("assert_delta! failed: {}", report);
+		}
+	}};
+}
+
+/// Assert that two numbers differ by the amount given, stopping at the first
+/// failure if this occurs in a proptest block.
+#[macro_export]
+macro_rules! prop_assert_delta {
+	($left:expr, $right:expr, $expected:expr$(,)?) => {{
+		if let Err(report) = comparable::check_delta($left, $right, $expected) {
+			return Err(proptest::test_runner::TestCaseError::fail(format!("prop_assert_delta! failed: {}", report)));
+		}
+	}};
+}
+
 // Re-export #[derive(Comparable)].
 //
 // The reason re-exporting is not enabled by default is that disabling it would
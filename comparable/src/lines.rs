@@ -0,0 +1,320 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use crate::align::{edit_script, Edit};
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{Conflict, Conflicts, Merge};
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{push_leaf, ChangePath, Leaf, LeafChange, LeafValue, Render};
+use crate::types::{Changed, Comparable};
+
+/// A string whose changes are computed line by line, as a list of
+/// [`LineHunk`]s, rather than as the pair of the old and the new string that
+/// `String` reports. Rendered, the changes read as a unified diff without
+/// context, so that changing one line of a long document makes for a short
+/// report:
+///
+/// ```
+/// # use comparable::*;
+/// let old = Lines("a\nb\nc\n".to_string());
+/// let new = Lines("a\nB\nc\n".to_string());
+/// assert_eq!(
+///     old.comparison(&new),
+///     Changed::Changed(vec![LineHunk {
+///         old_line: 2,
+///         new_line: 2,
+///         removed: vec!["b\n".to_string()],
+///         added: vec!["B\n".to_string()],
+///     }])
+/// );
+/// assert_eq!(render::<Lines<String>>(&old.comparison(&new)).to_string(), "@@ -2 +2 @@\n-b\n+B");
+/// ```
+///
+/// The same comparison can be selected for a single field of a derived type
+/// with the `#[comparable_lines]` attribute.
+#[repr(transparent)]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Lines<T: ?Sized>(pub T);
+
+impl<T: ?Sized> Lines<T> {
+	/// View a reference to a value as a reference to a `Lines` value.
+	pub fn from_ref(value: &T) -> &Self {
+		// SAFETY: `Lines<T>` is `repr(transparent)` over `T`.
+		unsafe { &*(value as *const T as *const Self) }
+	}
+
+	/// View a mutable reference to a value as a mutable reference to a
+	/// `Lines` value.
+	pub fn from_mut(value: &mut T) -> &mut Self {
+		// SAFETY: `Lines<T>` is `repr(transparent)` over `T`.
+		unsafe { &mut *(value as *mut T as *mut Self) }
+	}
+}
+
+impl<T> From<T> for Lines<T> {
+	fn from(value: T) -> Self {
+		Lines(value)
+	}
+}
+
+impl<T: ?Sized> Deref for Lines<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T: ?Sized> DerefMut for Lines<T> {
+	fn deref_mut(&mut self) -> &mut T {
+		&mut self.0
+	}
+}
+
+/// A run of lines removed from a string, and the lines added in their place,
+/// either of which may be empty. Lines are numbered from 1, and keep their
+/// line terminators; `old_line` and `new_line` are the numbers of the first
+/// line of the run in the old and the new string, or of the line that follows
+/// it if it has no lines on that side.
+///
+/// A hunk displays as in a unified diff, without context lines.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LineHunk {
+	pub old_line: usize,
+	pub new_line: usize,
+	pub removed: Vec<String>,
+	pub added: Vec<String>,
+}
+
+impl fmt::Display for LineHunk {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let range = |line: usize, count: usize| match count {
+			0 => format!("{},0", line - 1),
+			1 => line.to_string(),
+			_ => format!("{},{}", line, count),
+		};
+		write!(f, "@@ -{} +{} @@", range(self.old_line, self.removed.len()), range(self.new_line, self.added.len()))?;
+		let lines = self.removed.iter().map(|line| ('-', line)).chain(self.added.iter().map(|line| ('+', line)));
+		for (sign, line) in lines {
+			match line.strip_suffix('\n') {
+				Some(line) => write!(f, "\n{}{}", sign, line)?,
+				None => write!(f, "\n{}{}\n\\ No newline at end of file", sign, line)?,
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Comparable for Lines<String> {
+	type Desc = String;
+
+	fn describe(&self) -> Self::Desc {
+		self.0.clone()
+	}
+
+	type Change = Vec<LineHunk>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		let (old, new): (Vec<&str>, Vec<&str>) =
+			(self.split_inclusive('\n').collect(), other.split_inclusive('\n').collect());
+		let ops = edit_script(old.len(), new.len(), |i, j| old[i] == new[j]).into_iter().map(|edit| match edit {
+			Edit::Keep(..) => Op::Keep(1),
+			Edit::Delete(i) => Op::Delete(old[i].to_string()),
+			Edit::Insert(j) => Op::Insert(new[j].to_string()),
+		});
+		let hunks = hunks(ops);
+		if hunks.is_empty() {
+			Changed::Unchanged
+		} else {
+			Changed::Changed(hunks)
+		}
+	}
+}
+
+impl Patch for Lines<String> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let mut lines = self.split_inclusive('\n');
+		let mut patched = String::new();
+		for op in ops(change) {
+			match op {
+				Op::Keep(count) => {
+					for _ in 0..count {
+						patched
+							.push_str(lines.next().ok_or_else(|| PatchError::Mismatch("too few lines".to_string()))?);
+					}
+				}
+				Op::Delete(line) => expect_value(&lines.next(), &Some(line.as_str()))?,
+				Op::Insert(line) => patched.push_str(&line),
+			}
+		}
+		lines.for_each(|line| patched.push_str(line));
+		self.0 = patched;
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		Ok(Lines(desc))
+	}
+}
+
+impl Invert for Lines<String> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		Ok(change
+			.into_iter()
+			.map(|LineHunk { old_line, new_line, removed, added }| LineHunk {
+				old_line: new_line,
+				new_line: old_line,
+				removed: added,
+				added: removed,
+			})
+			.collect())
+	}
+}
+
+impl Merge for Lines<String> {
+	/// Hunks are merged as long as they do not touch the same lines, nor
+	/// insert lines at the same place, unless both sides made the same change.
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		let end = |hunk: &LineHunk| hunk.old_line + hunk.removed.len();
+		let mut merged: Vec<LineHunk> = Vec::new();
+		let mut conflicts = Vec::new();
+		let (mut left, mut right) = (left.into_iter().peekable(), right.into_iter().peekable());
+		loop {
+			let hunk = match (left.peek(), right.peek()) {
+				(Some(l), Some(r)) if l == r => {
+					right.next();
+					left.next()
+				}
+				(Some(l), Some(r)) if l.old_line == r.old_line || (l.old_line < end(r) && r.old_line < end(l)) => {
+					let alone = |hunk: LineHunk| vec![LineHunk { new_line: hunk.old_line, ..hunk }];
+					let (l, r) = (left.next().unwrap(), right.next().unwrap());
+					conflicts.push(Conflict { left: alone(l), right: alone(r) });
+					continue;
+				}
+				(Some(l), Some(r)) if r.old_line < l.old_line => right.next(),
+				(Some(_), _) => left.next(),
+				(None, _) => right.next(),
+			};
+			match hunk {
+				Some(hunk) => merged.push(hunk),
+				None => break,
+			}
+		}
+		if !conflicts.is_empty() {
+			return Err(Conflicts(conflicts));
+		}
+		let mut offset = 0isize;
+		for hunk in &mut merged {
+			hunk.new_line = (hunk.old_line as isize + offset) as usize;
+			offset += hunk.added.len() as isize - hunk.removed.len() as isize;
+		}
+		Ok(merged)
+	}
+}
+
+impl Compose for Lines<String> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		// The lines in the middle string are those the first change keeps or
+		// inserts, and the second one keeps or deletes.
+		let mut composed = Vec::new();
+		let (mut first, mut second) = (ops(first), ops(second));
+		let (mut a, mut b) = (first.next(), second.next());
+		loop {
+			match (a.take(), b.take()) {
+				(None, None) => break,
+				(Some(Op::Delete(line)), other) => {
+					composed.push(Op::Delete(line));
+					b = other;
+				}
+				(other, Some(Op::Insert(line))) => {
+					composed.push(Op::Insert(line));
+					a = other;
+				}
+				// Beyond the last hunk of either change, it keeps every line.
+				(None, Some(op)) => composed.push(op),
+				(Some(op), None) => composed.push(op),
+				(Some(Op::Keep(m)), Some(Op::Keep(n))) => {
+					composed.push(Op::Keep(m.min(n)));
+					a = if m > n { Some(Op::Keep(m - n)) } else { None };
+					b = if n > m { Some(Op::Keep(n - m)) } else { None };
+				}
+				(Some(Op::Keep(m)), Some(Op::Delete(line))) => {
+					composed.push(Op::Delete(line));
+					a = if m > 1 { Some(Op::Keep(m - 1)) } else { None };
+				}
+				(Some(Op::Insert(line)), Some(Op::Keep(n))) => {
+					composed.push(Op::Insert(line));
+					b = if n > 1 { Some(Op::Keep(n - 1)) } else { None };
+				}
+				(Some(Op::Insert(inserted)), Some(Op::Delete(deleted))) => expect_value(&deleted, &inserted)?,
+			}
+			a = a.or_else(|| first.next());
+			b = b.or_else(|| second.next());
+		}
+		let hunks: Vec<LineHunk> = hunks(composed).into_iter().filter(|hunk| hunk.removed != hunk.added).collect();
+		Ok(if hunks.is_empty() { Changed::Unchanged } else { Changed::Changed(hunks) })
+	}
+}
+
+impl Render for Lines<String> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		for hunk in change {
+			push_leaf(None, LeafChange::Diff(hunk.to_string()), path, leaves);
+		}
+	}
+}
+
+// A step of an edit script over lines: keep some lines, or delete or insert
+// one.
+enum Op {
+	Keep(usize),
+	Delete(String),
+	Insert(String),
+}
+
+// Spell out hunks as an edit script, leaving out the lines kept after the last
+// hunk.
+fn ops(hunks: Vec<LineHunk>) -> impl Iterator<Item = Op> {
+	let mut line = 1;
+	hunks.into_iter().flat_map(move |hunk| {
+		let kept = hunk.old_line.saturating_sub(line);
+		line = hunk.old_line + hunk.removed.len();
+		Some(Op::Keep(kept))
+			.filter(|_| kept > 0)
+			.into_iter()
+			.chain(hunk.removed.into_iter().map(Op::Delete))
+			.chain(hunk.added.into_iter().map(Op::Insert))
+	})
+}
+
+// Gather the lines deleted and inserted between those kept by an edit script
+// into hunks.
+fn hunks(ops: impl IntoIterator<Item = Op>) -> Vec<LineHunk> {
+	let mut hunks: Vec<LineHunk> = Vec::new();
+	let (mut old_line, mut new_line) = (1, 1);
+	let mut open = false;
+	for op in ops {
+		if !open && !matches!(op, Op::Keep(_)) {
+			hunks.push(LineHunk { old_line, new_line, removed: Vec::new(), added: Vec::new() });
+			open = true;
+		}
+		match op {
+			Op::Keep(count) => {
+				old_line += count;
+				new_line += count;
+				open = false;
+			}
+			Op::Delete(line) => {
+				old_line += 1;
+				hunks.last_mut().unwrap().removed.push(line);
+			}
+			Op::Insert(line) => {
+				new_line += 1;
+				hunks.last_mut().unwrap().added.push(line);
+			}
+		}
+	}
+	hunks
+}
@@ -2,6 +2,12 @@
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 
+use crate::compose::{apply_to_desc, Compose};
+use crate::invert::Invert;
+use crate::merge::{Conflict, Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{Patch, PatchError};
+use crate::render::{push_leaf, render_at, ChangePath, Leaf, LeafChange, LeafValue, PathSegment, Render};
 use crate::types::{Changed, Comparable, MaybeSerde};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -10,6 +16,14 @@ pub enum MapChange<Key, Desc, Change> {
 	Added(Key, Desc),
 	Changed(Key, Change),
 	Removed(Key),
+	/// An element that moved from one index in the original sequence to
+	/// another in the new one. Only reported for sequences compared by key,
+	/// like `Keyed`, and only when asked for.
+	Moved {
+		key: Key,
+		from: usize,
+		to: usize,
+	},
 }
 
 impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Comparable> Comparable for BTreeMap<Key, Value> {
@@ -22,13 +36,21 @@ impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Comparable> Comparable for BT
 	type Change = Vec<MapChange<Key, Value::Desc, Value::Change>>;
 
 	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
 		let mut changes = Vec::new();
 		changes.append(
 			&mut other
 				.iter()
 				.flat_map(|(k, v)| {
 					if let Some(vo) = self.get(k) {
-						vo.comparison(v).map(|changes| MapChange::Changed(k.clone(), changes))
+						options
+							.compare_at(PathSegment::Key(k), vo, v)
+							.map(|changes| MapChange::Changed(k.clone(), changes))
+					} else if options.ignores(PathSegment::Key(k)) {
+						Changed::Unchanged
 					} else {
 						Changed::Changed(MapChange::Added(k.clone(), v.describe()))
 					}
@@ -39,7 +61,7 @@ impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Comparable> Comparable for BT
 			&mut self
 				.keys()
 				.flat_map(|k| {
-					if !other.contains_key(k) {
+					if !other.contains_key(k) && !options.ignores(PathSegment::Key(k)) {
 						Changed::Changed(MapChange::Removed(k.clone()))
 					} else {
 						Changed::Unchanged
@@ -55,7 +77,310 @@ impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Comparable> Comparable for BT
 	}
 }
 
-fn to_btreemap<K: Clone + Ord, V>(map: &HashMap<K, V>) -> BTreeMap<K, &V> {
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Patch> Patch for BTreeMap<Key, Value> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		for change in change {
+			match change {
+				MapChange::Added(key, desc) => {
+					if self.contains_key(&key) {
+						return Err(present_key(&key));
+					}
+					self.insert(key, Value::from_desc(desc)?);
+				}
+				MapChange::Changed(key, change) => {
+					self.get_mut(&key).ok_or_else(|| missing_key(&key))?.apply(change)?
+				}
+				MapChange::Removed(key) => {
+					self.remove(&key).ok_or_else(|| missing_key(&key))?;
+				}
+				MapChange::Moved { .. } => return Err(unordered()),
+			}
+		}
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(|(k, v)| Ok((k, Value::from_desc(v)?))).collect()
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Invert> Invert for BTreeMap<Key, Value> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_map_changes::<Key, Value>(change)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Merge> Merge for BTreeMap<Key, Value> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_map_changes::<Key, Value>(left, right)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Compose> Compose for BTreeMap<Key, Value> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_map_changes::<Key, Value>(first, second, false)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Render> Render for BTreeMap<Key, Value> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_map_changes::<Key, Value, V>(change, false, path, leaves)
+	}
+}
+
+pub(crate) fn missing_key<Key: Debug>(key: &Key) -> PatchError {
+	PatchError::Mismatch(format!("key {:?} is not present", key))
+}
+
+pub(crate) fn present_key<Key: Debug>(key: &Key) -> PatchError {
+	PatchError::Mismatch(format!("key {:?} is already present", key))
+}
+
+fn unordered() -> PatchError {
+	PatchError::Unsupported("maps have no order, so their entries cannot move".to_string())
+}
+
+pub(crate) type MapChanges<Key, Value> =
+	Vec<MapChange<Key, <Value as Comparable>::Desc, <Value as Comparable>::Change>>;
+
+/// Invert a list of `MapChange`s. The removal of an entry cannot be inverted,
+/// since the value removed is not recorded.
+pub(crate) fn invert_map_changes<Key: Debug, Value: Invert>(
+	changes: MapChanges<Key, Value>,
+) -> Result<MapChanges<Key, Value>, PatchError> {
+	changes
+		.into_iter()
+		.map(|change| {
+			Ok(match change {
+				MapChange::Added(key, _) => MapChange::Removed(key),
+				MapChange::Changed(key, change) => MapChange::Changed(key, Value::invert(change)?),
+				MapChange::Removed(key) => {
+					return Err(PatchError::Unsupported(format!(
+						"the value removed at key {:?} is not recorded, so it cannot be restored",
+						key
+					)))
+				}
+				MapChange::Moved { key, from, to } => MapChange::Moved { key, from: to, to: from },
+			})
+		})
+		.collect()
+}
+
+/// Merge two lists of `MapChange`s entry by entry, merging the changes both
+/// sides make to the value of the same entry.
+pub(crate) fn merge_map_changes<Key: Ord + Clone, Value: Merge>(
+	left: MapChanges<Key, Value>,
+	right: MapChanges<Key, Value>,
+) -> Result<MapChanges<Key, Value>, Conflicts<MapChanges<Key, Value>>> {
+	merge_by_key(
+		left,
+		right,
+		|change| map_change_key(change).clone(),
+		|left, right| match (left, right) {
+			(MapChange::Changed(key, left), MapChange::Changed(_, right)) => Value::merge_changes(left, right)
+				.map(|change| MapChange::Changed(key.clone(), change))
+				.map_err(|conflicts| conflicts.map(|change| vec![MapChange::Changed(key.clone(), change)])),
+			(left, right) => Err(Conflicts::between(vec![left], vec![right])),
+		},
+	)
+}
+
+/// Merge two lists of changes to entries identified by a key. The changes to
+/// an entry that only one side touches are kept, and so are those both sides
+/// agree on; if each side makes one change to the same entry, they are merged
+/// with `merge_entry`, and anything else is a conflict.
+pub(crate) fn merge_by_key<Key: Ord, Entry: PartialEq>(
+	left: Vec<Entry>,
+	right: Vec<Entry>,
+	key: impl Fn(&Entry) -> Key,
+	merge_entry: impl Fn(Entry, Entry) -> Result<Entry, Conflicts<Vec<Entry>>>,
+) -> Result<Vec<Entry>, Conflicts<Vec<Entry>>> {
+	let mut merged = Vec::new();
+	let mut conflicts = Vec::new();
+	for (_, (mut left, mut right)) in pair_by_key(left, right, key) {
+		if right.is_empty() || left == right {
+			merged.append(&mut left);
+		} else if left.is_empty() {
+			merged.append(&mut right);
+		} else if left.len() == 1 && right.len() == 1 {
+			match merge_entry(left.remove(0), right.remove(0)) {
+				Ok(entry) => merged.push(entry),
+				Err(found) => conflicts.extend(found.0),
+			}
+		} else {
+			conflicts.push(Conflict { left, right });
+		}
+	}
+
+	if conflicts.is_empty() {
+		Ok(merged)
+	} else {
+		Err(Conflicts(conflicts))
+	}
+}
+
+/// Group two lists of changes to entries identified by a key by that key.
+pub(crate) fn pair_by_key<Key: Ord, Entry>(
+	left: Vec<Entry>,
+	right: Vec<Entry>,
+	key: impl Fn(&Entry) -> Key,
+) -> BTreeMap<Key, (Vec<Entry>, Vec<Entry>)> {
+	let mut entries: BTreeMap<Key, (Vec<Entry>, Vec<Entry>)> = BTreeMap::new();
+	for entry in left {
+		entries.entry(key(&entry)).or_default().0.push(entry);
+	}
+	for entry in right {
+		entries.entry(key(&entry)).or_default().1.push(entry);
+	}
+	entries
+}
+
+// What a list of `MapChange`s does to the value of one entry.
+enum Entry<Desc, Change> {
+	Added(Desc),
+	Changed(Change),
+	Removed,
+	// Removed, and then added back with a new value.
+	Replaced(Desc),
+}
+
+/// Compose two lists of `MapChange`s entry by entry. `keyed` says whether they
+/// are the changes of a sequence compared by key, like `Keyed`, which cannot
+/// record an element removed and then added back. Moves are composed too,
+/// but since their indices refer to positions in the sequence, a move made
+/// only by the second list can only be kept if the first list adds, removes
+/// and moves nothing.
+pub(crate) fn compose_map_changes<Key: Ord + Clone + Debug, Value: Compose>(
+	first: MapChanges<Key, Value>,
+	second: MapChanges<Key, Value>,
+	keyed: bool,
+) -> Result<Changed<MapChanges<Key, Value>>, PatchError> {
+	let reorders = first.iter().any(|change| !matches!(change, MapChange::Changed(..)));
+	let mut changed = Vec::new();
+	let mut removed = Vec::new();
+	let mut added_back = Vec::new();
+	let mut moved = Vec::new();
+	for (key, (first, second)) in pair_by_key(first, second, |change| map_change_key(change).clone()) {
+		let (first, first_move) = entry_changes::<Key, Value>(&key, first)?;
+		let (second, second_move) = entry_changes::<Key, Value>(&key, second)?;
+		let entry = match (first, second) {
+			(None, entry) | (entry, None) => entry,
+			(Some(Entry::Added(desc)), Some(Entry::Changed(change))) => {
+				Some(Entry::Added(apply_to_desc::<Value>(desc, change)?))
+			}
+			(Some(Entry::Replaced(desc)), Some(Entry::Changed(change))) => {
+				Some(Entry::Replaced(apply_to_desc::<Value>(desc, change)?))
+			}
+			(Some(Entry::Changed(first)), Some(Entry::Changed(second))) => {
+				Value::compose(first, second)?.map(Entry::Changed).next()
+			}
+			(Some(Entry::Added(_)), Some(Entry::Removed)) => None,
+			(Some(Entry::Changed(_)), Some(Entry::Removed)) | (Some(Entry::Replaced(_)), Some(Entry::Removed)) => {
+				Some(Entry::Removed)
+			}
+			(Some(Entry::Changed(_)), Some(Entry::Replaced(desc)))
+			| (Some(Entry::Removed), Some(Entry::Added(desc))) => Some(Entry::Replaced(desc)),
+			(Some(Entry::Removed), Some(_)) => return Err(missing_key(&key)),
+			(Some(_), Some(_)) => return Err(present_key(&key)),
+		};
+
+		let moves = match entry {
+			Some(Entry::Added(_)) | Some(Entry::Removed) => None,
+			_ => match (first_move, second_move) {
+				(Some((from, _)), Some((_, to))) => Some((from, to)),
+				(Some(moves), None) => Some(moves),
+				(None, Some(moves)) if !reorders => Some(moves),
+				(None, Some(_)) => {
+					return Err(PatchError::Unsupported(format!(
+						"the original position of key {:?} is not recorded, so its move cannot be composed",
+						key
+					)))
+				}
+				(None, None) => None,
+			},
+		};
+		if let Some((from, to)) = moves {
+			moved.push(MapChange::Moved { key: key.clone(), from, to });
+		}
+
+		match entry {
+			Some(Entry::Added(desc)) => changed.push(MapChange::Added(key, desc)),
+			Some(Entry::Changed(change)) => changed.push(MapChange::Changed(key, change)),
+			Some(Entry::Removed) => removed.push(MapChange::Removed(key)),
+			Some(Entry::Replaced(_)) if keyed => {
+				return Err(PatchError::Unsupported(format!(
+					"the element with key {:?} is removed and added back, which cannot be recorded",
+					key
+				)))
+			}
+			Some(Entry::Replaced(desc)) => {
+				removed.push(MapChange::Removed(key.clone()));
+				added_back.push(MapChange::Added(key, desc));
+			}
+			None => {}
+		}
+	}
+
+	let composed: MapChanges<Key, Value> = changed.into_iter().chain(removed).chain(added_back).chain(moved).collect();
+	Ok(if composed.is_empty() { Changed::Unchanged } else { Changed::Changed(composed) })
+}
+
+// Sum up the changes made to one entry by a list of `MapChange`s, along with
+// any move.
+#[allow(clippy::type_complexity)]
+fn entry_changes<Key: Debug, Value: Comparable>(
+	key: &Key,
+	changes: MapChanges<Key, Value>,
+) -> Result<(Option<Entry<Value::Desc, Value::Change>>, Option<(usize, usize)>), PatchError> {
+	let mut entry = None;
+	let mut moves = None;
+	for change in changes {
+		match (entry.take(), change) {
+			(entry_so_far, MapChange::Moved { from, to, .. }) => {
+				entry = entry_so_far;
+				moves = Some((from, to));
+			}
+			(None, MapChange::Added(_, desc)) => entry = Some(Entry::Added(desc)),
+			(Some(Entry::Removed), MapChange::Added(_, desc)) => entry = Some(Entry::Replaced(desc)),
+			(None, MapChange::Changed(_, change)) => entry = Some(Entry::Changed(change)),
+			(None, MapChange::Removed(_)) => entry = Some(Entry::Removed),
+			(Some(_), _) => {
+				return Err(PatchError::Mismatch(format!("key {:?} is changed more than once", key)));
+			}
+		}
+	}
+	Ok((entry, moves))
+}
+
+/// Render a list of `MapChange`s, reaching each entry through its key: as the
+/// key of a map, or as that of an element of a `#[comparable_key]` vector if
+/// `keyed` is set.
+pub(crate) fn render_map_changes<Key: Debug + MaybeSerde, Value: Render, V: LeafValue>(
+	changes: &MapChanges<Key, Value>,
+	keyed: bool,
+	path: &mut ChangePath<V>,
+	leaves: &mut Vec<Leaf<V>>,
+) {
+	for change in changes {
+		let key = V::of(map_change_key(change));
+		let key = if keyed { PathSegment::Element(key) } else { PathSegment::Key(key) };
+		match change {
+			MapChange::Added(_, desc) => push_leaf(Some(key), LeafChange::Added(V::of(desc)), path, leaves),
+			MapChange::Changed(_, change) => render_at::<Value, _>(change, key, path, leaves),
+			MapChange::Removed(_) => push_leaf(Some(key), LeafChange::Removed(None), path, leaves),
+			MapChange::Moved { from, to, .. } => push_leaf(Some(key), LeafChange::Moved(*from, *to), path, leaves),
+		}
+	}
+}
+
+pub(crate) fn map_change_key<Key, Desc, Change>(change: &MapChange<Key, Desc, Change>) -> &Key {
+	match change {
+		MapChange::Added(key, _) | MapChange::Changed(key, _) | MapChange::Removed(key) => key,
+		MapChange::Moved { key, .. } => key,
+	}
+}
+
+pub(crate) fn to_btreemap<K: Clone + Ord, V>(map: &HashMap<K, V>) -> BTreeMap<K, &V> {
 	map.iter().map(|(k, v)| (k.clone(), v)).collect::<BTreeMap<K, &V>>().into_iter().collect()
 }
 
@@ -69,6 +394,61 @@ impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Comparable> Comparable for Ha
 	type Change = Vec<MapChange<Key, Value::Desc, Value::Change>>;
 
 	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
-		to_btreemap(self).comparison(&to_btreemap(other))
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		to_btreemap(self).comparison_with(&to_btreemap(other), options)
+	}
+}
+
+impl<Key: std::hash::Hash + Ord + Clone + Debug + MaybeSerde, Value: Patch> Patch for HashMap<Key, Value> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		for change in change {
+			match change {
+				MapChange::Added(key, desc) => {
+					if self.contains_key(&key) {
+						return Err(present_key(&key));
+					}
+					self.insert(key, Value::from_desc(desc)?);
+				}
+				MapChange::Changed(key, change) => {
+					self.get_mut(&key).ok_or_else(|| missing_key(&key))?.apply(change)?
+				}
+				MapChange::Removed(key) => {
+					self.remove(&key).ok_or_else(|| missing_key(&key))?;
+				}
+				MapChange::Moved { .. } => return Err(unordered()),
+			}
+		}
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(|(k, v)| Ok((k, Value::from_desc(v)?))).collect()
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Invert> Invert for HashMap<Key, Value> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_map_changes::<Key, Value>(change)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Merge> Merge for HashMap<Key, Value> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_map_changes::<Key, Value>(left, right)
+	}
+}
+
+impl<Key: std::hash::Hash + Ord + Clone + Debug + MaybeSerde, Value: Compose> Compose for HashMap<Key, Value> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_map_changes::<Key, Value>(first, second, false)
+	}
+}
+
+impl<Key: std::hash::Hash + Ord + Clone + Debug + MaybeSerde, Value: Render> Render for HashMap<Key, Value> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_map_changes::<Key, Value, V>(change, false, path, leaves)
 	}
 }
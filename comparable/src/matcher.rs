@@ -0,0 +1,318 @@
+use std::cmp::Ordering;
+use std::ffi::OsString;
+use std::fmt::{self, Debug, Write};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::num::*;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::ffi::OsStringChange;
+use crate::net::{Ipv4AddrChange, Ipv6AddrChange};
+use crate::path::PathBufChange;
+use crate::scalar::*;
+use crate::string::StringChange;
+use crate::types::{Changed, Comparable};
+
+/// A description of the values an actual value may be, used in place of an
+/// exact expected change by `assert_changes_match!`, so that a test need not
+/// spell out the parts of a change it does not care about, such as timestamps
+/// or generated identifiers:
+///
+/// ```
+/// # use comparable::*;
+/// #[derive(Comparable)]
+/// struct Session {
+///     user: String,
+///     token: String,
+///     logins: u64,
+/// }
+///
+/// let old = Session { user: "jo".to_string(), token: "tok-481".to_string(), logins: 3 };
+/// let new = Session { user: "jo".to_string(), token: "tok-907".to_string(), logins: 4 };
+/// assert_changes_match!(
+///     &old,
+///     &new,
+///     Matcher::changed(Matcher::elements(vec![
+///         Matcher::satisfies(|change| matches!(change, SessionChange::Token(_))),
+///         Matcher::equals(SessionChange::Logins(U64Change(3, 4))),
+///     ]))
+/// );
+/// ```
+///
+/// Matchers are built with the functions below, some of which take other
+/// matchers for the parts of a value. A matcher displays as a description of
+/// what it matches, which failures report.
+pub struct Matcher<T: ?Sized> {
+	test: Box<dyn Fn(&T) -> bool>,
+	description: String,
+}
+
+impl<T: ?Sized> Matcher<T> {
+	/// A matcher for the values passing a test, described as given.
+	pub fn new(description: impl Into<String>, test: impl Fn(&T) -> bool + 'static) -> Self {
+		Matcher { test: Box::new(test), description: description.into() }
+	}
+
+	/// Whether a value is one this matcher describes.
+	pub fn matches(&self, actual: &T) -> bool {
+		(self.test)(actual)
+	}
+
+	/// Matches any value at all.
+	pub fn anything() -> Self {
+		Self::new("_", |_| true)
+	}
+
+	/// Matches the values for which `predicate` returns true.
+	pub fn satisfies(predicate: impl Fn(&T) -> bool + 'static) -> Self {
+		Self::new("<predicate>", predicate)
+	}
+}
+
+impl<T: PartialEq + Debug + 'static> Matcher<T> {
+	/// Matches the values equal to `expected`.
+	pub fn equals(expected: T) -> Self {
+		Self::new(format!("{:?}", expected), move |actual| *actual == expected)
+	}
+}
+
+impl<T: 'static> Matcher<Changed<T>> {
+	/// Matches a change matching `change`.
+	pub fn changed(change: Matcher<T>) -> Self {
+		let description = format!("Changed({})", change);
+		Self::new(description, move |actual| match actual {
+			Changed::Changed(actual) => change.matches(actual),
+			Changed::Unchanged => false,
+		})
+	}
+
+	/// Matches any change, but not the lack of one.
+	pub fn any_change() -> Self {
+		Self::new("Changed(_)", |actual| !actual.is_unchanged())
+	}
+
+	/// Matches the lack of a change.
+	pub fn unchanged() -> Self {
+		Self::new("Unchanged", |actual| actual.is_unchanged())
+	}
+}
+
+impl<T: 'static> Matcher<Vec<T>> {
+	/// Matches the lists with as many elements as there are matchers, each
+	/// matching the matcher in the same place.
+	pub fn elements(elements: Vec<Matcher<T>>) -> Self {
+		let description = format!("[{}]", elements.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "));
+		Self::new(description, move |actual| {
+			actual.len() == elements.len()
+				&& actual.iter().zip(&elements).all(|(actual, element)| element.matches(actual))
+		})
+	}
+}
+
+impl<C: ScalarChange + 'static> Matcher<C> {
+	/// Matches the changes whose old value matches `old`.
+	pub fn old_value(old: Matcher<C::Value>) -> Self {
+		let description = format!("a change from {}", old);
+		Self::new(description, move |actual| old.matches(actual.old_value()))
+	}
+
+	/// Matches the changes whose new value matches `new`.
+	pub fn new_value(new: Matcher<C::Value>) -> Self {
+		let description = format!("a change to {}", new);
+		Self::new(description, move |actual| new.matches(actual.new_value()))
+	}
+}
+
+impl<C: ScalarChange + 'static> Matcher<C>
+where
+	C::Value: PartialOrd,
+{
+	/// Matches the changes whose new value is greater than the old one.
+	pub fn increased() -> Self {
+		Self::new("an increase", |actual| actual.new_value() > actual.old_value())
+	}
+
+	/// Matches the changes whose new value is less than the old one.
+	pub fn decreased() -> Self {
+		Self::new("a decrease", |actual| actual.new_value() < actual.old_value())
+	}
+}
+
+#[cfg(feature = "regex")]
+impl<T: AsRef<str> + ?Sized> Matcher<T> {
+	/// Matches the strings in which the regular expression `pattern` finds a
+	/// match. Panics if the pattern is invalid.
+	pub fn regex(pattern: &str) -> Self {
+		let regex = regex::Regex::new(pattern).unwrap_or_else(|err| panic!("invalid regex {:?}: {}", pattern, err));
+		Self::new(format!("/{}/", pattern), move |actual| regex.is_match(actual.as_ref()))
+	}
+}
+
+impl<T: ?Sized> fmt::Display for Matcher<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.description)
+	}
+}
+
+impl<T: ?Sized> Debug for Matcher<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Matcher({})", self.description)
+	}
+}
+
+/// The changes to scalars, which record the old value and the new one.
+pub trait ScalarChange {
+	type Value;
+
+	fn old_value(&self) -> &Self::Value;
+	fn new_value(&self) -> &Self::Value;
+}
+
+macro_rules! impl_scalar_change {
+	($($change:ty: $t:ty),*) => {$(
+		impl ScalarChange for $change {
+			type Value = $t;
+
+			fn old_value(&self) -> &$t {
+				&self.0
+			}
+
+			fn new_value(&self) -> &$t {
+				&self.1
+			}
+		}
+	)*};
+}
+
+impl_scalar_change!(
+	BoolChange: bool,
+	U8Change: u8,
+	I8Change: i8,
+	U16Change: u16,
+	I16Change: i16,
+	U32Change: u32,
+	I32Change: i32,
+	U64Change: u64,
+	I64Change: i64,
+	UsizeChange: usize,
+	IsizeChange: isize,
+	F32Change: f32,
+	F64Change: f64,
+	CharChange: char,
+	OrderingChange: Ordering,
+	StringChange: String,
+	PathBufChange: PathBuf,
+	DurationChange: Duration,
+	SystemTimeChange: SystemTime,
+	InstantChange: InstantDesc,
+	Ipv4AddrChange: Ipv4Addr,
+	Ipv6AddrChange: Ipv6Addr,
+	OsStringChange: OsString,
+	NonZeroU8Change: NonZeroU8,
+	NonZeroI8Change: NonZeroI8,
+	NonZeroU16Change: NonZeroU16,
+	NonZeroI16Change: NonZeroI16,
+	NonZeroU32Change: NonZeroU32,
+	NonZeroI32Change: NonZeroI32,
+	NonZeroU64Change: NonZeroU64,
+	NonZeroI64Change: NonZeroI64,
+	NonZeroU128Change: NonZeroU128,
+	NonZeroI128Change: NonZeroI128,
+	NonZeroUsizeChange: NonZeroUsize,
+	NonZeroIsizeChange: NonZeroIsize
+);
+
+/// Check the change between two values against a matcher, returning a
+/// description of how it differs if it does. Used by `assert_changes_match!`.
+#[doc(hidden)]
+pub fn check_changes_match<T: Comparable + ?Sized>(
+	left: &T,
+	right: &T,
+	matcher: &Matcher<Changed<T::Change>>,
+) -> Result<(), String> {
+	let actual = left.comparison(right);
+	if matcher.matches(&actual) {
+		Ok(())
+	} else {
+		Err(format!("expected changes matching {}, found {:?}", matcher, actual))
+	}
+}
+
+/// Check that each matcher matches a different one of the changes between
+/// two values, in any order, returning a report of the matchers that match
+/// none of them if some do. Unless `only` is false, the changes that no
+/// matcher matches are reported as unexpected too. Used by
+/// `assert_changes_include!` and `assert_changes_only!`.
+#[doc(hidden)]
+pub fn check_changes_include<T, E>(left: &T, right: &T, expected: &[Matcher<E>], only: bool) -> Result<(), String>
+where
+	T: Comparable<Change = Vec<E>> + ?Sized,
+	E: Debug,
+{
+	let actual = left.comparison(right).unwrap_or_default();
+	let matched = match_entries(expected, &actual);
+	let missing: Vec<String> =
+		expected.iter().zip(&matched).filter(|(_, m)| m.is_none()).map(|(m, _)| m.to_string()).collect();
+	let unexpected: Vec<String> = if only {
+		let claimed: Vec<usize> = matched.iter().flatten().copied().collect();
+		(0..actual.len()).filter(|i| !claimed.contains(i)).map(|i| format!("{:?}", actual[i])).collect()
+	} else {
+		Vec::new()
+	};
+	if missing.is_empty() && unexpected.is_empty() {
+		return Ok(());
+	}
+	let mut out = String::new();
+	let mut section = |title: &str, prefix: &str, entries: &[String]| {
+		let _ = writeln!(out, "{}:", title);
+		if entries.is_empty() {
+			let _ = writeln!(out, "    (none)");
+		}
+		for entry in entries {
+			let _ = writeln!(out, "  {} {}", prefix, entry);
+		}
+	};
+	let actual: Vec<String> = actual.iter().map(|entry| format!("{:?}", entry)).collect();
+	section("actual changes", " ", &actual);
+	section("missing changes, expected but not found", "-", &missing);
+	if only {
+		section("unexpected changes, found but not expected", "+", &unexpected);
+	}
+	Err(out)
+}
+
+// For each matcher, the index of a different entry it matches, matching as
+// many matchers as possible, by finding augmenting paths.
+fn match_entries<E>(matchers: &[Matcher<E>], entries: &[E]) -> Vec<Option<usize>> {
+	#[allow(clippy::unnecessary_map_or)] // `Option::is_none_or` needs Rust 1.82
+	fn assign<E>(
+		m: usize,
+		matchers: &[Matcher<E>],
+		entries: &[E],
+		owner: &mut [Option<usize>],
+		seen: &mut [bool],
+	) -> bool {
+		for e in 0..entries.len() {
+			if !seen[e] && matchers[m].matches(&entries[e]) {
+				seen[e] = true;
+				if owner[e].map_or(true, |other| assign(other, matchers, entries, owner, seen)) {
+					owner[e] = Some(m);
+					return true;
+				}
+			}
+		}
+		false
+	}
+
+	let mut owner = vec![None; entries.len()];
+	for m in 0..matchers.len() {
+		assign(m, matchers, entries, &mut owner, &mut vec![false; entries.len()]);
+	}
+	let mut matched = vec![None; matchers.len()];
+	for (e, m) in owner.iter().enumerate() {
+		if let Some(m) = *m {
+			matched[m] = Some(e);
+		}
+	}
+	matched
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+
+use crate::patch::{Patch, PatchError};
+use crate::types::{Changed, Comparable};
+
+/// Two changes made to the same part of a value that cannot both be kept.
+/// Each side is a change to the whole value that only touches the part in
+/// conflict, such as a single field of a struct or a single entry of a map.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug)]
+pub struct Conflict<Change> {
+	pub left: Change,
+	pub right: Change,
+}
+
+/// The conflicts found while merging two changes; see [`merge`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug)]
+pub struct Conflicts<Change>(pub Vec<Conflict<Change>>);
+
+impl<Change> Conflicts<Change> {
+	/// Conflicts between two changes, reported as a single conflict.
+	pub fn between(left: Change, right: Change) -> Self {
+		Conflicts(vec![Conflict { left, right }])
+	}
+
+	/// Turn conflicts between changes to part of a value into conflicts
+	/// between changes to the whole, by wrapping each side with `f`.
+	pub fn map<Outer>(self, f: impl Fn(Change) -> Outer) -> Conflicts<Outer> {
+		Conflicts(
+			self.0.into_iter().map(|Conflict { left, right }| Conflict { left: f(left), right: f(right) }).collect(),
+		)
+	}
+}
+
+impl<Change: fmt::Debug> fmt::Display for Conflicts<Change> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} conflicting change(s)", self.0.len())?;
+		for Conflict { left, right } in &self.0 {
+			write!(f, "\n  left: {:?}\n  right: {:?}", left, right)?;
+		}
+		Ok(())
+	}
+}
+
+impl<Change: fmt::Debug> std::error::Error for Conflicts<Change> {}
+
+/// The reason a three-way [`merge`] failed.
+#[derive(PartialEq, Debug)]
+pub enum MergeError<Change> {
+	/// The two sides changed the same part of the value in different ways.
+	Conflicts(Conflicts<Change>),
+	/// The merged change could not be applied to the base value, which only
+	/// happens if neither could the changes on either side, for example
+	/// because they add a value that cannot be rebuilt from its description
+	/// (see [`Patch::from_desc`]).
+	Patch(PatchError),
+}
+
+impl<Change> From<Conflicts<Change>> for MergeError<Change> {
+	fn from(conflicts: Conflicts<Change>) -> Self {
+		MergeError::Conflicts(conflicts)
+	}
+}
+
+impl<Change> From<PatchError> for MergeError<Change> {
+	fn from(err: PatchError) -> Self {
+		MergeError::Patch(err)
+	}
+}
+
+impl<Change: fmt::Debug> fmt::Display for MergeError<Change> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			MergeError::Conflicts(conflicts) => conflicts.fmt(f),
+			MergeError::Patch(err) => err.fmt(f),
+		}
+	}
+}
+
+impl<Change: fmt::Debug> std::error::Error for MergeError<Change> {}
+
+/// Values whose changes can be merged: given two changes made to the same
+/// value, [`Merge::merge_changes`] combines them into one change holding
+/// both, as long as they do not change the same part of the value in
+/// different ways. This is what [`merge`] uses to perform a three-way merge.
+///
+/// Deriving `Comparable` for a type marked `#[comparable_merge]` also derives
+/// `Merge`, which requires that the type of every compared field implements
+/// `Merge` too. Changes to different fields of
+/// a struct, different entries of a map, or different elements of a set or a
+/// sequence are merged; changes to the same field, entry or element are merged
+/// in turn, down to scalars, which conflict unless both sides changed them
+/// into the same value.
+pub trait Merge: Comparable {
+	/// Merge two changes made to the same value, or report the parts of them
+	/// that conflict.
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>>;
+}
+
+/// Perform a three-way merge: combine the changes made from `base` to `left`
+/// with those made from `base` to `right`, and apply them to `base`.
+///
+/// ```
+/// # use comparable::*;
+/// # use std::collections::BTreeMap;
+/// let size = |width: u32, height: u32| -> BTreeMap<String, u32> {
+///     vec![("width".to_string(), width), ("height".to_string(), height)].into_iter().collect()
+/// };
+/// let (base, left, right) = (size(80, 24), size(100, 24), size(80, 40));
+/// assert_eq!(merge(&base, &left, &right), Ok(size(100, 40)));
+///
+/// assert_eq!(
+///     merge(&base, &left, &size(120, 24)),
+///     Err(MergeError::Conflicts(Conflicts::between(
+///         vec![MapChange::Changed("width".to_string(), U32Change(80, 100))],
+///         vec![MapChange::Changed("width".to_string(), U32Change(80, 120))],
+///     )))
+/// );
+/// ```
+pub fn merge<T: Merge + Patch + Clone>(base: &T, left: &T, right: &T) -> Result<T, MergeError<T::Change>> {
+	let change = match (base.comparison(left), base.comparison(right)) {
+		(Changed::Unchanged, Changed::Unchanged) => return Ok(base.clone()),
+		(Changed::Changed(change), Changed::Unchanged) | (Changed::Unchanged, Changed::Changed(change)) => change,
+		(Changed::Changed(left), Changed::Changed(right)) => T::merge_changes(left, right)?,
+	};
+	let mut merged = base.clone();
+	merged.apply(change)?;
+	Ok(merged)
+}
+
+/// Merge the changes made by each side to one part of a value, such as a field
+/// of a struct, either of which may be `Changed::Unchanged`. Any conflicts are
+/// wrapped with `wrap` into conflicts between changes to the whole value, and
+/// added to `conflicts`.
+pub fn merge_part<T: Merge + ?Sized, Outer>(
+	left: Changed<T::Change>,
+	right: Changed<T::Change>,
+	conflicts: &mut Vec<Conflict<Outer>>,
+	wrap: impl Fn(T::Change) -> Outer,
+) -> Changed<T::Change> {
+	match (left, right) {
+		(Changed::Unchanged, Changed::Unchanged) => Changed::Unchanged,
+		(Changed::Changed(change), Changed::Unchanged) | (Changed::Unchanged, Changed::Changed(change)) => {
+			Changed::Changed(change)
+		}
+		(Changed::Changed(left), Changed::Changed(right)) => match T::merge_changes(left, right) {
+			Ok(change) => Changed::Changed(change),
+			Err(found) => {
+				conflicts.extend(found.map(wrap).0);
+				Changed::Unchanged
+			}
+		},
+	}
+}
+
+/// Merge two changes that cannot be broken down any further: they conflict
+/// unless they are the same.
+pub(crate) fn merge_whole<Change: PartialEq>(left: Change, right: Change) -> Result<Change, Conflicts<Change>> {
+	if left == right {
+		Ok(left)
+	} else {
+		Err(Conflicts::between(left, right))
+	}
+}
+
+impl<T: Merge> Merge for &T {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		T::merge_changes(left, right)
+	}
+}
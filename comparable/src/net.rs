@@ -0,0 +1,334 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+use crate::compose::{compose_changed, compose_replacing, Compose};
+use crate::invert::{invert_changed, Invert};
+use crate::merge::{merge_part, merge_whole, Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{render_at, render_replaced, ChangePath, Leaf, LeafValue, PathSegment, Render};
+use crate::scalar::{U16Change, U32Change};
+use crate::types::{Changed, Comparable};
+
+macro_rules! impl_address {
+	($t:ty, $change:ident) => {
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+		#[derive(PartialEq, Debug)]
+		pub struct $change(pub $t, pub $t);
+
+		impl Comparable for $t {
+			type Desc = $t;
+
+			fn describe(&self) -> Self::Desc {
+				*self
+			}
+
+			type Change = $change;
+
+			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				if self != other {
+					Changed::Changed($change(*self, *other))
+				} else {
+					Changed::Unchanged
+				}
+			}
+		}
+
+		impl Patch for $t {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				let $change(old, new) = change;
+				expect_value(self, &old)?;
+				*self = new;
+				Ok(())
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				Ok(desc)
+			}
+		}
+
+		impl Invert for $t {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				let $change(old, new) = change;
+				Ok($change(new, old))
+			}
+		}
+
+		impl Merge for $t {
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				merge_whole(left, right)
+			}
+		}
+
+		impl Compose for $t {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				let ($change(old, middle), $change(expected, new)) = (first, second);
+				expect_value(&middle, &expected)?;
+				Ok(old.comparison(&new))
+			}
+		}
+
+		impl Render for $t {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				let $change(old, new) = change;
+				render_replaced(old, new, path, leaves);
+			}
+		}
+	};
+}
+
+impl_address!(Ipv4Addr, Ipv4AddrChange);
+impl_address!(Ipv6Addr, Ipv6AddrChange);
+
+/// The change to an `IpAddr`: to the address, if both are of the same family,
+/// or from an address of one family to one of the other.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug)]
+pub enum IpAddrChange {
+	BothV4(Ipv4AddrChange),
+	BothV6(Ipv6AddrChange),
+	Different(IpAddr, IpAddr),
+}
+
+impl Comparable for IpAddr {
+	type Desc = IpAddr;
+
+	fn describe(&self) -> Self::Desc {
+		*self
+	}
+
+	type Change = IpAddrChange;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		match (self, other) {
+			(IpAddr::V4(x), IpAddr::V4(y)) => x.comparison(y).map(IpAddrChange::BothV4),
+			(IpAddr::V6(x), IpAddr::V6(y)) => x.comparison(y).map(IpAddrChange::BothV6),
+			(_, _) => Changed::Changed(IpAddrChange::Different(*self, *other)),
+		}
+	}
+}
+
+impl Patch for IpAddr {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		match (self, change) {
+			(IpAddr::V4(x), IpAddrChange::BothV4(change)) => x.apply(change),
+			(IpAddr::V6(x), IpAddrChange::BothV6(change)) => x.apply(change),
+			(this, IpAddrChange::Different(old, new)) => {
+				expect_value(this, &old)?;
+				*this = new;
+				Ok(())
+			}
+			(this, change) => Err(PatchError::Mismatch(format!("{:?} cannot apply to {:?}", change, this))),
+		}
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		Ok(desc)
+	}
+}
+
+impl Invert for IpAddr {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		match change {
+			IpAddrChange::BothV4(change) => Ipv4Addr::invert(change).map(IpAddrChange::BothV4),
+			IpAddrChange::BothV6(change) => Ipv6Addr::invert(change).map(IpAddrChange::BothV6),
+			IpAddrChange::Different(old, new) => Ok(IpAddrChange::Different(new, old)),
+		}
+	}
+}
+
+impl Merge for IpAddr {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_whole(left, right)
+	}
+}
+
+impl Compose for IpAddr {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		match (first, second) {
+			(IpAddrChange::BothV4(first), IpAddrChange::BothV4(second)) => {
+				Ok(Ipv4Addr::compose(first, second)?.map(IpAddrChange::BothV4))
+			}
+			(IpAddrChange::BothV6(first), IpAddrChange::BothV6(second)) => {
+				Ok(Ipv6Addr::compose(first, second)?.map(IpAddrChange::BothV6))
+			}
+			(first, second) => compose_replacing::<Self>(first, second, |change| match change {
+				IpAddrChange::Different(old, new) => Ok((old, new)),
+				change => Err(change),
+			}),
+		}
+	}
+}
+
+impl Render for IpAddr {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		match change {
+			IpAddrChange::BothV4(change) => Ipv4Addr::render_change(change, path, leaves),
+			IpAddrChange::BothV6(change) => Ipv6Addr::render_change(change, path, leaves),
+			IpAddrChange::Different(old, new) => render_replaced(old, new, path, leaves),
+		}
+	}
+}
+
+/// A change to one part of a `SocketAddr`, reported as a change to the field
+/// of a struct would be. The flow information and scope ID of a V4 address,
+/// which has neither, are taken to be zero.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug)]
+pub enum SocketAddrChange {
+	Ip(IpAddrChange),
+	Port(U16Change),
+	FlowInfo(U32Change),
+	ScopeId(U32Change),
+}
+
+type Parts = (IpAddr, u16, u32, u32);
+
+type PartChanges = (Changed<IpAddrChange>, Changed<U16Change>, Changed<U32Change>, Changed<U32Change>);
+
+fn parts(addr: &SocketAddr) -> Parts {
+	match addr {
+		SocketAddr::V4(addr) => (IpAddr::V4(*addr.ip()), addr.port(), 0, 0),
+		SocketAddr::V6(addr) => (IpAddr::V6(*addr.ip()), addr.port(), addr.flowinfo(), addr.scope_id()),
+	}
+}
+
+fn from_parts((ip, port, flowinfo, scope_id): Parts) -> Result<SocketAddr, PatchError> {
+	match ip {
+		IpAddr::V4(_) if flowinfo != 0 || scope_id != 0 => {
+			Err(PatchError::Mismatch(format!("a V4 address has no flow info or scope ID, found {:?}", ip)))
+		}
+		IpAddr::V4(_) => Ok(SocketAddr::new(ip, port)),
+		IpAddr::V6(ip) => Ok(SocketAddr::V6(SocketAddrV6::new(ip, port, flowinfo, scope_id))),
+	}
+}
+
+fn split(changes: Vec<SocketAddrChange>) -> PartChanges {
+	let mut split = (Changed::Unchanged, Changed::Unchanged, Changed::Unchanged, Changed::Unchanged);
+	for change in changes {
+		match change {
+			SocketAddrChange::Ip(change) => split.0 = Changed::Changed(change),
+			SocketAddrChange::Port(change) => split.1 = Changed::Changed(change),
+			SocketAddrChange::FlowInfo(change) => split.2 = Changed::Changed(change),
+			SocketAddrChange::ScopeId(change) => split.3 = Changed::Changed(change),
+		}
+	}
+	split
+}
+
+fn join((ip, port, flowinfo, scope_id): PartChanges) -> Vec<SocketAddrChange> {
+	let mut changes = Vec::new();
+	changes.extend(ip.map(SocketAddrChange::Ip));
+	changes.extend(port.map(SocketAddrChange::Port));
+	changes.extend(flowinfo.map(SocketAddrChange::FlowInfo));
+	changes.extend(scope_id.map(SocketAddrChange::ScopeId));
+	changes
+}
+
+impl Comparable for SocketAddr {
+	type Desc = SocketAddr;
+
+	fn describe(&self) -> Self::Desc {
+		*self
+	}
+
+	type Change = Vec<SocketAddrChange>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		let (this, other) = (parts(self), parts(other));
+		let changes = join((
+			options.compare_at(PathSegment::Field("ip"), &this.0, &other.0),
+			options.compare_at(PathSegment::Field("port"), &this.1, &other.1),
+			options.compare_at(PathSegment::Field("flowinfo"), &this.2, &other.2),
+			options.compare_at(PathSegment::Field("scope_id"), &this.3, &other.3),
+		));
+		if changes.is_empty() {
+			Changed::Unchanged
+		} else {
+			Changed::Changed(changes)
+		}
+	}
+}
+
+impl Patch for SocketAddr {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let mut parts = parts(self);
+		for change in change {
+			match change {
+				SocketAddrChange::Ip(change) => parts.0.apply(change)?,
+				SocketAddrChange::Port(change) => parts.1.apply(change)?,
+				SocketAddrChange::FlowInfo(change) => parts.2.apply(change)?,
+				SocketAddrChange::ScopeId(change) => parts.3.apply(change)?,
+			}
+		}
+		*self = from_parts(parts)?;
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		Ok(desc)
+	}
+}
+
+impl Invert for SocketAddr {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		let (ip, port, flowinfo, scope_id) = split(change);
+		Ok(join((
+			invert_changed::<IpAddr>(ip)?,
+			invert_changed::<u16>(port)?,
+			invert_changed::<u32>(flowinfo)?,
+			invert_changed::<u32>(scope_id)?,
+		)))
+	}
+}
+
+impl Merge for SocketAddr {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		let (left, right) = (split(left), split(right));
+		let mut conflicts = Vec::new();
+		let merged = join((
+			merge_part::<IpAddr, _>(left.0, right.0, &mut conflicts, |change| vec![SocketAddrChange::Ip(change)]),
+			merge_part::<u16, _>(left.1, right.1, &mut conflicts, |change| vec![SocketAddrChange::Port(change)]),
+			merge_part::<u32, _>(left.2, right.2, &mut conflicts, |change| vec![SocketAddrChange::FlowInfo(change)]),
+			merge_part::<u32, _>(left.3, right.3, &mut conflicts, |change| vec![SocketAddrChange::ScopeId(change)]),
+		));
+		if conflicts.is_empty() {
+			Ok(merged)
+		} else {
+			Err(Conflicts(conflicts))
+		}
+	}
+}
+
+impl Compose for SocketAddr {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		let (first, second) = (split(first), split(second));
+		let composed = join((
+			compose_changed::<IpAddr>(first.0, second.0)?,
+			compose_changed::<u16>(first.1, second.1)?,
+			compose_changed::<u32>(first.2, second.2)?,
+			compose_changed::<u32>(first.3, second.3)?,
+		));
+		Ok(if composed.is_empty() { Changed::Unchanged } else { Changed::Changed(composed) })
+	}
+}
+
+impl Render for SocketAddr {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		for change in change {
+			match change {
+				SocketAddrChange::Ip(change) => render_at::<IpAddr, _>(change, PathSegment::Field("ip"), path, leaves),
+				SocketAddrChange::Port(change) => render_at::<u16, _>(change, PathSegment::Field("port"), path, leaves),
+				SocketAddrChange::FlowInfo(change) => {
+					render_at::<u32, _>(change, PathSegment::Field("flowinfo"), path, leaves)
+				}
+				SocketAddrChange::ScopeId(change) => {
+					render_at::<u32, _>(change, PathSegment::Field("scope_id"), path, leaves)
+				}
+			}
+		}
+	}
+}
@@ -1,5 +1,11 @@
 // use serde;
 
+use crate::compose::{compose_replacing, Compose};
+use crate::invert::Invert;
+use crate::merge::{merge_whole, Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{render_replaced, ChangePath, Leaf, LeafValue, Render};
 use crate::types::{Changed, Comparable};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -19,10 +25,76 @@ impl<T: Comparable> Comparable for Option<T> {
 	type Change = OptionChange<Self::Desc, T::Change>;
 
 	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
 		match (self, other) {
 			(None, None) => Changed::Unchanged,
-			(Some(x), Some(y)) => x.comparison(y).map(OptionChange::BothSome),
+			(Some(x), Some(y)) => x.comparison_with(y, options).map(OptionChange::BothSome),
 			(_, _) => Changed::Changed(OptionChange::Different(self.describe(), other.describe())),
 		}
 	}
 }
+
+impl<T: Patch> Patch for Option<T> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		match (self.as_mut(), change) {
+			(Some(x), OptionChange::BothSome(change)) => x.apply(change),
+			(None, OptionChange::BothSome(_)) => Err(PatchError::Mismatch("expected Some, found None".to_string())),
+			(_, OptionChange::Different(old, new)) => {
+				expect_value(&self.describe(), &old)?;
+				*self = Self::from_desc(new)?;
+				Ok(())
+			}
+		}
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.map(T::from_desc).transpose()
+	}
+}
+
+impl<T: Invert> Invert for Option<T> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		match change {
+			OptionChange::BothSome(change) => T::invert(change).map(OptionChange::BothSome),
+			OptionChange::Different(old, new) => Ok(OptionChange::Different(new, old)),
+		}
+	}
+}
+
+impl<T: Merge> Merge for Option<T> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		match (left, right) {
+			(OptionChange::BothSome(left), OptionChange::BothSome(right)) => T::merge_changes(left, right)
+				.map(OptionChange::BothSome)
+				.map_err(|conflicts| conflicts.map(OptionChange::BothSome)),
+			(left, right) => merge_whole(left, right),
+		}
+	}
+}
+
+impl<T: Compose> Compose for Option<T> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		match (first, second) {
+			(OptionChange::BothSome(first), OptionChange::BothSome(second)) => {
+				Ok(T::compose(first, second)?.map(OptionChange::BothSome))
+			}
+			(first, second) => compose_replacing::<Self>(first, second, |change| match change {
+				OptionChange::Different(old, new) => Ok((old, new)),
+				change => Err(change),
+			}),
+		}
+	}
+}
+
+// The value inside `Some` has no path of its own.
+impl<T: Render> Render for Option<T> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		match change {
+			OptionChange::BothSome(change) => T::render_change(change, path, leaves),
+			OptionChange::Different(old, new) => render_replaced(old, new, path, leaves),
+		}
+	}
+}
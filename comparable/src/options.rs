@@ -0,0 +1,214 @@
+use std::borrow::Cow;
+use std::fmt::{self, Debug};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::approx::FloatTolerance;
+use crate::render::PathSegment;
+use crate::types::{Changed, Comparable};
+
+/// Options that change how values are compared, given to
+/// [`Comparable::comparison_with`](crate::Comparable::comparison_with) and
+/// passed on by each value to the values it is made of, such as the fields of
+/// a derived type or the elements of a collection. The default options
+/// compare values as `comparison` does, so that they can be relaxed at run
+/// time, for example to be lenient when running locally but strict in CI:
+///
+/// ```
+/// # use comparable::*;
+/// #[derive(Comparable)]
+/// struct Reading {
+///     values: Vec<f64>,
+/// }
+///
+/// let (old, new) = (Reading { values: vec![0.1 + 0.2] }, Reading { values: vec![0.3] });
+/// assert!(!old.comparison(&new).is_unchanged());
+///
+/// let lenient = CompareOptions {
+///     float_tolerance: FloatTolerance { abs: 1e-9, ..FloatTolerance::EXACT },
+///     ..CompareOptions::default()
+/// };
+/// assert_eq!(old.comparison_with(&new, &lenient), Changed::Unchanged);
+/// ```
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct CompareOptions {
+	/// The tolerance with which `f32` and `f64` values are compared. Fields
+	/// marked with `#[comparable_tolerance(...)]` use their own instead.
+	pub float_tolerance: FloatTolerance,
+	/// How far apart two `Duration`s, `SystemTime`s or `Instant`s may be and
+	/// still compare as unchanged.
+	pub time_tolerance: Duration,
+	/// The paths, from the value compared, to parts of it whose changes are
+	/// left out of the comparison; see [`PathPattern`].
+	pub ignore: Vec<PathPattern>,
+	/// Whether an `Rc` or an `Arc` pointing to the same value as the one it
+	/// is compared with is unchanged, without comparing the value. This saves
+	/// walking large shared values, but a value that is not equal to itself,
+	/// such as a NaN, is then reported as unchanged.
+	pub pointer_identity: bool,
+}
+
+impl CompareOptions {
+	/// Leave out the changes to the parts of a value matching a pattern, as
+	/// parsed by [`PathPattern::from_str`]. Panics if the pattern is invalid.
+	pub fn ignoring(mut self, pattern: &str) -> Self {
+		self.ignore.push(pattern.parse().unwrap_or_else(|err| panic!("{}", err)));
+		self
+	}
+
+	/// The options with which to compare the part of a value reached through
+	/// `segment`, whose ignore patterns are what remains of those that match
+	/// it.
+	#[doc(hidden)]
+	#[allow(clippy::unnecessary_map_or)] // `Option::is_none_or` needs Rust 1.82
+	pub fn at(&self, segment: PathSegment<&dyn Debug>) -> Cow<'_, CompareOptions> {
+		if self.ignore.is_empty() {
+			return Cow::Borrowed(self);
+		}
+		let key = match &segment {
+			PathSegment::Index(index) => Some(index.to_string()),
+			PathSegment::Key(key) | PathSegment::Element(key) => Some(format!("{:?}", key)),
+			PathSegment::Field(_) | PathSegment::Variant(_) => None,
+		};
+		let ignore = self
+			.ignore
+			.iter()
+			.filter_map(|pattern| {
+				let (first, rest) = pattern.0.split_first()?;
+				let matches = match (first, &segment) {
+					(PatternSegment::Field(name), PathSegment::Field(field)) => {
+						name.as_deref().map_or(true, |n| n == *field)
+					}
+					(PatternSegment::Variant(name), PathSegment::Variant(variant)) => {
+						name.as_deref().map_or(true, |n| n == *variant)
+					}
+					(
+						PatternSegment::Key(text),
+						PathSegment::Index(_) | PathSegment::Key(_) | PathSegment::Element(_),
+					) => text.is_none() || text == &key,
+					_ => false,
+				};
+				matches.then(|| PathPattern(rest.to_vec()))
+			})
+			.collect();
+		Cow::Owned(CompareOptions { ignore, ..*self })
+	}
+
+	/// Whether the changes to the value compared with these options are all
+	/// left out.
+	#[doc(hidden)]
+	pub fn is_ignored(&self) -> bool {
+		self.ignore.iter().any(|pattern| pattern.0.is_empty())
+	}
+
+	/// Whether the changes to the part of a value reached through `segment`
+	/// are all left out.
+	#[doc(hidden)]
+	pub fn ignores(&self, segment: PathSegment<&dyn Debug>) -> bool {
+		!self.ignore.is_empty() && self.at(segment).is_ignored()
+	}
+
+	/// Compare the parts of two values reached through `segment`, leaving out
+	/// the changes that these options ignore.
+	#[doc(hidden)]
+	pub fn compare_at<T: Comparable + ?Sized>(
+		&self,
+		segment: PathSegment<&dyn Debug>,
+		this: &T,
+		other: &T,
+	) -> Changed<T::Change> {
+		let options = self.at(segment);
+		if options.is_ignored() {
+			Changed::Unchanged
+		} else {
+			this.comparison_with(other, &options)
+		}
+	}
+}
+
+/// A pattern matching paths to parts of a value, written as those paths are
+/// rendered (see [`render`](crate::render)): `.name` for a field, `::Name`
+/// for an enum variant, and `[3]` or `["key"]` for an index, or for a key as
+/// it is written with `Debug`. A `*` in place of a name, index or key matches
+/// any of them, so that `.items[*].etag` matches the `etag` field of every
+/// element of `items`.
+///
+/// A change matches a pattern if its path starts with one matching it, so
+/// that ignoring `.meta` ignores every change to `meta` and its parts.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PathPattern(Vec<PatternSegment>);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum PatternSegment {
+	Field(Option<String>),
+	Variant(Option<String>),
+	Key(Option<String>),
+}
+
+/// The reason a [`PathPattern`] could not be parsed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PathPatternError(pub String);
+
+impl fmt::Display for PathPatternError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid path pattern: {}", self.0)
+	}
+}
+
+impl std::error::Error for PathPatternError {}
+
+impl FromStr for PathPattern {
+	type Err = PathPatternError;
+
+	fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+		let error = |reason: &str| PathPatternError(format!("{}: {}", reason, pattern));
+		let wildcard = |text: &str| if text == "*" { None } else { Some(text.to_string()) };
+		let mut segments = Vec::new();
+		let mut rest = pattern;
+		while !rest.is_empty() {
+			if let Some(bracketed) = rest.strip_prefix('[') {
+				// A key may contain `]` inside a quoted string.
+				let (mut quoted, mut escaped) = (false, false);
+				let end = bracketed
+					.char_indices()
+					.find(|&(_, c)| {
+						let end = c == ']' && !quoted;
+						if escaped {
+							escaped = false;
+						} else if c == '\\' {
+							escaped = quoted;
+						} else if c == '"' {
+							quoted = !quoted;
+						}
+						end
+					})
+					.map(|(i, _)| i)
+					.ok_or_else(|| error("unclosed `[`"))?;
+				if end == 0 {
+					return Err(error("empty `[]`"));
+				}
+				segments.push(PatternSegment::Key(wildcard(&bracketed[..end])));
+				rest = &bracketed[end + 1..];
+			} else {
+				let (variant, named) = if let Some(named) = rest.strip_prefix("::") {
+					(true, named)
+				} else if let Some(named) = rest.strip_prefix('.') {
+					(false, named)
+				} else {
+					return Err(error("expected `.`, `::` or `[`"));
+				};
+				let end = named.find(['.', '[', ':']).unwrap_or(named.len());
+				if end == 0 {
+					return Err(error("missing name"));
+				}
+				let name = wildcard(&named[..end]);
+				segments.push(if variant { PatternSegment::Variant(name) } else { PatternSegment::Field(name) });
+				rest = &named[end..];
+			}
+		}
+		if segments.is_empty() {
+			return Err(error("empty pattern"));
+		}
+		Ok(PathPattern(segments))
+	}
+}
@@ -0,0 +1,81 @@
+use std::fmt;
+
+use crate::types::Comparable;
+
+/// The reason a change could not be applied to a value.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PatchError {
+	/// The value is not the one the change was computed from: an old value
+	/// recorded in the change differs from the current one, or an element,
+	/// key or variant the change refers to is not there.
+	Mismatch(String),
+	/// The change cannot be applied to any value of the type, because it
+	/// lacks the information needed to rebuild part of a value. This happens
+	/// when a value must be built from its description, and the description
+	/// does not mirror the type (see [`Patch::from_desc`]), or when a change
+	/// is inverted that does not record a value it takes away (see
+	/// [`Invert::invert`](crate::Invert::invert)).
+	Unsupported(String),
+}
+
+impl fmt::Display for PatchError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			PatchError::Mismatch(msg) => write!(f, "change does not apply: {}", msg),
+			PatchError::Unsupported(msg) => write!(f, "change cannot be applied: {}", msg),
+		}
+	}
+}
+
+impl std::error::Error for PatchError {}
+
+/// Values that can have a change, as computed by [`Comparable::comparison`],
+/// applied to them, turning the first value compared into the second:
+///
+/// ```
+/// # use comparable::*;
+/// let mut a = vec![1, 2, 3];
+/// let b = vec![1, 4];
+/// if let Changed::Changed(change) = a.comparison(&b) {
+///     a.apply(change).unwrap();
+/// }
+/// assert_eq!(a, b);
+/// ```
+///
+/// Deriving `Comparable` for a type marked `#[comparable_patch]` also derives
+/// `Patch`, which requires that the type of every compared field implements
+/// `Patch` too.
+pub trait Patch: Comparable + Sized {
+	/// Apply a change to a value. The change is checked against the value as
+	/// it is applied, and if it turns out not to fit, an error is returned
+	/// and the value may be left partially changed.
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError>;
+
+	/// Rebuild a value from its description, as needed to apply changes that
+	/// add a value or replace one outright. This fails with
+	/// `PatchError::Unsupported` if the description leaves out part of the
+	/// value, for example because a field is ignored, or because the type
+	/// uses a custom description.
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError>;
+}
+
+// A reference can be compared, so that types holding one can derive `Patch`,
+// but not changed or rebuilt.
+impl<T: Comparable> Patch for &T {
+	fn apply(&mut self, _change: Self::Change) -> Result<(), PatchError> {
+		Err(PatchError::Unsupported("a reference cannot be changed".to_string()))
+	}
+
+	fn from_desc(_desc: Self::Desc) -> Result<Self, PatchError> {
+		Err(PatchError::Unsupported("a reference cannot be built from its description".to_string()))
+	}
+}
+
+/// Check that the old value recorded in a change is the current value.
+pub(crate) fn expect_value<T: PartialEq + fmt::Debug>(current: &T, old: &T) -> Result<(), PatchError> {
+	if current == old {
+		Ok(())
+	} else {
+		Err(PatchError::Mismatch(format!("expected {:?}, found {:?}", old, current)))
+	}
+}
@@ -1,3 +1,8 @@
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{merge_whole, Conflicts, Merge};
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{render_replaced, ChangePath, Leaf, LeafValue, Render};
 use crate::types::{Changed, Comparable};
 use std::path::{Path, PathBuf};
 
@@ -23,6 +28,47 @@ impl Comparable for PathBuf {
 	}
 }
 
+impl Patch for PathBuf {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let PathBufChange(old, new) = change;
+		expect_value(self, &old)?;
+		*self = new;
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		Ok(desc)
+	}
+}
+
+impl Invert for PathBuf {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		let PathBufChange(old, new) = change;
+		Ok(PathBufChange(new, old))
+	}
+}
+
+impl Merge for PathBuf {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_whole(left, right)
+	}
+}
+
+impl Compose for PathBuf {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		let (PathBufChange(old, middle), PathBufChange(expected, new)) = (first, second);
+		expect_value(&middle, &expected)?;
+		Ok(old.comparison(&new))
+	}
+}
+
+impl Render for PathBuf {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		let PathBufChange(old, new) = change;
+		render_replaced(old, new, path, leaves);
+	}
+}
+
 impl Comparable for Path {
 	type Desc = PathBuf;
 
@@ -40,3 +86,21 @@ impl Comparable for Path {
 		}
 	}
 }
+
+impl Invert for Path {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		PathBuf::invert(change)
+	}
+}
+
+impl Merge for Path {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_whole(left, right)
+	}
+}
+
+impl Render for Path {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		PathBuf::render_change(change, path, leaves)
+	}
+}
@@ -0,0 +1,264 @@
+use std::collections::{BinaryHeap, LinkedList, VecDeque};
+
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{Patch, PatchError};
+use crate::render::{ChangePath, Leaf, LeafValue, Render};
+use crate::set::{
+	apply_vec_changes, compose_vec_changes, invert_set_changes, invert_vec_changes, merge_vec_changes,
+	render_set_changes, render_vec_changes, SetChange, VecChange,
+};
+use crate::types::{Changed, Comparable};
+
+// A `VecDeque` and a `LinkedList` are sequences just as a `Vec` is, so they
+// are compared, and their changes applied, as one.
+
+impl<Value: PartialEq + Comparable> Comparable for VecDeque<Value> {
+	type Desc = Vec<Value::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		self.iter().map(|v| v.describe()).collect()
+	}
+
+	type Change = Vec<VecChange<Value::Desc, Value::Change>>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		self.iter().collect::<Vec<_>>().comparison_with(&other.iter().collect(), options)
+	}
+}
+
+impl<Value: PartialEq + Patch> Patch for VecDeque<Value> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let mut values = Vec::from(std::mem::take(self));
+		let result = apply_vec_changes(&mut values, change);
+		*self = values.into();
+		result
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(Value::from_desc).collect()
+	}
+}
+
+impl<Value: PartialEq + Invert> Invert for VecDeque<Value> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_vec_changes::<Value>(change)
+	}
+}
+
+impl<Value: PartialEq + Merge> Merge for VecDeque<Value> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_vec_changes::<Value>(left, right)
+	}
+}
+
+impl<Value: PartialEq + Compose> Compose for VecDeque<Value> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_vec_changes::<Value>(first, second)
+	}
+}
+
+impl<Value: PartialEq + Render> Render for VecDeque<Value> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_vec_changes::<Value, _>(change, path, leaves)
+	}
+}
+
+impl<Value: PartialEq + Comparable> Comparable for LinkedList<Value> {
+	type Desc = Vec<Value::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		self.iter().map(|v| v.describe()).collect()
+	}
+
+	type Change = Vec<VecChange<Value::Desc, Value::Change>>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		self.iter().collect::<Vec<_>>().comparison_with(&other.iter().collect(), options)
+	}
+}
+
+impl<Value: PartialEq + Patch> Patch for LinkedList<Value> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let mut values: Vec<Value> = std::mem::take(self).into_iter().collect();
+		let result = apply_vec_changes(&mut values, change);
+		*self = values.into_iter().collect();
+		result
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(Value::from_desc).collect()
+	}
+}
+
+impl<Value: PartialEq + Invert> Invert for LinkedList<Value> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_vec_changes::<Value>(change)
+	}
+}
+
+impl<Value: PartialEq + Merge> Merge for LinkedList<Value> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_vec_changes::<Value>(left, right)
+	}
+}
+
+impl<Value: PartialEq + Compose> Compose for LinkedList<Value> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_vec_changes::<Value>(first, second)
+	}
+}
+
+impl<Value: PartialEq + Render> Render for LinkedList<Value> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_vec_changes::<Value, _>(change, path, leaves)
+	}
+}
+
+// A `BinaryHeap` only orders its greatest member, so it is compared as the
+// multiset of its members: each occurrence of a member one heap has more of
+// than the other is reported as added or removed, in sorted order.
+impl<Value: Ord + Comparable> Comparable for BinaryHeap<Value> {
+	type Desc = Vec<Value::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		sorted(self).into_iter().map(|v| v.describe()).collect()
+	}
+
+	type Change = Vec<SetChange<Value::Desc>>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		let (this, other) = (sorted(self), sorted(other));
+		let (mut added, mut removed) = (Vec::new(), Vec::new());
+		let (mut i, mut j) = (0, 0);
+		while i < this.len() || j < other.len() {
+			if j == other.len() || (i < this.len() && this[i] < other[j]) {
+				removed.push(SetChange::Removed(this[i].describe()));
+				i += 1;
+			} else if i == this.len() || other[j] < this[i] {
+				added.push(SetChange::Added(other[j].describe()));
+				j += 1;
+			} else {
+				i += 1;
+				j += 1;
+			}
+		}
+		added.append(&mut removed);
+		if added.is_empty() {
+			Changed::Unchanged
+		} else {
+			Changed::Changed(added)
+		}
+	}
+}
+
+fn sorted<Value: Ord>(heap: &BinaryHeap<Value>) -> Vec<&Value> {
+	let mut values: Vec<&Value> = heap.iter().collect();
+	values.sort();
+	values
+}
+
+// The members of the heap and those added are sorted together once, so that
+// every member removed is found in a single pass, and a member that is not
+// present is found before the heap is changed.
+impl<Value: Ord + Patch> Patch for BinaryHeap<Value> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let mut values = std::mem::take(self).into_vec();
+		let present = values.len();
+		let mut removed = Vec::new();
+		for change in change {
+			match change {
+				SetChange::Added(desc) => values.push(Value::from_desc(desc)?),
+				SetChange::Removed(desc) => removed.push(Value::from_desc(desc)?),
+			}
+		}
+		removed.sort();
+
+		let mut order: Vec<usize> = (0..values.len()).collect();
+		order.sort_by(|&a, &b| values[a].cmp(&values[b]));
+		let mut dropped = vec![false; values.len()];
+		let mut next = order.iter().peekable();
+		for value in &removed {
+			while next.next_if(|&&i| values[i] < *value).is_some() {}
+			match next.next_if(|&&i| values[i] == *value) {
+				Some(&i) => dropped[i] = true,
+				None => {
+					values.truncate(present);
+					*self = values.into();
+					return Err(PatchError::Mismatch(format!("{:?} is not present", value.describe())));
+				}
+			}
+		}
+
+		let mut dropped = dropped.into_iter();
+		values.retain(|_| !dropped.next().unwrap_or(false));
+		*self = values.into();
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(Value::from_desc).collect()
+	}
+}
+
+impl<Value: Ord + Invert> Invert for BinaryHeap<Value> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		Ok(invert_set_changes(change))
+	}
+}
+
+// Both sides start from the same heap, so an occurrence added or removed by
+// both is the same one, and each change is kept as many times as the side
+// making it more often makes it.
+impl<Value: Ord + Merge> Merge for BinaryHeap<Value> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		let mut shared = vec![false; left.len()];
+		let mut merged = Vec::new();
+		for change in right {
+			match (0..left.len()).find(|&i| !shared[i] && left[i] == change) {
+				Some(i) => shared[i] = true,
+				None => merged.push(change),
+			}
+		}
+		Ok(left.into_iter().chain(merged).collect())
+	}
+}
+
+// An occurrence added by one list and removed by the other cancels out. The
+// heap the lists start from is unknown, so any number of occurrences may
+// have been there to remove, and they are never out of sequence.
+impl<Value: Ord + Compose> Compose for BinaryHeap<Value> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		let mut composed = first;
+		for change in second {
+			let cancelled = composed.iter().position(|earlier| match (earlier, &change) {
+				(SetChange::Added(earlier), SetChange::Removed(later))
+				| (SetChange::Removed(earlier), SetChange::Added(later)) => earlier == later,
+				_ => false,
+			});
+			match cancelled {
+				Some(earlier) => {
+					composed.remove(earlier);
+				}
+				None => composed.push(change),
+			}
+		}
+		Ok(if composed.is_empty() { Changed::Unchanged } else { Changed::Changed(composed) })
+	}
+}
+
+impl<Value: Ord + Render> Render for BinaryHeap<Value> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_set_changes(change, path, leaves)
+	}
+}
@@ -0,0 +1,178 @@
+use std::ops::{Range, RangeInclusive};
+
+use crate::compose::{compose_changed, Compose};
+use crate::invert::{invert_changed, Invert};
+use crate::merge::{merge_part, Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{Patch, PatchError};
+use crate::render::{render_at, ChangePath, Leaf, LeafValue, PathSegment, Render};
+use crate::types::{Changed, Comparable};
+
+/// A change to one end of a range, reported as a change to the field of a
+/// struct would be, so that a range whose ends both moved has two changes.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug)]
+pub enum RangeChange<Change> {
+	Start(Change),
+	End(Change),
+}
+
+type EndChanges<Change> = (Changed<Change>, Changed<Change>);
+
+fn split<Change>(changes: Vec<RangeChange<Change>>) -> EndChanges<Change> {
+	let mut split = (Changed::Unchanged, Changed::Unchanged);
+	for change in changes {
+		match change {
+			RangeChange::Start(change) => split.0 = Changed::Changed(change),
+			RangeChange::End(change) => split.1 = Changed::Changed(change),
+		}
+	}
+	split
+}
+
+fn join<Change>((start, end): EndChanges<Change>) -> Vec<RangeChange<Change>> {
+	let mut changes = Vec::new();
+	changes.extend(start.map(RangeChange::Start));
+	changes.extend(end.map(RangeChange::End));
+	changes
+}
+
+fn apply_ends<T: Patch>(start: &mut T, end: &mut T, changes: Vec<RangeChange<T::Change>>) -> Result<(), PatchError> {
+	for change in changes {
+		match change {
+			RangeChange::Start(change) => start.apply(change)?,
+			RangeChange::End(change) => end.apply(change)?,
+		}
+	}
+	Ok(())
+}
+
+// The ends of a range, which `Range` keeps in fields and `RangeInclusive`
+// behind methods.
+trait Ends<T> {
+	fn ends(&self) -> (&T, &T);
+	fn from_ends(start: T, end: T) -> Self;
+}
+
+impl<T> Ends<T> for Range<T> {
+	fn ends(&self) -> (&T, &T) {
+		(&self.start, &self.end)
+	}
+
+	fn from_ends(start: T, end: T) -> Self {
+		start..end
+	}
+}
+
+impl<T> Ends<T> for RangeInclusive<T> {
+	fn ends(&self) -> (&T, &T) {
+		(self.start(), self.end())
+	}
+
+	fn from_ends(start: T, end: T) -> Self {
+		start..=end
+	}
+}
+
+impl<T: Patch> Patch for Range<T> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		apply_ends(&mut self.start, &mut self.end, change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		Ok(T::from_desc(desc.start)?..T::from_desc(desc.end)?)
+	}
+}
+
+// The ends of a `RangeInclusive` cannot be changed in place, so they are
+// copied out, changed and put back.
+impl<T: Patch + Clone> Patch for RangeInclusive<T> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let (mut start, mut end) = self.clone().into_inner();
+		apply_ends(&mut start, &mut end, change)?;
+		*self = start..=end;
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		let (start, end) = desc.into_inner();
+		Ok(T::from_desc(start)?..=T::from_desc(end)?)
+	}
+}
+
+macro_rules! impl_range {
+	($($range:ident: $($bound:ident)?),*) => {$(
+		impl<T: Comparable> Comparable for $range<T> {
+			type Desc = $range<T::Desc>;
+
+			fn describe(&self) -> Self::Desc {
+				let (start, end) = self.ends();
+				$range::from_ends(start.describe(), end.describe())
+			}
+
+			type Change = Vec<RangeChange<T::Change>>;
+
+			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				self.comparison_with(other, &CompareOptions::default())
+			}
+
+			fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+				let (this, other) = (self.ends(), other.ends());
+				let changes = join((
+					options.compare_at(PathSegment::Field("start"), this.0, other.0),
+					options.compare_at(PathSegment::Field("end"), this.1, other.1),
+				));
+				if changes.is_empty() {
+					Changed::Unchanged
+				} else {
+					Changed::Changed(changes)
+				}
+			}
+		}
+
+		impl<T: Invert> Invert for $range<T> {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				let (start, end) = split(change);
+				Ok(join((invert_changed::<T>(start)?, invert_changed::<T>(end)?)))
+			}
+		}
+
+		impl<T: Merge> Merge for $range<T> {
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				let (left, right) = (split(left), split(right));
+				let mut conflicts = Vec::new();
+				let merged = join((
+					merge_part::<T, _>(left.0, right.0, &mut conflicts, |change| vec![RangeChange::Start(change)]),
+					merge_part::<T, _>(left.1, right.1, &mut conflicts, |change| vec![RangeChange::End(change)]),
+				));
+				if conflicts.is_empty() {
+					Ok(merged)
+				} else {
+					Err(Conflicts(conflicts))
+				}
+			}
+		}
+
+		impl<T: Compose $(+ $bound)?> Compose for $range<T> {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				let (first, second) = (split(first), split(second));
+				let composed =
+					join((compose_changed::<T>(first.0, second.0)?, compose_changed::<T>(first.1, second.1)?));
+				Ok(if composed.is_empty() { Changed::Unchanged } else { Changed::Changed(composed) })
+			}
+		}
+
+		impl<T: Render> Render for $range<T> {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				for change in change {
+					match change {
+						RangeChange::Start(change) => render_at::<T, _>(change, PathSegment::Field("start"), path, leaves),
+						RangeChange::End(change) => render_at::<T, _>(change, PathSegment::Field("end"), path, leaves),
+					}
+				}
+			}
+		}
+	)*};
+}
+
+impl_range!(Range:, RangeInclusive: Clone);
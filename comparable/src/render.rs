@@ -0,0 +1,228 @@
+use std::fmt::{self, Debug, Display};
+
+use crate::types::{Changed, Comparable, MaybeSerde};
+
+/// How the values found in a change, and the keys on the path to them, are
+/// represented in its leaves. People read them as `String`s, written with
+/// `Debug`; with the `serde` feature, `json_patch` reads
+/// them as JSON.
+pub trait LeafValue: Clone {
+	fn of<T: Debug + MaybeSerde>(value: &T) -> Self;
+}
+
+impl LeafValue for String {
+	fn of<T: Debug + MaybeSerde>(value: &T) -> Self {
+		format!("{:?}", value)
+	}
+}
+
+/// One step on the way from a value to a part of it that changed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PathSegment<V = String> {
+	/// A field of a struct or enum variant, or an element of a tuple, such
+	/// as `.name` or `.0`. The sole field of a tuple struct or variant has no
+	/// segment of its own.
+	Field(&'static str),
+	/// The variant of an enum whose fields changed, such as `::Rect`.
+	Variant(&'static str),
+	/// An index into a sequence, such as `[3]`.
+	Index(usize),
+	/// A key of a map, such as `["k"]`.
+	Key(V),
+	/// The key of an element of a `#[comparable_key]` vector, such as `["k"]`.
+	Element(V),
+}
+
+impl<V: Display> Display for PathSegment<V> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			PathSegment::Field(name) => write!(f, ".{}", name),
+			PathSegment::Variant(name) => write!(f, "::{}", name),
+			PathSegment::Index(index) => write!(f, "[{}]", index),
+			PathSegment::Key(key) | PathSegment::Element(key) => write!(f, "[{}]", key),
+		}
+	}
+}
+
+/// The path from a value to a part of it, such as `.inner.flags[3]`. The
+/// empty path stands for the value itself.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ChangePath<V = String>(pub Vec<PathSegment<V>>);
+
+impl<V> Default for ChangePath<V> {
+	fn default() -> Self {
+		ChangePath(Vec::new())
+	}
+}
+
+impl<V> ChangePath<V> {
+	pub fn push(&mut self, segment: PathSegment<V>) {
+		self.0.push(segment);
+	}
+
+	pub fn pop(&mut self) {
+		self.0.pop();
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl<V: Display> Display for ChangePath<V> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.0.iter().try_for_each(|segment| write!(f, "{}", segment))
+	}
+}
+
+/// What happened to one part of a value. Values are rendered with `Debug`,
+/// unless another [`LeafValue`] is asked for.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LeafChange<V = String> {
+	/// The part was replaced: its old value, and its new one.
+	Changed(V, V),
+	/// An element or entry was added with the given value.
+	Added(V),
+	/// An element or entry was removed, along with its value if the change
+	/// records it.
+	Removed(Option<V>),
+	/// An element moved from one index to another.
+	Moved(usize, usize),
+	/// The part was edited as shown by a diff of it: a hunk of a unified diff
+	/// of the lines of a string, the marked-up spans of a string, or the
+	/// signed difference between two numbers.
+	Diff(String),
+}
+
+impl<V: Display> Display for LeafChange<V> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			LeafChange::Changed(old, new) => write!(f, "{} -> {}", old, new),
+			LeafChange::Added(value) => write!(f, "added {}", value),
+			LeafChange::Removed(Some(value)) => write!(f, "removed {}", value),
+			LeafChange::Removed(None) => write!(f, "removed"),
+			LeafChange::Moved(from, to) => write!(f, "moved from {} to {}", from, to),
+			LeafChange::Diff(diff) => write!(f, "{}", diff),
+		}
+	}
+}
+
+/// A change to one part of a value, together with the path to that part.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Leaf<V = String> {
+	pub path: ChangePath<V>,
+	pub change: LeafChange<V>,
+}
+
+impl<V: Display> Display for Leaf<V> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.path.is_empty() {
+			write!(f, "{}", self.change)
+		} else {
+			write!(f, "{}: {}", self.path, self.change)
+		}
+	}
+}
+
+/// The leaves of a change, as produced by [`render`], displayed one per line.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Rendered(pub Vec<Leaf>);
+
+impl Display for Rendered {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, leaf) in self.0.iter().enumerate() {
+			if i > 0 {
+				writeln!(f)?;
+			}
+			write!(f, "{}", leaf)?;
+		}
+		Ok(())
+	}
+}
+
+/// Values whose changes can be rendered for people to read, by flattening
+/// them into [`Leaf`]s: the path to each part of the value that changed,
+/// along with what happened to it. See [`render`].
+///
+/// Deriving `Comparable` for a type marked `#[comparable_render]` also derives
+/// `Render`, which requires that the type of every compared field implements
+/// `Render` too.
+pub trait Render: Comparable {
+	/// Add the leaves of a change, made to the part of a value at `path`, to
+	/// `leaves`. `path` is left as it was found.
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>);
+}
+
+/// Flatten a change into the leaves that make it up, which display one per
+/// line:
+///
+/// ```
+/// # use comparable::*;
+/// # use std::collections::BTreeMap;
+/// let mut a = BTreeMap::new();
+/// a.insert("x".to_string(), vec![true, false]);
+/// let mut b = a.clone();
+/// b.get_mut("x").unwrap()[1] = true;
+/// b.insert("y".to_string(), vec![]);
+/// assert_eq!(
+///     render::<BTreeMap<String, Vec<bool>>>(&a.comparison(&b)).to_string(),
+///     "[\"x\"][1]: false -> true\n[\"y\"]: added []"
+/// );
+/// ```
+pub fn render<T: Render + ?Sized>(change: &Changed<T::Change>) -> Rendered {
+	let mut leaves = Vec::new();
+	render_changed::<T, _>(change, &mut ChangePath::default(), &mut leaves);
+	Rendered(leaves)
+}
+
+/// Render a change that may be `Changed::Unchanged`, as found in the changes
+/// of tuples, arrays and enum variants.
+pub fn render_changed<T: Render + ?Sized, V: LeafValue>(
+	change: &Changed<T::Change>,
+	path: &mut ChangePath<V>,
+	leaves: &mut Vec<Leaf<V>>,
+) {
+	if let Changed::Changed(change) = change {
+		T::render_change(change, path, leaves);
+	}
+}
+
+/// Render a change to the part of a value reached through `segment`.
+pub fn render_at<T: Render + ?Sized, V: LeafValue>(
+	change: &T::Change,
+	segment: PathSegment<V>,
+	path: &mut ChangePath<V>,
+	leaves: &mut Vec<Leaf<V>>,
+) {
+	path.push(segment);
+	T::render_change(change, path, leaves);
+	path.pop();
+}
+
+/// Render the replacement of one value by another.
+pub fn render_replaced<Desc: Debug + MaybeSerde, V: LeafValue>(
+	old: &Desc,
+	new: &Desc,
+	path: &ChangePath<V>,
+	leaves: &mut Vec<Leaf<V>>,
+) {
+	leaves.push(Leaf { path: path.clone(), change: LeafChange::Changed(V::of(old), V::of(new)) });
+}
+
+/// Add a leaf at the given path, reached through `segment` if given.
+pub(crate) fn push_leaf<V: LeafValue>(
+	segment: Option<PathSegment<V>>,
+	change: LeafChange<V>,
+	path: &ChangePath<V>,
+	leaves: &mut Vec<Leaf<V>>,
+) {
+	let mut path = path.clone();
+	path.0.extend(segment);
+	leaves.push(Leaf { path, change });
+}
+
+impl<T: Render> Render for &T {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		T::render_change(change, path, leaves)
+	}
+}
@@ -0,0 +1,131 @@
+use std::fmt::{self, Display, Write};
+use std::io::IsTerminal;
+
+use crate::render::{render, Leaf, LeafChange, Render};
+use crate::types::Changed;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// How a change differs from the one expected, leaf by leaf, as reported by
+/// `assert_changes_rendered!`: the leaves of the actual change, the expected
+/// leaves missing from it, and the leaves of it that were not expected. See
+/// [`Leaf`] for what makes up a leaf.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct ChangeReport {
+	pub actual: Vec<Leaf>,
+	pub missing: Vec<Leaf>,
+	pub unexpected: Vec<Leaf>,
+}
+
+impl ChangeReport {
+	/// Compare a change with the one expected, or return `None` if they are
+	/// the same.
+	pub fn between<T: Render + ?Sized>(expected: &Changed<T::Change>, actual: &Changed<T::Change>) -> Option<Self> {
+		if expected == actual {
+			return None;
+		}
+		let actual = render::<T>(actual).0;
+		let mut unexpected = actual.clone();
+		let mut missing = Vec::new();
+		for leaf in render::<T>(expected).0 {
+			match unexpected.iter().position(|other| *other == leaf) {
+				Some(i) => {
+					unexpected.remove(i);
+				}
+				None => missing.push(leaf),
+			}
+		}
+		Some(ChangeReport { actual, missing, unexpected })
+	}
+
+	/// Write out the report, coloring each leaf with ANSI escape codes if
+	/// `color` is set: old and removed values, and deleted lines and text of
+	/// diffs, in red; new and added ones in green.
+	pub fn report(&self, color: bool) -> String {
+		let mut out = String::new();
+		let mut section = |title: &str, prefix: &str, leaves: &[Leaf]| {
+			let _ = writeln!(out, "{}:", title);
+			if leaves.is_empty() {
+				let _ = writeln!(out, "    (none)");
+			}
+			for leaf in leaves {
+				// Leaves that span several lines, like diffs, are indented as one.
+				let leaf = Colored(leaf, color).to_string().replace('\n', "\n    ");
+				let _ = writeln!(out, "  {} {}", prefix, leaf);
+			}
+		};
+		section("actual changes", " ", &self.actual);
+		section("missing changes, expected but not found", "-", &self.missing);
+		section("unexpected changes, found but not expected", "+", &self.unexpected);
+		if self.missing.is_empty() && self.unexpected.is_empty() {
+			out.push_str("(the expected and actual changes differ only in their order or representation)\n");
+		}
+		out
+	}
+}
+
+impl Display for ChangeReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.report(false))
+	}
+}
+
+struct Colored<'a>(&'a Leaf, bool);
+
+impl Display for Colored<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let Colored(leaf, color) = *self;
+		if !color {
+			return write!(f, "{}", leaf);
+		}
+		if !leaf.path.is_empty() {
+			write!(f, "{}: ", leaf.path)?;
+		}
+		match &leaf.change {
+			LeafChange::Changed(old, new) => write!(f, "{}{}{} -> {}{}{}", RED, old, RESET, GREEN, new, RESET),
+			LeafChange::Added(_) => write!(f, "{}{}{}", GREEN, leaf.change, RESET),
+			LeafChange::Removed(_) => write!(f, "{}{}{}", RED, leaf.change, RESET),
+			LeafChange::Moved(..) => write!(f, "{}", leaf.change),
+			LeafChange::Diff(diff) if !diff.starts_with("@@") => {
+				let diff = diff
+					.replace("[-", &format!("{}[-", RED))
+					.replace("-]", &format!("-]{}", RESET))
+					.replace("{+", &format!("{}{{+", GREEN))
+					.replace("+}", &format!("+}}{}", RESET));
+				write!(f, "{}", diff)
+			}
+			LeafChange::Diff(diff) => {
+				for (i, line) in diff.lines().enumerate() {
+					if i > 0 {
+						writeln!(f)?;
+					}
+					match line.chars().next() {
+						Some('-') => write!(f, "{}{}{}", RED, line, RESET)?,
+						Some('+') => write!(f, "{}{}{}", GREEN, line, RESET)?,
+						_ => write!(f, "{}", line)?,
+					}
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+/// Whether reports written to standard error should be colored: only if it is
+/// a terminal, and `NO_COLOR` is not set to anything.
+#[allow(clippy::unnecessary_map_or)] // `Option::is_none_or` needs Rust 1.82
+pub fn use_color() -> bool {
+	std::env::var_os("NO_COLOR").map_or(true, |value| value.is_empty()) && std::io::stderr().is_terminal()
+}
+
+/// Check the change between two values against the one expected, returning a
+/// report of how they differ if they do. Used by `assert_changes_rendered!`.
+#[doc(hidden)]
+pub fn check_changes<T: Render + ?Sized>(left: &T, right: &T, expected: &Changed<T::Change>) -> Result<(), String> {
+	match ChangeReport::between::<T>(expected, &left.comparison(right)) {
+		None => Ok(()),
+		Some(report) => Err(report.report(use_color())),
+	}
+}
@@ -0,0 +1,116 @@
+use crate::compose::{compose_replacing, Compose};
+use crate::invert::Invert;
+use crate::merge::{merge_whole, Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{render_at, render_replaced, ChangePath, Leaf, LeafValue, PathSegment, Render};
+use crate::types::{Changed, Comparable};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug)]
+pub enum ResultChange<Desc, Change, ErrorChange> {
+	BothOk(Change),
+	BothErr(ErrorChange),
+	Different(Desc, Desc),
+}
+
+// `Ok` and `Err` are variants like those of a derived enum, so that their
+// changes have the paths `::Ok` and `::Err`.
+impl<T: Comparable, E: Comparable> Comparable for Result<T, E> {
+	type Desc = Result<T::Desc, E::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		match self {
+			Ok(x) => Ok(x.describe()),
+			Err(e) => Err(e.describe()),
+		}
+	}
+
+	type Change = ResultChange<Self::Desc, T::Change, E::Change>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		match (self, other) {
+			(Ok(x), Ok(y)) => options.compare_at(PathSegment::Variant("Ok"), x, y).map(ResultChange::BothOk),
+			(Err(x), Err(y)) => options.compare_at(PathSegment::Variant("Err"), x, y).map(ResultChange::BothErr),
+			(_, _) => Changed::Changed(ResultChange::Different(self.describe(), other.describe())),
+		}
+	}
+}
+
+impl<T: Patch, E: Patch> Patch for Result<T, E> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		match (self.as_mut(), change) {
+			(Ok(x), ResultChange::BothOk(change)) => x.apply(change),
+			(Err(e), ResultChange::BothErr(change)) => e.apply(change),
+			(Err(_), ResultChange::BothOk(_)) => Err(PatchError::Mismatch("expected Ok, found Err".to_string())),
+			(Ok(_), ResultChange::BothErr(_)) => Err(PatchError::Mismatch("expected Err, found Ok".to_string())),
+			(_, ResultChange::Different(old, new)) => {
+				expect_value(&self.describe(), &old)?;
+				*self = Self::from_desc(new)?;
+				Ok(())
+			}
+		}
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		match desc {
+			Ok(x) => T::from_desc(x).map(Ok),
+			Err(e) => E::from_desc(e).map(Err),
+		}
+	}
+}
+
+impl<T: Invert, E: Invert> Invert for Result<T, E> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		match change {
+			ResultChange::BothOk(change) => T::invert(change).map(ResultChange::BothOk),
+			ResultChange::BothErr(change) => E::invert(change).map(ResultChange::BothErr),
+			ResultChange::Different(old, new) => Ok(ResultChange::Different(new, old)),
+		}
+	}
+}
+
+impl<T: Merge, E: Merge> Merge for Result<T, E> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		match (left, right) {
+			(ResultChange::BothOk(left), ResultChange::BothOk(right)) => T::merge_changes(left, right)
+				.map(ResultChange::BothOk)
+				.map_err(|conflicts| conflicts.map(ResultChange::BothOk)),
+			(ResultChange::BothErr(left), ResultChange::BothErr(right)) => E::merge_changes(left, right)
+				.map(ResultChange::BothErr)
+				.map_err(|conflicts| conflicts.map(ResultChange::BothErr)),
+			(left, right) => merge_whole(left, right),
+		}
+	}
+}
+
+impl<T: Compose, E: Compose> Compose for Result<T, E> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		match (first, second) {
+			(ResultChange::BothOk(first), ResultChange::BothOk(second)) => {
+				Ok(T::compose(first, second)?.map(ResultChange::BothOk))
+			}
+			(ResultChange::BothErr(first), ResultChange::BothErr(second)) => {
+				Ok(E::compose(first, second)?.map(ResultChange::BothErr))
+			}
+			(first, second) => compose_replacing::<Self>(first, second, |change| match change {
+				ResultChange::Different(old, new) => Ok((old, new)),
+				change => Err(change),
+			}),
+		}
+	}
+}
+
+impl<T: Render, E: Render> Render for Result<T, E> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		match change {
+			ResultChange::BothOk(change) => render_at::<T, _>(change, PathSegment::Variant("Ok"), path, leaves),
+			ResultChange::BothErr(change) => render_at::<E, _>(change, PathSegment::Variant("Err"), path, leaves),
+			ResultChange::Different(old, new) => render_replaced(old, new, path, leaves),
+		}
+	}
+}
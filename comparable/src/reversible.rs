@@ -0,0 +1,331 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+
+use crate::compose::{apply_to_desc, out_of_sequence, revert_desc, Compose};
+use crate::invert::Invert;
+use crate::map::{merge_by_key, missing_key, pair_by_key, to_btreemap, MapChange, MapChanges};
+use crate::merge::{Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{push_leaf, render_at, ChangePath, Leaf, LeafChange, LeafValue, PathSegment, Render};
+use crate::types::{Changed, Comparable, MaybeSerde};
+
+/// A map whose changes record the value of every entry removed from it, so
+/// that they can be inverted.
+///
+/// Maps are normally compared into a list of `MapChange`s, where a removed
+/// entry is reported by its key alone, as `MapChange::Removed`. That is enough
+/// to apply the change, but not to undo it. `Reversible` compares a `BTreeMap`
+/// or `HashMap` into a list of `ReversibleMapChange`s instead, which are the
+/// same except that `ReversibleMapChange::Removed` also carries the
+/// description of the value removed.
+///
+/// The same comparison can be selected for a single field of a derived type
+/// with the `#[comparable_reversible]` attribute.
+#[repr(transparent)]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Reversible<T: ?Sized>(pub T);
+
+impl<T: ?Sized> Reversible<T> {
+	/// View a reference to a value as a reference to a `Reversible` value.
+	pub fn from_ref(value: &T) -> &Self {
+		// SAFETY: `Reversible<T>` is `repr(transparent)` over `T`.
+		unsafe { &*(value as *const T as *const Self) }
+	}
+
+	/// View a mutable reference to a value as a mutable reference to a
+	/// `Reversible` value.
+	pub fn from_mut(value: &mut T) -> &mut Self {
+		// SAFETY: `Reversible<T>` is `repr(transparent)` over `T`.
+		unsafe { &mut *(value as *mut T as *mut Self) }
+	}
+}
+
+impl<T> From<T> for Reversible<T> {
+	fn from(value: T) -> Self {
+		Reversible(value)
+	}
+}
+
+impl<T: ?Sized> Deref for Reversible<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T: ?Sized> DerefMut for Reversible<T> {
+	fn deref_mut(&mut self) -> &mut T {
+		&mut self.0
+	}
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug)]
+pub enum ReversibleMapChange<Key, Desc, Change> {
+	Added(Key, Desc),
+	Changed(Key, Change),
+	Removed(Key, Desc),
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Comparable> Comparable for Reversible<BTreeMap<Key, Value>> {
+	type Desc = BTreeMap<Key, Value::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		self.0.describe()
+	}
+
+	type Change = Vec<ReversibleMapChange<Key, Value::Desc, Value::Change>>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		reversible_comparison(&self.0, &other.0, options)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Patch> Patch for Reversible<BTreeMap<Key, Value>> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let change = forget_removed_values(|key| self.0.get(key), change)?;
+		self.0.apply(change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		BTreeMap::from_desc(desc).map(Reversible)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Invert> Invert for Reversible<BTreeMap<Key, Value>> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_reversible_map_changes::<Key, Value>(change)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Merge> Merge for Reversible<BTreeMap<Key, Value>> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_reversible_map_changes::<Key, Value>(left, right)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Compose> Compose for Reversible<BTreeMap<Key, Value>> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_reversible_map_changes::<Key, Value>(first, second)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Render> Render for Reversible<BTreeMap<Key, Value>> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_reversible_map_changes::<Key, Value, V>(change, path, leaves)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Comparable> Comparable for Reversible<HashMap<Key, Value>> {
+	type Desc = BTreeMap<Key, Value::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		self.0.describe()
+	}
+
+	type Change = Vec<ReversibleMapChange<Key, Value::Desc, Value::Change>>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		reversible_comparison(&to_btreemap(&self.0), &to_btreemap(&other.0), options)
+	}
+}
+
+impl<Key: std::hash::Hash + Ord + Clone + Debug + MaybeSerde, Value: Patch> Patch for Reversible<HashMap<Key, Value>> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let change = forget_removed_values(|key| self.0.get(key), change)?;
+		self.0.apply(change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		HashMap::from_desc(desc).map(Reversible)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Invert> Invert for Reversible<HashMap<Key, Value>> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_reversible_map_changes::<Key, Value>(change)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Merge> Merge for Reversible<HashMap<Key, Value>> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_reversible_map_changes::<Key, Value>(left, right)
+	}
+}
+
+impl<Key: std::hash::Hash + Ord + Clone + Debug + MaybeSerde, Value: Compose> Compose
+	for Reversible<HashMap<Key, Value>>
+{
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_reversible_map_changes::<Key, Value>(first, second)
+	}
+}
+
+impl<Key: Ord + Clone + Debug + MaybeSerde, Value: Render> Render for Reversible<HashMap<Key, Value>> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_reversible_map_changes::<Key, Value, V>(change, path, leaves)
+	}
+}
+
+type ReversibleMapChanges<Key, Value> =
+	Vec<ReversibleMapChange<Key, <Value as Comparable>::Desc, <Value as Comparable>::Change>>;
+
+fn reversible_comparison<Key: Ord + Clone + Debug + MaybeSerde, Value: Comparable>(
+	this: &BTreeMap<Key, Value>,
+	other: &BTreeMap<Key, Value>,
+	options: &CompareOptions,
+) -> Changed<ReversibleMapChanges<Key, Value>> {
+	this.comparison_with(other, options).map(|changes| {
+		changes
+			.into_iter()
+			.map(|change| match change {
+				MapChange::Added(key, desc) => ReversibleMapChange::Added(key, desc),
+				MapChange::Changed(key, change) => ReversibleMapChange::Changed(key, change),
+				MapChange::Removed(key) => {
+					let desc = this[&key].describe();
+					ReversibleMapChange::Removed(key, desc)
+				}
+				MapChange::Moved { .. } => unreachable!("maps have no order, so their entries cannot move"),
+			})
+			.collect()
+	})
+}
+
+// Check the value recorded for every removed entry against the current one,
+// leaving changes that can be applied as those of a plain map.
+fn forget_removed_values<'a, Key: Debug, Value: Comparable + 'a>(
+	get: impl Fn(&Key) -> Option<&'a Value>,
+	changes: ReversibleMapChanges<Key, Value>,
+) -> Result<MapChanges<Key, Value>, PatchError> {
+	changes
+		.into_iter()
+		.map(|change| {
+			Ok(match change {
+				ReversibleMapChange::Added(key, desc) => MapChange::Added(key, desc),
+				ReversibleMapChange::Changed(key, change) => MapChange::Changed(key, change),
+				ReversibleMapChange::Removed(key, desc) => {
+					expect_value(&get(&key).ok_or_else(|| missing_key(&key))?.describe(), &desc)?;
+					MapChange::Removed(key)
+				}
+			})
+		})
+		.collect()
+}
+
+fn invert_reversible_map_changes<Key, Value: Invert>(
+	changes: ReversibleMapChanges<Key, Value>,
+) -> Result<ReversibleMapChanges<Key, Value>, PatchError> {
+	changes
+		.into_iter()
+		.map(|change| {
+			Ok(match change {
+				ReversibleMapChange::Added(key, desc) => ReversibleMapChange::Removed(key, desc),
+				ReversibleMapChange::Changed(key, change) => ReversibleMapChange::Changed(key, Value::invert(change)?),
+				ReversibleMapChange::Removed(key, desc) => ReversibleMapChange::Added(key, desc),
+			})
+		})
+		.collect()
+}
+
+fn reversible_map_change_key<Key, Desc, Change>(change: &ReversibleMapChange<Key, Desc, Change>) -> &Key {
+	match change {
+		ReversibleMapChange::Added(key, _)
+		| ReversibleMapChange::Changed(key, _)
+		| ReversibleMapChange::Removed(key, _) => key,
+	}
+}
+
+fn merge_reversible_map_changes<Key: Ord + Clone, Value: Merge>(
+	left: ReversibleMapChanges<Key, Value>,
+	right: ReversibleMapChanges<Key, Value>,
+) -> Result<ReversibleMapChanges<Key, Value>, Conflicts<ReversibleMapChanges<Key, Value>>> {
+	merge_by_key(
+		left,
+		right,
+		|change| reversible_map_change_key(change).clone(),
+		|left, right| match (left, right) {
+			(ReversibleMapChange::Changed(key, left), ReversibleMapChange::Changed(_, right)) => {
+				Value::merge_changes(left, right)
+					.map(|change| ReversibleMapChange::Changed(key.clone(), change))
+					.map_err(|conflicts| {
+						conflicts.map(|change| vec![ReversibleMapChange::Changed(key.clone(), change)])
+					})
+			}
+			(left, right) => Err(Conflicts::between(vec![left], vec![right])),
+		},
+	)
+}
+
+// Since every change records the values it adds and removes, the changes to an
+// entry can always be composed, unless they do not follow each other.
+fn compose_reversible_map_changes<Key: Ord + Clone + Debug, Value: Compose>(
+	first: ReversibleMapChanges<Key, Value>,
+	second: ReversibleMapChanges<Key, Value>,
+) -> Result<Changed<ReversibleMapChanges<Key, Value>>, PatchError> {
+	let mut composed = Vec::new();
+	for (key, (mut first, mut second)) in pair_by_key(first, second, |change| reversible_map_change_key(change).clone())
+	{
+		if first.len() > 1 || second.len() > 1 {
+			return Err(PatchError::Mismatch(format!("key {:?} is changed more than once", key)));
+		}
+		let (first, second) = match (first.pop(), second.pop()) {
+			(Some(first), Some(second)) => (first, second),
+			(Some(change), None) | (None, Some(change)) => {
+				composed.push(change);
+				continue;
+			}
+			(None, None) => continue,
+		};
+		match (first, second) {
+			(ReversibleMapChange::Added(key, desc), ReversibleMapChange::Changed(_, change)) => {
+				composed.push(ReversibleMapChange::Added(key, apply_to_desc::<Value>(desc, change)?))
+			}
+			(ReversibleMapChange::Added(_, desc), ReversibleMapChange::Removed(_, removed)) => {
+				expect_value(&removed, &desc)?
+			}
+			(ReversibleMapChange::Changed(key, first), ReversibleMapChange::Changed(_, second)) => {
+				if let Changed::Changed(change) = Value::compose(first, second)? {
+					composed.push(ReversibleMapChange::Changed(key, change))
+				}
+			}
+			(ReversibleMapChange::Changed(key, change), ReversibleMapChange::Removed(_, desc)) => {
+				composed.push(ReversibleMapChange::Removed(key, revert_desc::<Value>(desc, change)?))
+			}
+			(ReversibleMapChange::Removed(key, old), ReversibleMapChange::Added(_, new)) => {
+				if let Changed::Changed(change) = Value::from_desc(old)?.comparison(&Value::from_desc(new)?) {
+					composed.push(ReversibleMapChange::Changed(key, change))
+				}
+			}
+			(first, second) => return Err(out_of_sequence(&first, &second)),
+		}
+	}
+	Ok(if composed.is_empty() { Changed::Unchanged } else { Changed::Changed(composed) })
+}
+
+fn render_reversible_map_changes<Key: Debug + MaybeSerde, Value: Render, V: LeafValue>(
+	changes: &ReversibleMapChanges<Key, Value>,
+	path: &mut ChangePath<V>,
+	leaves: &mut Vec<Leaf<V>>,
+) {
+	for change in changes {
+		let key = PathSegment::Key(V::of(reversible_map_change_key(change)));
+		match change {
+			ReversibleMapChange::Added(_, desc) => push_leaf(Some(key), LeafChange::Added(V::of(desc)), path, leaves),
+			ReversibleMapChange::Changed(_, change) => render_at::<Value, _>(change, key, path, leaves),
+			ReversibleMapChange::Removed(_, desc) => {
+				push_leaf(Some(key), LeafChange::Removed(Some(V::of(desc))), path, leaves)
+			}
+		}
+	}
+}
@@ -1,5 +1,20 @@
 // use serde;
 
+use std::cmp::Ordering;
+use std::fmt;
+use std::num::{
+	NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32,
+	NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+};
+use std::ops::Neg;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{merge_whole, Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{render_replaced, ChangePath, Leaf, LeafValue, Render};
 use crate::types::{Changed, Comparable};
 
 impl Comparable for () {
@@ -281,6 +296,14 @@ impl Comparable for f32 {
 			Changed::Unchanged
 		}
 	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		if !options.float_tolerance.eq_f32(*self, *other) {
+			Changed::Changed(F32Change(*self, *other))
+		} else {
+			Changed::Unchanged
+		}
+	}
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -304,6 +327,14 @@ impl Comparable for f64 {
 			Changed::Unchanged
 		}
 	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		if !options.float_tolerance.eq_f64(*self, *other) {
+			Changed::Changed(F64Change(*self, *other))
+		} else {
+			Changed::Unchanged
+		}
+	}
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -327,3 +358,542 @@ impl Comparable for char {
 		}
 	}
 }
+
+impl Invert for () {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		Ok(change)
+	}
+}
+
+impl Compose for () {
+	fn compose(_first: Self::Change, _second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		Ok(Changed::Unchanged)
+	}
+}
+
+impl Render for () {
+	fn render_change<V: LeafValue>(_change: &Self::Change, _path: &mut ChangePath<V>, _leaves: &mut Vec<Leaf<V>>) {}
+}
+
+impl Merge for () {
+	fn merge_changes(left: Self::Change, _right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		Ok(left)
+	}
+}
+
+impl Patch for () {
+	fn apply(&mut self, _change: Self::Change) -> Result<(), PatchError> {
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		Ok(desc)
+	}
+}
+
+macro_rules! impl_patch_for_scalar {
+	($t:ty, $change:ident) => {
+		impl Patch for $t {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				let $change(old, new) = change;
+				expect_value(self, &old)?;
+				*self = new;
+				Ok(())
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				Ok(desc)
+			}
+		}
+
+		impl Invert for $t {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				let $change(old, new) = change;
+				Ok($change(new, old))
+			}
+		}
+
+		impl Merge for $t {
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				merge_whole(left, right)
+			}
+		}
+
+		impl Compose for $t {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				let ($change(old, middle), $change(expected, new)) = (first, second);
+				expect_value(&middle, &expected)?;
+				Ok(old.comparison(&new))
+			}
+		}
+
+		impl Render for $t {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				let $change(old, new) = change;
+				render_replaced(old, new, path, leaves);
+			}
+		}
+	};
+}
+
+impl_patch_for_scalar!(bool, BoolChange);
+impl_patch_for_scalar!(u8, U8Change);
+impl_patch_for_scalar!(i8, I8Change);
+impl_patch_for_scalar!(u16, U16Change);
+impl_patch_for_scalar!(i16, I16Change);
+impl_patch_for_scalar!(u32, U32Change);
+impl_patch_for_scalar!(i32, I32Change);
+impl_patch_for_scalar!(u64, U64Change);
+impl_patch_for_scalar!(i64, I64Change);
+impl_patch_for_scalar!(usize, UsizeChange);
+impl_patch_for_scalar!(isize, IsizeChange);
+impl_patch_for_scalar!(char, CharChange);
+
+macro_rules! impl_nonzero {
+	($($t:ident: $change:ident),*) => {$(
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+		#[derive(PartialEq, Debug)]
+		pub struct $change(pub $t, pub $t);
+
+		impl Comparable for $t {
+			type Desc = $t;
+
+			fn describe(&self) -> Self::Desc {
+				*self
+			}
+
+			type Change = $change;
+
+			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				if self != other {
+					Changed::Changed($change(*self, *other))
+				} else {
+					Changed::Unchanged
+				}
+			}
+		}
+
+		impl_patch_for_scalar!($t, $change);
+	)*};
+}
+
+impl_nonzero!(
+	NonZeroU8: NonZeroU8Change,
+	NonZeroI8: NonZeroI8Change,
+	NonZeroU16: NonZeroU16Change,
+	NonZeroI16: NonZeroI16Change,
+	NonZeroU32: NonZeroU32Change,
+	NonZeroI32: NonZeroI32Change,
+	NonZeroU64: NonZeroU64Change,
+	NonZeroI64: NonZeroI64Change,
+	NonZeroU128: NonZeroU128Change,
+	NonZeroI128: NonZeroI128Change,
+	NonZeroUsize: NonZeroUsizeChange,
+	NonZeroIsize: NonZeroIsizeChange
+);
+
+// An ordering is described by its value as a number, -1, 0 or 1, since serde
+// has no representation of its own for one.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Debug)]
+pub struct OrderingChange(
+	#[cfg_attr(feature = "serde", serde(with = "ordering_serde"))] pub Ordering,
+	#[cfg_attr(feature = "serde", serde(with = "ordering_serde"))] pub Ordering,
+);
+
+fn ordering_from_desc(desc: i8) -> Result<Ordering, PatchError> {
+	match desc {
+		-1 => Ok(Ordering::Less),
+		0 => Ok(Ordering::Equal),
+		1 => Ok(Ordering::Greater),
+		_ => Err(PatchError::Unsupported(format!("{} does not describe an ordering", desc))),
+	}
+}
+
+#[cfg(feature = "serde")]
+mod ordering_serde {
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+	use std::cmp::Ordering;
+
+	pub fn serialize<S: Serializer>(ordering: &Ordering, serializer: S) -> Result<S::Ok, S::Error> {
+		(*ordering as i8).serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ordering, D::Error> {
+		super::ordering_from_desc(i8::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+	}
+}
+
+impl Comparable for Ordering {
+	type Desc = i8;
+
+	fn describe(&self) -> Self::Desc {
+		*self as i8
+	}
+
+	type Change = OrderingChange;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		if self != other {
+			Changed::Changed(OrderingChange(*self, *other))
+		} else {
+			Changed::Unchanged
+		}
+	}
+}
+
+impl Patch for Ordering {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let OrderingChange(old, new) = change;
+		expect_value(self, &old)?;
+		*self = new;
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		ordering_from_desc(desc)
+	}
+}
+
+impl Invert for Ordering {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		let OrderingChange(old, new) = change;
+		Ok(OrderingChange(new, old))
+	}
+}
+
+impl Merge for Ordering {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_whole(left, right)
+	}
+}
+
+impl Compose for Ordering {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		let (OrderingChange(old, middle), OrderingChange(expected, new)) = (first, second);
+		expect_value(&middle, &expected)?;
+		Ok(old.comparison(&new))
+	}
+}
+
+impl Render for Ordering {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		let OrderingChange(old, new) = change;
+		render_replaced(&old.describe(), &new.describe(), path, leaves);
+	}
+}
+
+// A wrapping number is compared as the number it wraps.
+impl<T: Comparable> Comparable for Wrapping<T> {
+	type Desc = T::Desc;
+
+	fn describe(&self) -> Self::Desc {
+		self.0.describe()
+	}
+
+	type Change = T::Change;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		self.0.comparison_with(&other.0, options)
+	}
+}
+
+impl<T: Patch> Patch for Wrapping<T> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		self.0.apply(change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		T::from_desc(desc).map(Wrapping)
+	}
+}
+
+impl<T: Invert> Invert for Wrapping<T> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		T::invert(change)
+	}
+}
+
+impl<T: Merge> Merge for Wrapping<T> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		T::merge_changes(left, right)
+	}
+}
+
+impl<T: Compose> Compose for Wrapping<T> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		T::compose(first, second)
+	}
+}
+
+impl<T: Render> Render for Wrapping<T> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		T::render_change(change, path, leaves)
+	}
+}
+
+// NaN never equals itself, and so always compares as changed; a NaN recorded
+// as the old value matches a current NaN.
+macro_rules! impl_patch_for_float {
+	($t:ty, $change:ident) => {
+		impl Patch for $t {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				let $change(old, new) = change;
+				if !(old.is_nan() && self.is_nan()) {
+					expect_value(self, &old)?;
+				}
+				*self = new;
+				Ok(())
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				Ok(desc)
+			}
+		}
+
+		impl Invert for $t {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				let $change(old, new) = change;
+				Ok($change(new, old))
+			}
+		}
+
+		impl Merge for $t {
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				merge_whole(left, right)
+			}
+		}
+
+		impl Compose for $t {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				let ($change(old, middle), $change(expected, new)) = (first, second);
+				if !(middle.is_nan() && expected.is_nan()) {
+					expect_value(&middle, &expected)?;
+				}
+				Ok(old.comparison(&new))
+			}
+		}
+
+		impl Render for $t {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				let $change(old, new) = change;
+				render_replaced(old, new, path, leaves);
+			}
+		}
+	};
+}
+
+impl_patch_for_float!(f32, F32Change);
+impl_patch_for_float!(f64, F64Change);
+
+/// The signed difference between two points in time or two durations, which
+/// displays with its sign, as `+5ms` or `-5ms`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeDelta {
+	negative: bool,
+	magnitude: Duration,
+}
+
+impl TimeDelta {
+	/// A difference forward in time by `magnitude`.
+	pub fn positive(magnitude: Duration) -> Self {
+		TimeDelta { negative: false, magnitude }
+	}
+
+	/// A difference backward in time by `magnitude`.
+	pub fn negative(magnitude: Duration) -> Self {
+		TimeDelta { negative: !magnitude.is_zero(), magnitude }
+	}
+
+	/// Whether the difference is backward in time.
+	pub fn is_negative(&self) -> bool {
+		self.negative
+	}
+
+	/// The absolute value of the difference.
+	pub fn magnitude(&self) -> Duration {
+		self.magnitude
+	}
+
+	fn between<T: Elapsed>(old: &T, new: &T) -> Self {
+		if new >= old {
+			TimeDelta::positive(new.since(old))
+		} else {
+			TimeDelta::negative(old.since(new))
+		}
+	}
+}
+
+impl Neg for TimeDelta {
+	type Output = TimeDelta;
+
+	fn neg(self) -> TimeDelta {
+		TimeDelta { negative: !self.negative && !self.magnitude.is_zero(), magnitude: self.magnitude }
+	}
+}
+
+impl fmt::Display for TimeDelta {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}{:?}", if self.negative { '-' } else { '+' }, self.magnitude)
+	}
+}
+
+impl fmt::Debug for TimeDelta {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "TimeDelta({})", self)
+	}
+}
+
+// The time from a later value back to an earlier one.
+trait Elapsed: PartialOrd {
+	fn since(&self, earlier: &Self) -> Duration;
+}
+
+impl Elapsed for Duration {
+	fn since(&self, earlier: &Self) -> Duration {
+		*self - *earlier
+	}
+}
+
+impl Elapsed for SystemTime {
+	fn since(&self, earlier: &Self) -> Duration {
+		self.duration_since(*earlier).unwrap_or_default()
+	}
+}
+
+impl Elapsed for Instant {
+	fn since(&self, earlier: &Self) -> Duration {
+		self.duration_since(*earlier)
+	}
+}
+
+/// An `Instant`, as the description of one. Instants only mean something to
+/// the process that made them, so with the `serde` feature one is written as
+/// its distance from an instant fixed the first time the process writes or
+/// reads one, and can only be read back by the same process.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct InstantDesc(#[cfg_attr(feature = "serde", serde(with = "instant_serde"))] pub Instant);
+
+impl From<Instant> for InstantDesc {
+	fn from(instant: Instant) -> Self {
+		InstantDesc(instant)
+	}
+}
+
+impl From<InstantDesc> for Instant {
+	fn from(desc: InstantDesc) -> Self {
+		desc.0
+	}
+}
+
+#[cfg(feature = "serde")]
+mod instant_serde {
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+	use std::sync::OnceLock;
+	use std::time::Instant;
+
+	use super::TimeDelta;
+
+	fn anchor() -> Instant {
+		static ANCHOR: OnceLock<Instant> = OnceLock::new();
+		*ANCHOR.get_or_init(Instant::now)
+	}
+
+	pub fn serialize<S: Serializer>(instant: &Instant, serializer: S) -> Result<S::Ok, S::Error> {
+		TimeDelta::between(&anchor(), instant).serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Instant, D::Error> {
+		let delta = TimeDelta::deserialize(deserializer)?;
+		let instant = if delta.is_negative() {
+			anchor().checked_sub(delta.magnitude())
+		} else {
+			anchor().checked_add(delta.magnitude())
+		};
+		instant.ok_or_else(|| serde::de::Error::custom("instant out of range"))
+	}
+}
+
+// Times are compared within the tolerance of the comparison options, and their
+// changes record how far the value moved, as well as where from and to.
+macro_rules! impl_time {
+	($t:ty, $change:ident, $desc:ty) => {
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+		#[derive(PartialEq, Debug)]
+		pub struct $change(pub $desc, pub $desc, pub TimeDelta);
+
+		impl Comparable for $t {
+			type Desc = $desc;
+
+			fn describe(&self) -> Self::Desc {
+				(*self).into()
+			}
+
+			type Change = $change;
+
+			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				self.comparison_with(other, &CompareOptions::default())
+			}
+
+			fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+				let delta = TimeDelta::between(self, other);
+				if delta.magnitude() <= options.time_tolerance {
+					Changed::Unchanged
+				} else {
+					Changed::Changed($change((*self).into(), (*other).into(), delta))
+				}
+			}
+		}
+
+		impl Patch for $t {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				let $change(old, new, _) = change;
+				expect_value(&self.describe(), &old)?;
+				*self = new.into();
+				Ok(())
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				Ok(desc.into())
+			}
+		}
+
+		impl Invert for $t {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				let $change(old, new, delta) = change;
+				Ok($change(new, old, -delta))
+			}
+		}
+
+		impl Merge for $t {
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				merge_whole(left, right)
+			}
+		}
+
+		impl Compose for $t {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				let ($change(old, middle, _), $change(expected, new, _)) = (first, second);
+				expect_value(&middle, &expected)?;
+				Ok(<$t>::from(old).comparison(&new.into()))
+			}
+		}
+
+		impl Render for $t {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				let $change(old, new, _) = change;
+				render_replaced(old, new, path, leaves);
+			}
+		}
+	};
+}
+
+impl_time!(Duration, DurationChange, Duration);
+impl_time!(SystemTime, SystemTimeChange, SystemTime);
+impl_time!(Instant, InstantChange, InstantDesc);
@@ -1,8 +1,14 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::Debug;
 // use serde;
 
-use crate::types::{Changed, Comparable};
+use crate::compose::{apply_to_desc, compose_changed, out_of_sequence, revert_desc, Compose};
+use crate::invert::{invert_changed, Invert};
+use crate::merge::{merge_part, Conflict, Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{push_leaf, render_at, ChangePath, Leaf, LeafChange, LeafValue, PathSegment, Render};
+use crate::types::{Changed, Comparable, MaybeSerde};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Debug)]
@@ -10,9 +16,17 @@ pub enum VecChange<Desc, Change> {
 	Added(usize, Desc),
 	Changed(usize, Change),
 	Removed(usize, Desc),
+	/// An element that moved from one index in the original sequence to
+	/// another in the new one, together with any change made to it. Only
+	/// reported by comparisons that align the two sequences, like `Aligned`.
+	Moved {
+		from: usize,
+		to: usize,
+		change: Changed<Change>,
+	},
 }
 
-impl<Value: PartialEq + Comparable> Comparable for Vec<Value> {
+impl<Value: PartialEq + Comparable> Comparable for [Value] {
 	type Desc = Vec<Value::Desc>;
 
 	fn describe(&self) -> Self::Desc {
@@ -22,19 +36,27 @@ impl<Value: PartialEq + Comparable> Comparable for Vec<Value> {
 	type Change = Vec<VecChange<Value::Desc, Value::Change>>;
 
 	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
 		let mut changes = Vec::new();
 		let other_len = other.len();
 		for i in 0..self.len() {
 			if i >= other_len {
-				changes.push(VecChange::Removed(i, self[i].describe()));
-			} else if let Changed::Changed(change) = self[i].comparison(&other[i]) {
+				if !options.ignores(PathSegment::Index(i)) {
+					changes.push(VecChange::Removed(i, self[i].describe()));
+				}
+			} else if let Changed::Changed(change) = options.compare_at(PathSegment::Index(i), &self[i], &other[i]) {
 				changes.push(VecChange::Changed(i, change));
 			}
 		}
 		if other.len() > self.len() {
 			#[allow(clippy::needless_range_loop)]
 			for i in self.len()..other.len() {
-				changes.push(VecChange::Added(i, other[i].describe()));
+				if !options.ignores(PathSegment::Index(i)) {
+					changes.push(VecChange::Added(i, other[i].describe()));
+				}
 			}
 		}
 		if changes.is_empty() {
@@ -45,6 +67,552 @@ impl<Value: PartialEq + Comparable> Comparable for Vec<Value> {
 	}
 }
 
+impl<Value: PartialEq + Comparable> Comparable for Vec<Value> {
+	type Desc = Vec<Value::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		self.as_slice().describe()
+	}
+
+	type Change = Vec<VecChange<Value::Desc, Value::Change>>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		self.as_slice().comparison_with(other.as_slice(), options)
+	}
+}
+
+impl<Value: PartialEq + Patch> Patch for Vec<Value> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		apply_vec_changes(self, change)
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(Value::from_desc).collect()
+	}
+}
+
+impl<Value: PartialEq + Invert> Invert for Vec<Value> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_vec_changes::<Value>(change)
+	}
+}
+
+impl<Value: PartialEq + Merge> Merge for Vec<Value> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_vec_changes::<Value>(left, right)
+	}
+}
+
+impl<Value: PartialEq + Compose> Compose for Vec<Value> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_vec_changes::<Value>(first, second)
+	}
+}
+
+impl<Value: PartialEq + Render> Render for Vec<Value> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_vec_changes::<Value, _>(change, path, leaves)
+	}
+}
+
+impl<Value: PartialEq + Invert> Invert for [Value] {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_vec_changes::<Value>(change)
+	}
+}
+
+impl<Value: PartialEq + Merge> Merge for [Value] {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_vec_changes::<Value>(left, right)
+	}
+}
+
+impl<Value: PartialEq + Render> Render for [Value] {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_vec_changes::<Value, _>(change, path, leaves)
+	}
+}
+
+impl<Value: PartialEq + Comparable> Comparable for &[Value] {
+	type Desc = Vec<Value::Desc>;
+
+	fn describe(&self) -> Self::Desc {
+		(**self).describe()
+	}
+
+	type Change = Vec<VecChange<Value::Desc, Value::Change>>;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		self.comparison_with(other, &CompareOptions::default())
+	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		(**self).comparison_with(*other, options)
+	}
+}
+
+// A borrowed slice can be compared, so that types holding one can derive
+// `Comparable`, but not changed or rebuilt.
+impl<Value: PartialEq + Comparable> Patch for &[Value] {
+	fn apply(&mut self, _change: Self::Change) -> Result<(), PatchError> {
+		Err(PatchError::Unsupported("a borrowed slice cannot be changed".to_string()))
+	}
+
+	fn from_desc(_desc: Self::Desc) -> Result<Self, PatchError> {
+		Err(PatchError::Unsupported("a borrowed slice cannot be built from its description".to_string()))
+	}
+}
+
+impl<Value: PartialEq + Invert> Invert for &[Value] {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		invert_vec_changes::<Value>(change)
+	}
+}
+
+impl<Value: PartialEq + Merge> Merge for &[Value] {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_vec_changes::<Value>(left, right)
+	}
+}
+
+impl<Value: PartialEq + Compose> Compose for &[Value] {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_vec_changes::<Value>(first, second)
+	}
+}
+
+impl<Value: PartialEq + Render> Render for &[Value] {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_vec_changes::<Value, _>(change, path, leaves)
+	}
+}
+
+/// Render a list of `VecChange`s. Like the changes themselves, an element that
+/// moved is found at its index in the original vector.
+pub(crate) fn render_vec_changes<Value: Render, V: LeafValue>(
+	changes: &VecChanges<Value>,
+	path: &mut ChangePath<V>,
+	leaves: &mut Vec<Leaf<V>>,
+) {
+	for change in changes {
+		match change {
+			VecChange::Added(i, desc) => {
+				push_leaf(Some(PathSegment::Index(*i)), LeafChange::Added(V::of(desc)), path, leaves)
+			}
+			VecChange::Changed(i, change) => render_at::<Value, _>(change, PathSegment::Index(*i), path, leaves),
+			VecChange::Removed(i, desc) => {
+				push_leaf(Some(PathSegment::Index(*i)), LeafChange::Removed(Some(V::of(desc))), path, leaves)
+			}
+			VecChange::Moved { from, to, change } => {
+				push_leaf(Some(PathSegment::Index(*from)), LeafChange::Moved(*from, *to), path, leaves);
+				if let Changed::Changed(change) = change {
+					render_at::<Value, _>(change, PathSegment::Index(*from), path, leaves);
+				}
+			}
+		}
+	}
+}
+
+/// Apply a list of `VecChange`s to a vector, reading the indices of `Removed`,
+/// `Changed` and the source of `Moved` as indices into the original vector,
+/// and those of `Added` and the destination of `Moved` as indices into the
+/// new one.
+pub(crate) fn apply_vec_changes<Value: Patch>(
+	values: &mut Vec<Value>,
+	changes: Vec<VecChange<Value::Desc, Value::Change>>,
+) -> Result<(), PatchError> {
+	rearrange(
+		values,
+		changes,
+		|value, change| value.apply(change),
+		|value, desc| expect_value(&value.describe(), &desc),
+		Value::from_desc,
+	)
+}
+
+enum Removal<Desc> {
+	Removed(Desc),
+	Moved(usize),
+}
+
+// Carry out a list of `VecChange`s as `apply_vec_changes` does, but leaving
+// what to do with each element to the caller: `change` is called on every
+// element changed in place or moved, `remove` on every element removed, and
+// `add` to make every element added.
+fn rearrange<T, Desc, Change>(
+	values: &mut Vec<T>,
+	changes: Vec<VecChange<Desc, Change>>,
+	mut change: impl FnMut(&mut T, Change) -> Result<(), PatchError>,
+	mut remove: impl FnMut(T, Desc) -> Result<(), PatchError>,
+	mut add: impl FnMut(Desc) -> Result<T, PatchError>,
+) -> Result<(), PatchError> {
+	let missing = |i: usize| PatchError::Mismatch(format!("no element at index {}", i));
+
+	// First change elements in place, while every index still refers to the
+	// original vector; then take out everything that was removed or moved,
+	// and finally put the added and moved elements in their new places.
+	let mut removals = Vec::new();
+	let mut insertions = Vec::new();
+	for vec_change in changes {
+		match vec_change {
+			VecChange::Changed(i, c) => change(values.get_mut(i).ok_or_else(|| missing(i))?, c)?,
+			VecChange::Removed(i, desc) => {
+				if i >= values.len() {
+					return Err(missing(i));
+				}
+				removals.push((i, Removal::Removed(desc)));
+			}
+			VecChange::Moved { from, to, change: c } => {
+				let value = values.get_mut(from).ok_or_else(|| missing(from))?;
+				if let Changed::Changed(c) = c {
+					change(value, c)?;
+				}
+				removals.push((from, Removal::Moved(to)));
+			}
+			VecChange::Added(j, desc) => insertions.push((j, add(desc)?)),
+		}
+	}
+
+	removals.sort_by_key(|&(i, _)| i);
+	if removals.windows(2).any(|w| w[0].0 == w[1].0) {
+		return Err(PatchError::Mismatch("an element is removed more than once".to_string()));
+	}
+	for (i, removal) in removals.into_iter().rev() {
+		let value = values.remove(i);
+		match removal {
+			Removal::Removed(desc) => remove(value, desc)?,
+			Removal::Moved(j) => insertions.push((j, value)),
+		}
+	}
+
+	insertions.sort_by_key(|&(j, _)| j);
+	for (j, value) in insertions {
+		if j > values.len() {
+			return Err(PatchError::Mismatch(format!("cannot insert at index {}", j)));
+		}
+		values.insert(j, value);
+	}
+	Ok(())
+}
+
+pub(crate) type VecChanges<Value> = Vec<VecChange<<Value as Comparable>::Desc, <Value as Comparable>::Change>>;
+
+/// Invert a list of `VecChange`s, read as `apply_vec_changes` reads them.
+/// Removals and additions trade places, as do the two ends of a move; the
+/// index of an element changed in place must be translated from the original
+/// vector to the new one, and it is found by counting: the elements that stay
+/// keep their relative order, and fill the places of the new vector not taken
+/// by added or moved elements.
+pub(crate) fn invert_vec_changes<Value: Invert + ?Sized>(
+	changes: VecChanges<Value>,
+) -> Result<VecChanges<Value>, PatchError> {
+	let mut taken_from = Vec::new();
+	let mut taken_to = Vec::new();
+	for change in &changes {
+		match *change {
+			VecChange::Removed(i, _) => taken_from.push(i),
+			VecChange::Added(j, _) => taken_to.push(j),
+			VecChange::Moved { from, to, .. } => {
+				taken_from.push(from);
+				taken_to.push(to);
+			}
+			VecChange::Changed(..) => {}
+		}
+	}
+	taken_to.sort_unstable();
+	let new_index = |i: usize| {
+		let mut rank = i - taken_from.iter().filter(|&&k| k < i).count();
+		let mut j = 0;
+		loop {
+			if taken_to.binary_search(&j).is_err() {
+				if rank == 0 {
+					return j;
+				}
+				rank -= 1;
+			}
+			j += 1;
+		}
+	};
+
+	changes
+		.into_iter()
+		.map(|change| {
+			Ok(match change {
+				VecChange::Added(j, desc) => VecChange::Removed(j, desc),
+				VecChange::Removed(i, desc) => VecChange::Added(i, desc),
+				VecChange::Changed(i, change) => VecChange::Changed(new_index(i), Value::invert(change)?),
+				VecChange::Moved { from, to, change } => {
+					VecChange::Moved { from: to, to: from, change: invert_changed::<Value>(change)? }
+				}
+			})
+		})
+		.collect()
+}
+
+/// Merge two lists of `VecChange`s, read as `apply_vec_changes` reads them.
+/// Elements changed in place by both sides have their changes merged. Adding,
+/// removing or moving elements shifts the others, so those changes are only
+/// kept if just one side makes them, or both make the same ones; an element
+/// removed by one side conflicts with any change the other makes to it.
+pub(crate) fn merge_vec_changes<Value: Merge + ?Sized>(
+	left: VecChanges<Value>,
+	right: VecChanges<Value>,
+) -> Result<VecChanges<Value>, Conflicts<VecChanges<Value>>> {
+	let (mut left_in_place, left_structure) = split_in_place::<Value>(left);
+	let (mut right_in_place, right_structure) = split_in_place::<Value>(right);
+	let mut conflicts = Vec::new();
+
+	let structure = if right_structure.is_empty() || left_structure == right_structure {
+		settle_in_place::<Value>(left_structure, &mut right_in_place, &mut conflicts, true)
+	} else if left_structure.is_empty() {
+		settle_in_place::<Value>(right_structure, &mut left_in_place, &mut conflicts, false)
+	} else {
+		conflicts.push(Conflict { left: left_structure, right: right_structure });
+		Vec::new()
+	};
+
+	let mut in_place = BTreeMap::new();
+	for (i, left) in left_in_place {
+		let right = right_in_place.remove(&i).into();
+		let change = merge_part::<Value, _>(Changed::Changed(left), right, &mut conflicts, |change| {
+			vec![VecChange::Changed(i, change)]
+		});
+		in_place.extend(change.map(|change| (i, change)));
+	}
+	in_place.append(&mut right_in_place);
+
+	let mut merged: VecChanges<Value> = in_place.into_iter().map(|(i, change)| VecChange::Changed(i, change)).collect();
+	merged.extend(structure);
+
+	if conflicts.is_empty() {
+		Ok(merged)
+	} else {
+		Err(Conflicts(conflicts))
+	}
+}
+
+// Separate the elements changed in place from the changes that add, remove or
+// move elements.
+fn split_in_place<Value: Comparable + ?Sized>(
+	changes: VecChanges<Value>,
+) -> (BTreeMap<usize, Value::Change>, VecChanges<Value>) {
+	let mut in_place = BTreeMap::new();
+	let mut structure = Vec::new();
+	for change in changes {
+		match change {
+			VecChange::Changed(i, change) => {
+				in_place.insert(i, change);
+			}
+			change => structure.push(change),
+		}
+	}
+	(in_place, structure)
+}
+
+// Reconcile the changes that add, remove or move elements made by one side
+// with the elements changed in place by the other side: changes to removed
+// elements conflict, and changes to moved elements are merged into the move.
+fn settle_in_place<Value: Merge + ?Sized>(
+	structure: VecChanges<Value>,
+	in_place: &mut BTreeMap<usize, Value::Change>,
+	conflicts: &mut Vec<Conflict<VecChanges<Value>>>,
+	structure_is_left: bool,
+) -> VecChanges<Value> {
+	let mut settled = Vec::new();
+	for change in structure {
+		match change {
+			VecChange::Removed(i, desc) if in_place.contains_key(&i) => {
+				let removal = vec![VecChange::Removed(i, desc)];
+				let other = in_place.remove(&i).into_iter().map(|change| VecChange::Changed(i, change)).collect();
+				conflicts.push(if structure_is_left {
+					Conflict { left: removal, right: other }
+				} else {
+					Conflict { left: other, right: removal }
+				});
+			}
+			VecChange::Moved { from, to, change } => {
+				let change = match in_place.remove(&from) {
+					Some(other) => {
+						let (left, right) = if structure_is_left {
+							(change, Changed::Changed(other))
+						} else {
+							(Changed::Changed(other), change)
+						};
+						merge_part::<Value, _>(left, right, conflicts, |change| vec![VecChange::Changed(from, change)])
+					}
+					None => change,
+				};
+				settled.push(VecChange::Moved { from, to, change });
+			}
+			change => settled.push(change),
+		}
+	}
+	settled
+}
+
+// An element of a sequence partway through composing changes to it: either an
+// element of the original sequence, at the given index, along with the change
+// made to it so far, or an element that was added.
+enum Slot<Desc, Change> {
+	Original(usize, Changed<Change>),
+	Added(Desc),
+}
+
+// The shortest vector a list of `VecChange`s applies to, which holds every
+// element it changes, removes or moves, and leaves room for every element it
+// puts in place; along with how many elements shorter the list makes it.
+fn needed_len<Desc, Change>(changes: &[VecChange<Desc, Change>]) -> (isize, isize) {
+	let (mut before, mut after, mut shrinks) = (0, 0, 0);
+	for change in changes {
+		match *change {
+			VecChange::Changed(i, _) => before = before.max(i as isize + 1),
+			VecChange::Removed(i, _) => {
+				before = before.max(i as isize + 1);
+				shrinks += 1;
+			}
+			VecChange::Moved { from, to, .. } => {
+				before = before.max(from as isize + 1);
+				after = after.max(to as isize + 1);
+			}
+			VecChange::Added(j, _) => {
+				after = after.max(j as isize + 1);
+				shrinks -= 1;
+			}
+		}
+	}
+	(before.max(after + shrinks), shrinks)
+}
+
+/// Compose two lists of `VecChange`s, read as `apply_vec_changes` reads them.
+/// Both are carried out in turn on a stand-in for the original vector, whose
+/// elements record where they came from and how they were changed; it only
+/// needs to be as long as both lists require, since elements past those they
+/// touch are never affected. The result is read off from where the
+/// original elements end up: the most that keep their relative order stay,
+/// and the rest moved. An element removed from the same place that another
+/// is added to is reported as changed, as `Vec` itself reports it.
+pub(crate) fn compose_vec_changes<Value: Compose>(
+	first: VecChanges<Value>,
+	second: VecChanges<Value>,
+) -> Result<Changed<VecChanges<Value>>, PatchError> {
+	// The first list must find every element it touches in the original
+	// vector, and the second in the vector the first leaves behind, which
+	// is as much shorter as the first removes more elements than it adds.
+	let (first_len, first_shrinks) = needed_len(&first);
+	let (second_len, _) = needed_len(&second);
+	let len = first_len.max(second_len + first_shrinks).max(0) as usize;
+	let mut slots: Vec<Slot<Value::Desc, Value::Change>> =
+		(0..len).map(|i| Slot::Original(i, Changed::Unchanged)).collect();
+	let mut removed = BTreeMap::new();
+	for changes in IntoIterator::into_iter([first, second]) {
+		rearrange(
+			&mut slots,
+			changes,
+			|slot, change| {
+				*slot = match std::mem::replace(slot, Slot::Original(0, Changed::Unchanged)) {
+					Slot::Original(i, changed) => {
+						Slot::Original(i, compose_changed::<Value>(changed, Changed::Changed(change))?)
+					}
+					Slot::Added(desc) => Slot::Added(apply_to_desc::<Value>(desc, change)?),
+				};
+				Ok(())
+			},
+			|slot, desc| {
+				match slot {
+					Slot::Original(i, Changed::Unchanged) => {
+						removed.insert(i, desc);
+					}
+					Slot::Original(i, Changed::Changed(change)) => {
+						removed.insert(i, revert_desc::<Value>(desc, change)?);
+					}
+					Slot::Added(added) => expect_value(&added, &desc)?,
+				}
+				Ok(())
+			},
+			|desc| Ok(Slot::Added(desc)),
+		)?;
+	}
+
+	let staying = longest_increasing(
+		&slots
+			.iter()
+			.filter_map(|slot| match slot {
+				Slot::Original(i, _) => Some(*i),
+				Slot::Added(_) => None,
+			})
+			.collect::<Vec<_>>(),
+	);
+	// Where an element was removed, and one added, between the same two
+	// elements that stay, the two are paired up in order.
+	let gap = |i: usize| staying.range(..i).count();
+	let mut removed_by_gap: BTreeMap<usize, Vec<(usize, Value::Desc)>> = BTreeMap::new();
+	for (i, desc) in removed {
+		removed_by_gap.entry(gap(i)).or_default().push((i, desc));
+	}
+	for removals in removed_by_gap.values_mut() {
+		removals.reverse();
+	}
+
+	let mut composed = BTreeMap::new();
+	let mut added = Vec::new();
+	let mut stayed = 0;
+	for (j, slot) in slots.into_iter().enumerate() {
+		match slot {
+			Slot::Added(desc) => match removed_by_gap.get_mut(&stayed).and_then(Vec::pop) {
+				Some((i, old)) => {
+					if let Changed::Changed(change) = Value::from_desc(old)?.comparison(&Value::from_desc(desc)?) {
+						composed.insert(i, VecChange::Changed(i, change));
+					}
+				}
+				None => added.push(VecChange::Added(j, desc)),
+			},
+			Slot::Original(i, change) if staying.contains(&i) => {
+				stayed += 1;
+				if let Changed::Changed(change) = change {
+					composed.insert(i, VecChange::Changed(i, change));
+				}
+			}
+			Slot::Original(i, change) => {
+				composed.insert(i, VecChange::Moved { from: i, to: j, change });
+			}
+		}
+	}
+	for (i, desc) in removed_by_gap.into_values().flatten() {
+		composed.insert(i, VecChange::Removed(i, desc));
+	}
+
+	let changes: VecChanges<Value> = composed.into_values().chain(added).collect();
+	Ok(if changes.is_empty() { Changed::Unchanged } else { Changed::Changed(changes) })
+}
+
+// The values making up a longest increasing subsequence of `values`.
+fn longest_increasing(values: &[usize]) -> BTreeSet<usize> {
+	// `tails[k]` is the position of the least value that ends an increasing
+	// subsequence of length `k + 1` found so far.
+	let mut tails: Vec<usize> = Vec::new();
+	let mut previous = vec![None; values.len()];
+	for (p, &value) in values.iter().enumerate() {
+		let k = tails.partition_point(|&t| values[t] < value);
+		previous[p] = k.checked_sub(1).map(|k| tails[k]);
+		if k == tails.len() {
+			tails.push(p);
+		} else {
+			tails[k] = p;
+		}
+	}
+	let mut subsequence = BTreeSet::new();
+	let mut p = tails.last().copied();
+	while let Some(q) = p {
+		subsequence.insert(values[q]);
+		p = previous[q];
+	}
+	subsequence
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Debug)]
 pub enum SetChange<Desc> {
@@ -95,6 +663,57 @@ impl<Value: Ord + Comparable> Comparable for BTreeSet<Value> {
 	}
 }
 
+impl<Value: Ord + Patch> Patch for BTreeSet<Value> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		for change in change {
+			match change {
+				SetChange::Added(desc) => {
+					let value = Value::from_desc(desc)?;
+					if self.contains(&value) {
+						return Err(PatchError::Mismatch(format!("{:?} is already present", value.describe())));
+					}
+					self.insert(value);
+				}
+				SetChange::Removed(desc) => {
+					let value = Value::from_desc(desc)?;
+					if !self.remove(&value) {
+						return Err(PatchError::Mismatch(format!("{:?} is not present", value.describe())));
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(Value::from_desc).collect()
+	}
+}
+
+impl<Value: Ord + Invert> Invert for BTreeSet<Value> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		Ok(invert_set_changes(change))
+	}
+}
+
+impl<Value: Ord + Merge> Merge for BTreeSet<Value> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		Ok(merge_set_changes(left, right))
+	}
+}
+
+impl<Value: Ord + Compose> Compose for BTreeSet<Value> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_set_changes(first, second)
+	}
+}
+
+impl<Value: Ord + Render> Render for BTreeSet<Value> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_set_changes(change, path, leaves)
+	}
+}
+
 impl<Value: std::hash::Hash + Ord + Comparable> Comparable for HashSet<Value> {
 	type Desc = Vec<Value::Desc>;
 
@@ -141,3 +760,118 @@ impl<Value: std::hash::Hash + Ord + Comparable> Comparable for HashSet<Value> {
 		}
 	}
 }
+
+impl<Value: std::hash::Hash + Ord + Patch> Patch for HashSet<Value> {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		for change in change {
+			match change {
+				SetChange::Added(desc) => {
+					let value = Value::from_desc(desc)?;
+					if self.contains(&value) {
+						return Err(PatchError::Mismatch(format!("{:?} is already present", value.describe())));
+					}
+					self.insert(value);
+				}
+				SetChange::Removed(desc) => {
+					let value = Value::from_desc(desc)?;
+					if !self.remove(&value) {
+						return Err(PatchError::Mismatch(format!("{:?} is not present", value.describe())));
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		desc.into_iter().map(Value::from_desc).collect()
+	}
+}
+
+impl<Value: std::hash::Hash + Ord + Invert> Invert for HashSet<Value> {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		Ok(invert_set_changes(change))
+	}
+}
+
+pub(crate) fn invert_set_changes<Desc>(changes: Vec<SetChange<Desc>>) -> Vec<SetChange<Desc>> {
+	changes
+		.into_iter()
+		.map(|change| match change {
+			SetChange::Added(desc) => SetChange::Removed(desc),
+			SetChange::Removed(desc) => SetChange::Added(desc),
+		})
+		.collect()
+}
+
+impl<Value: std::hash::Hash + Ord + Merge> Merge for HashSet<Value> {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		Ok(merge_set_changes(left, right))
+	}
+}
+
+// Both sides start from the same set, so they can only add members it lacks
+// and remove members it has, and never conflict.
+fn merge_set_changes<Desc: PartialEq>(left: Vec<SetChange<Desc>>, right: Vec<SetChange<Desc>>) -> Vec<SetChange<Desc>> {
+	let mut merged = left;
+	for change in right {
+		if !merged.contains(&change) {
+			merged.push(change);
+		}
+	}
+	merged
+}
+
+impl<Value: std::hash::Hash + Ord + Compose> Compose for HashSet<Value> {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		compose_set_changes(first, second)
+	}
+}
+
+impl<Value: std::hash::Hash + Ord + Render> Render for HashSet<Value> {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		render_set_changes(change, path, leaves)
+	}
+}
+
+// A member added by one list and removed by the other cancels out; the same
+// member added or removed twice means the lists were not made in sequence.
+fn compose_set_changes<Desc: PartialEq + Debug>(
+	first: Vec<SetChange<Desc>>,
+	second: Vec<SetChange<Desc>>,
+) -> Result<Changed<Vec<SetChange<Desc>>>, PatchError> {
+	let mut composed = first;
+	for change in second {
+		if let Some(earlier) = composed.iter().position(|earlier| set_member(earlier) == set_member(&change)) {
+			if composed[earlier] == change {
+				return Err(out_of_sequence(&composed[earlier], &change));
+			}
+			composed.remove(earlier);
+		} else {
+			composed.push(change);
+		}
+	}
+	Ok(if composed.is_empty() { Changed::Unchanged } else { Changed::Changed(composed) })
+}
+
+fn set_member<Desc>(change: &SetChange<Desc>) -> &Desc {
+	match change {
+		SetChange::Added(desc) | SetChange::Removed(desc) => desc,
+	}
+}
+
+// The members of a set have no path of their own, so they are reported as
+// added to or removed from the set itself.
+pub(crate) fn render_set_changes<Desc: Debug + MaybeSerde, V: LeafValue>(
+	changes: &[SetChange<Desc>],
+	path: &ChangePath<V>,
+	leaves: &mut Vec<Leaf<V>>,
+) {
+	for change in changes {
+		let change = match change {
+			SetChange::Added(desc) => LeafChange::Added(V::of(desc)),
+			SetChange::Removed(desc) => LeafChange::Removed(Some(V::of(desc))),
+		};
+		push_leaf(None, change, path, leaves);
+	}
+}
@@ -1,3 +1,14 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::align::{edit_script, Edit};
+use crate::compose::Compose;
+use crate::invert::Invert;
+use crate::merge::{merge_whole, Conflict, Conflicts, Merge};
+use crate::patch::{expect_value, Patch, PatchError};
+use crate::render::{push_leaf, render_replaced, ChangePath, Leaf, LeafChange, LeafValue, Render};
 use crate::types::{Changed, Comparable};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -22,6 +33,47 @@ impl Comparable for String {
 	}
 }
 
+impl Patch for String {
+	fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+		let StringChange(old, new) = change;
+		expect_value(self, &old)?;
+		*self = new;
+		Ok(())
+	}
+
+	fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+		Ok(desc)
+	}
+}
+
+impl Invert for String {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		let StringChange(old, new) = change;
+		Ok(StringChange(new, old))
+	}
+}
+
+impl Merge for String {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_whole(left, right)
+	}
+}
+
+impl Compose for String {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		let (StringChange(old, middle), StringChange(expected, new)) = (first, second);
+		expect_value(&middle, &expected)?;
+		Ok(old.comparison(&new))
+	}
+}
+
+impl Render for String {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		let StringChange(old, new) = change;
+		render_replaced(old, new, path, leaves);
+	}
+}
+
 impl Comparable for &str {
 	type Desc = <String as Comparable>::Desc;
 
@@ -39,3 +91,392 @@ impl Comparable for &str {
 		}
 	}
 }
+
+// A borrowed string can be compared, so that types holding one can derive
+// `Patch`, but not changed or rebuilt.
+impl Patch for &str {
+	fn apply(&mut self, _change: Self::Change) -> Result<(), PatchError> {
+		Err(PatchError::Unsupported("a borrowed string cannot be changed".to_string()))
+	}
+
+	fn from_desc(_desc: Self::Desc) -> Result<Self, PatchError> {
+		Err(PatchError::Unsupported("a borrowed string cannot be built from its description".to_string()))
+	}
+}
+
+impl Invert for &str {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		String::invert(change)
+	}
+}
+
+impl Merge for &str {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_whole(left, right)
+	}
+}
+
+impl Compose for &str {
+	fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+		String::compose(first, second)
+	}
+}
+
+impl Render for &str {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		String::render_change(change, path, leaves)
+	}
+}
+
+impl Comparable for str {
+	type Desc = <String as Comparable>::Desc;
+
+	fn describe(&self) -> Self::Desc {
+		self.to_string()
+	}
+
+	type Change = <String as Comparable>::Change;
+
+	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+		if self != other {
+			Changed::Changed(StringChange(self.to_string(), other.to_string()))
+		} else {
+			Changed::Unchanged
+		}
+	}
+}
+
+impl Invert for str {
+	fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+		String::invert(change)
+	}
+}
+
+impl Merge for str {
+	fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+		merge_whole(left, right)
+	}
+}
+
+impl Render for str {
+	fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+		String::render_change(change, path, leaves)
+	}
+}
+
+/// A string whose changes are computed grapheme by grapheme, as a list of
+/// [`StringSpan`]s, rather than as the pair of the old and the new string that
+/// `String` reports. Rendered, the changes mark the deleted and inserted text
+/// in place, as `git diff --word-diff` does:
+///
+/// ```
+/// # use comparable::*;
+/// let old = Graphemes("Jon Smith".to_string());
+/// let new = Graphemes("John Smyth".to_string());
+/// assert_eq!(render::<Graphemes<String>>(&old.comparison(&new)).to_string(), "Jo{+h+}n Sm[-i-]{+y+}th");
+/// ```
+///
+/// The same comparison can be selected for a single field of a derived type
+/// with the `#[comparable_graphemes]` attribute.
+#[repr(transparent)]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Graphemes<T: ?Sized>(pub T);
+
+/// A string whose changes are computed word by word, as a list of
+/// [`StringSpan`]s. Words are split at Unicode word boundaries, so that spaces
+/// and punctuation are words of their own:
+///
+/// ```
+/// # use comparable::*;
+/// let old = Words("The quick brown fox".to_string());
+/// let new = Words("The slow brown fox".to_string());
+/// assert_eq!(render::<Words<String>>(&old.comparison(&new)).to_string(), "The [-quick-]{+slow+} brown fox");
+/// ```
+///
+/// The same comparison can be selected for a single field of a derived type
+/// with the `#[comparable_words]` attribute.
+#[repr(transparent)]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Words<T: ?Sized>(pub T);
+
+/// A span of a string compared by [`Graphemes`] or [`Words`]: text that both
+/// the old and the new string have, or that was deleted from the old one or
+/// inserted into the new one. A change lists the spans of the whole string,
+/// with any deleted text before the text inserted in its place.
+///
+/// A span displays as its text, with deleted text wrapped in `[-` and `-]`,
+/// and inserted text in `{+` and `+}`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum StringSpan {
+	Equal(String),
+	Delete(String),
+	Insert(String),
+}
+
+impl fmt::Display for StringSpan {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			StringSpan::Equal(text) => write!(f, "{}", text),
+			StringSpan::Delete(text) => write!(f, "[-{}-]", text),
+			StringSpan::Insert(text) => write!(f, "{{+{}+}}", text),
+		}
+	}
+}
+
+macro_rules! impl_string_spans {
+	($wrapper:ident, $tokens:expr) => {
+		impl<T: ?Sized> $wrapper<T> {
+			/// View a reference to a value as a reference to a wrapped value.
+			pub fn from_ref(value: &T) -> &Self {
+				// SAFETY: the wrapper is `repr(transparent)` over `T`.
+				unsafe { &*(value as *const T as *const Self) }
+			}
+
+			/// View a mutable reference to a value as a mutable reference to a
+			/// wrapped value.
+			pub fn from_mut(value: &mut T) -> &mut Self {
+				// SAFETY: the wrapper is `repr(transparent)` over `T`.
+				unsafe { &mut *(value as *mut T as *mut Self) }
+			}
+		}
+
+		impl<T> From<T> for $wrapper<T> {
+			fn from(value: T) -> Self {
+				$wrapper(value)
+			}
+		}
+
+		impl<T: ?Sized> Deref for $wrapper<T> {
+			type Target = T;
+
+			fn deref(&self) -> &T {
+				&self.0
+			}
+		}
+
+		impl<T: ?Sized> DerefMut for $wrapper<T> {
+			fn deref_mut(&mut self) -> &mut T {
+				&mut self.0
+			}
+		}
+
+		impl Comparable for $wrapper<String> {
+			type Desc = String;
+
+			fn describe(&self) -> Self::Desc {
+				self.0.clone()
+			}
+
+			type Change = Vec<StringSpan>;
+
+			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				span_comparison(&self.0, &other.0, $tokens)
+			}
+		}
+
+		impl Patch for $wrapper<String> {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				apply_spans(&mut self.0, change)
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				Ok($wrapper(desc))
+			}
+		}
+
+		impl Invert for $wrapper<String> {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				Ok(invert_spans(change))
+			}
+		}
+
+		impl Merge for $wrapper<String> {
+			/// Changes are merged as long as they do not touch the same text,
+			/// nor insert text at the same place, unless both sides made the
+			/// same change.
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				merge_spans(left, right)
+			}
+		}
+
+		impl Compose for $wrapper<String> {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				let (old, middle) = (old_text(&first), new_text(&first));
+				let (expected, new) = (old_text(&second), new_text(&second));
+				expect_value(&middle, &expected)?;
+				Ok(span_comparison(&old, &new, $tokens))
+			}
+		}
+
+		impl Render for $wrapper<String> {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				let diff = change.iter().map(|span| span.to_string()).collect();
+				push_leaf(None, LeafChange::Diff(diff), path, leaves);
+			}
+		}
+	};
+}
+
+impl_string_spans!(Graphemes, |text| text.graphemes(true).collect());
+impl_string_spans!(Words, |text| text.split_word_bounds().collect());
+
+fn span_comparison(old: &str, new: &str, tokens: fn(&str) -> Vec<&str>) -> Changed<Vec<StringSpan>> {
+	if old == new {
+		return Changed::Unchanged;
+	}
+	let (old, new) = (tokens(old), tokens(new));
+	Changed::Changed(normalize(edit_script(old.len(), new.len(), |i, j| old[i] == new[j]).into_iter().map(|edit| {
+		match edit {
+			Edit::Keep(i, _) => StringSpan::Equal(old[i].to_string()),
+			Edit::Delete(i) => StringSpan::Delete(old[i].to_string()),
+			Edit::Insert(j) => StringSpan::Insert(new[j].to_string()),
+		}
+	})))
+}
+
+// Join adjacent spans of the same kind, and put the text deleted between two
+// equal spans before the text inserted there.
+fn normalize(spans: impl IntoIterator<Item = StringSpan>) -> Vec<StringSpan> {
+	let mut normalized: Vec<StringSpan> = Vec::new();
+	// The text deleted and inserted since the last equal span.
+	let (mut deleted, mut inserted) = (String::new(), String::new());
+	for span in spans.into_iter().chain(Some(StringSpan::Equal(String::new()))) {
+		match span {
+			StringSpan::Delete(text) => deleted.push_str(&text),
+			StringSpan::Insert(text) => inserted.push_str(&text),
+			StringSpan::Equal(text) => {
+				if !deleted.is_empty() {
+					normalized.push(StringSpan::Delete(std::mem::take(&mut deleted)));
+				}
+				if !inserted.is_empty() {
+					normalized.push(StringSpan::Insert(std::mem::take(&mut inserted)));
+				}
+				match normalized.last_mut() {
+					Some(StringSpan::Equal(equal)) => equal.push_str(&text),
+					_ if !text.is_empty() => normalized.push(StringSpan::Equal(text)),
+					_ => {}
+				}
+			}
+		}
+	}
+	normalized
+}
+
+// The text of the old string, of which the spans keep or delete parts.
+fn old_text(spans: &[StringSpan]) -> String {
+	spans
+		.iter()
+		.filter_map(|span| match span {
+			StringSpan::Equal(text) | StringSpan::Delete(text) => Some(text.as_str()),
+			StringSpan::Insert(_) => None,
+		})
+		.collect()
+}
+
+// The text of the new string, of which the spans keep or insert parts.
+fn new_text(spans: &[StringSpan]) -> String {
+	spans
+		.iter()
+		.filter_map(|span| match span {
+			StringSpan::Equal(text) | StringSpan::Insert(text) => Some(text.as_str()),
+			StringSpan::Delete(_) => None,
+		})
+		.collect()
+}
+
+fn apply_spans(value: &mut String, spans: Vec<StringSpan>) -> Result<(), PatchError> {
+	expect_value(value, &old_text(&spans))?;
+	*value = new_text(&spans);
+	Ok(())
+}
+
+fn invert_spans(spans: Vec<StringSpan>) -> Vec<StringSpan> {
+	normalize(spans.into_iter().map(|span| match span {
+		StringSpan::Equal(text) => StringSpan::Equal(text),
+		StringSpan::Delete(text) => StringSpan::Insert(text),
+		StringSpan::Insert(text) => StringSpan::Delete(text),
+	}))
+}
+
+// A replacement of the bytes `start..end` of the old string by `text`.
+#[derive(PartialEq)]
+struct Splice {
+	start: usize,
+	end: usize,
+	text: String,
+}
+
+fn splices(spans: &[StringSpan]) -> Vec<Splice> {
+	let mut splices: Vec<Splice> = Vec::new();
+	let mut offset = 0;
+	let mut open = false;
+	for span in spans {
+		if !open && !matches!(span, StringSpan::Equal(_)) {
+			splices.push(Splice { start: offset, end: offset, text: String::new() });
+			open = true;
+		}
+		match span {
+			StringSpan::Equal(text) => {
+				offset += text.len();
+				open = false;
+			}
+			StringSpan::Delete(text) => {
+				offset += text.len();
+				splices.last_mut().unwrap().end = offset;
+			}
+			StringSpan::Insert(text) => splices.last_mut().unwrap().text.push_str(text),
+		}
+	}
+	splices
+}
+
+// The spans that make the given splices to the old string.
+fn splice(old: &str, splices: impl IntoIterator<Item = Splice>) -> Vec<StringSpan> {
+	let mut spans = Vec::new();
+	let mut offset = 0;
+	for Splice { start, end, text } in splices {
+		spans.push(StringSpan::Equal(old[offset..start].to_string()));
+		spans.push(StringSpan::Delete(old[start..end].to_string()));
+		spans.push(StringSpan::Insert(text));
+		offset = end;
+	}
+	spans.push(StringSpan::Equal(old[offset..].to_string()));
+	normalize(spans)
+}
+
+fn merge_spans(left: Vec<StringSpan>, right: Vec<StringSpan>) -> Result<Vec<StringSpan>, Conflicts<Vec<StringSpan>>> {
+	let old = old_text(&left);
+	if old != old_text(&right) {
+		return Err(Conflicts::between(left, right));
+	}
+	let mut merged = Vec::new();
+	let mut conflicts = Vec::new();
+	let (mut left, mut right) = (splices(&left).into_iter().peekable(), splices(&right).into_iter().peekable());
+	loop {
+		let next = match (left.peek(), right.peek()) {
+			(Some(l), Some(r)) if l == r => {
+				right.next();
+				left.next()
+			}
+			(Some(l), Some(r)) if l.start == r.start || (l.start < r.end && r.start < l.end) => {
+				let alone = |one: Splice| splice(&old, Some(one));
+				let (l, r) = (left.next().unwrap(), right.next().unwrap());
+				conflicts.push(Conflict { left: alone(l), right: alone(r) });
+				continue;
+			}
+			(Some(l), Some(r)) if r.start < l.start => right.next(),
+			(Some(_), _) => left.next(),
+			(None, _) => right.next(),
+		};
+		match next {
+			Some(next) => merged.push(next),
+			None => break,
+		}
+	}
+	if conflicts.is_empty() {
+		Ok(splice(&old, merged))
+	} else {
+		Err(Conflicts(conflicts))
+	}
+}
@@ -1,3 +1,9 @@
+use crate::compose::{compose_changed, Compose};
+use crate::invert::{invert_changed, Invert};
+use crate::merge::{merge_part, Conflicts, Merge};
+use crate::options::CompareOptions;
+use crate::patch::{Patch, PatchError};
+use crate::render::{render_changed, ChangePath, Leaf, LeafValue, PathSegment, Render};
 use crate::types::{Changed, Comparable};
 
 macro_rules! impl_all {
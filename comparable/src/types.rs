@@ -1,5 +1,7 @@
 use std::fmt::Debug;
 
+use crate::options::CompareOptions;
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Debug)]
 pub enum Changed<T> {
@@ -123,6 +125,26 @@ pub trait Comparable {
 	/// `comparable::assert_changes` function so that tests can ensure that
 	/// what was expected to happen did happen -- and nothing more.
 	fn comparison(&self, other: &Self) -> Changed<Self::Change>;
+
+	/// Compare two values of a type as `comparison` does, but under the given
+	/// options. Types whose comparison the options affect override this, as
+	/// do types made of other values, to pass the options on to them; the
+	/// default ignores the options.
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		let _ = options;
+		self.comparison(other)
+	}
+
+	/// Whether two values stand for the same entity, even if that entity has
+	/// changed between them. Comparisons that align sequences, like
+	/// `comparable::Aligned`, use this to match up elements, so that an
+	/// element which was both edited and moved can still be followed. By
+	/// default two values are the same only if they compare as unchanged;
+	/// deriving `Comparable` for a struct with fields marked
+	/// `#[comparable_identity]` compares just those fields instead.
+	fn same_identity(&self, other: &Self) -> bool {
+		self.comparison(other).is_unchanged()
+	}
 }
 
 impl<T: Comparable> Comparable for &T {
@@ -137,4 +159,12 @@ impl<T: Comparable> Comparable for &T {
 	fn comparison(&self, other: &Self) -> Changed<Self::Change> {
 		(*self).comparison(other)
 	}
+
+	fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
+		(*self).comparison_with(other, options)
+	}
+
+	fn same_identity(&self, other: &Self) -> bool {
+		(*self).same_identity(other)
+	}
 }
@@ -1,5 +1,9 @@
-use quote::format_ident;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::Token;
 
+use crate::definition::Definition;
 use crate::utils::{has_attr, has_attrs};
 
 pub struct Attributes {
@@ -11,6 +15,11 @@ pub struct Attributes {
 	pub compare_default: bool,
 	pub comparable_public: bool,
 	pub comparable_private: bool,
+	pub comparable_patch: bool,
+	pub comparable_invert: bool,
+	pub comparable_merge: bool,
+	pub comparable_compose: bool,
+	pub comparable_render: bool,
 	pub comparable_desc_suffix: syn::Ident,
 	pub comparable_change_suffix: syn::Ident,
 	pub comparable_attributes: Vec<proc_macro2::TokenStream>,
@@ -33,6 +42,12 @@ impl Attributes {
 			comparable_public: has_attr(attrs, "comparable_public").is_some(),
 			comparable_private: has_attr(attrs, "comparable_private").is_some(),
 
+			comparable_patch: has_attr(attrs, "comparable_patch").is_some(),
+			comparable_invert: has_attr(attrs, "comparable_invert").is_some(),
+			comparable_merge: has_attr(attrs, "comparable_merge").is_some(),
+			comparable_compose: has_attr(attrs, "comparable_compose").is_some(),
+			comparable_render: has_attr(attrs, "comparable_render").is_some(),
+
 			comparable_desc_suffix: attr_to_ident(attrs, "comparable_desc_suffix", "Desc"),
 			comparable_change_suffix: attr_to_ident(attrs, "comparable_change_suffix", "Change"),
 
@@ -49,3 +64,330 @@ fn attr_to_ident(attrs: &[syn::Attribute], name: &str, suffix: &str) -> syn::Ide
 		.map(|x| x.parse_args::<syn::Ident>().unwrap_or_else(|_| panic!("Failed to parse \"{}\" attribute", name)))
 		.unwrap_or_else(|| format_ident!("{}", suffix))
 }
+
+/// Field attributes that choose an alternate way of comparing a field. Each
+/// one names a transparent wrapper type in `comparable`, whose `Comparable`
+/// implementation is used in place of the one for the field's own type.
+#[derive(Clone)]
+pub struct FieldAttributes {
+	pub comparable_aligned: bool,
+	pub comparable_delta: bool,
+	pub comparable_graphemes: bool,
+	pub comparable_key: Option<KeyAttribute>,
+	pub comparable_lines: bool,
+	pub comparable_reversible: bool,
+	pub comparable_tolerance: Option<ToleranceAttribute>,
+	pub comparable_words: bool,
+}
+
+/// The arguments to `#[comparable_tolerance(...)]`: the fields of a
+/// `comparable::FloatTolerance` to set, as `abs = 1e-9`, `rel = ...` or
+/// `ulps = ...`, and `nan_equal`. They are turned into an implementation of
+/// `comparable::Tolerance` for a unit type named after the field, `tolerance`.
+#[derive(Clone)]
+pub struct ToleranceAttribute {
+	pub tolerance: syn::Ident,
+	pub fields: Vec<TokenStream>,
+}
+
+/// The argument to `#[comparable_key(...)]`: a closure that extracts the key
+/// of an element, optionally followed by `moves` to also report reordering.
+/// It is turned into an implementation of `comparable::KeyFn` for a unit type
+/// named after the field, `key_fn`.
+#[derive(Clone)]
+pub struct KeyAttribute {
+	pub key_fn: syn::Ident,
+	pub closure: syn::ExprClosure,
+	pub key_type: syn::Type,
+	pub report_moves: bool,
+}
+
+/// The field attributes that choose a wrapper, of which a field may have only
+/// one.
+const WRAPPER_ATTRIBUTES: &[&str] = &[
+	"comparable_aligned",
+	"comparable_delta",
+	"comparable_graphemes",
+	"comparable_key",
+	"comparable_lines",
+	"comparable_reversible",
+	"comparable_tolerance",
+	"comparable_words",
+];
+
+impl FieldAttributes {
+	/// Check that a field is given at most one way of being compared, pointing
+	/// at the second attribute that gives it another.
+	pub fn check(field: &syn::Field) -> Result<(), syn::Error> {
+		let wrappers: Vec<&syn::Attribute> =
+			field.attrs.iter().filter(|attr| WRAPPER_ATTRIBUTES.iter().any(|name| attr.path.is_ident(name))).collect();
+		if wrappers.len() < 2 {
+			return Ok(());
+		}
+		let names: Vec<String> = wrappers
+			.iter()
+			.map(|attr| format!("#[{}]", attr.path.get_ident().map(ToString::to_string).unwrap_or_default()))
+			.collect();
+		Err(syn::Error::new_spanned(
+			wrappers[1],
+			format!("conflicting attributes {}: a field can be compared in only one way", names.join(", ")),
+		))
+	}
+
+	/// Read the attributes of a field. `owner` names the type (or the type
+	/// and variant) that the field belongs to, so that any helper types
+	/// generated for the field have unique names.
+	pub fn from(owner: &syn::Ident, index: usize, field: &syn::Field) -> Self {
+		FieldAttributes {
+			comparable_aligned: has_attr(&field.attrs, "comparable_aligned").is_some(),
+			comparable_delta: has_attr(&field.attrs, "comparable_delta").is_some(),
+			comparable_graphemes: has_attr(&field.attrs, "comparable_graphemes").is_some(),
+			comparable_key: has_attr(&field.attrs, "comparable_key").map(|attr| {
+				let key_fn = format_ident!("{}{}Key", owner, Definition::variant_name_from_field(index, &field.ident));
+				KeyAttribute::parse(key_fn, attr)
+			}),
+			comparable_lines: has_attr(&field.attrs, "comparable_lines").is_some(),
+			comparable_reversible: has_attr(&field.attrs, "comparable_reversible").is_some(),
+			comparable_tolerance: has_attr(&field.attrs, "comparable_tolerance").map(|attr| {
+				let tolerance =
+					format_ident!("{}{}Tolerance", owner, Definition::variant_name_from_field(index, &field.ident));
+				ToleranceAttribute::parse(tolerance, attr, &field.ty)
+			}),
+			comparable_words: has_attr(&field.attrs, "comparable_words").is_some(),
+		}
+	}
+
+	fn wrapper(&self, ty: &syn::Type) -> Option<syn::Type> {
+		let wrapper = if self.comparable_aligned {
+			quote!(comparable::Aligned<#ty>)
+		} else if self.comparable_delta {
+			quote!(comparable::Delta<#ty>)
+		} else if self.comparable_graphemes {
+			quote!(comparable::Graphemes<#ty>)
+		} else if let Some(key) = &self.comparable_key {
+			let key_fn = &key.key_fn;
+			quote!(comparable::Keyed<#ty, #key_fn>)
+		} else if self.comparable_lines {
+			quote!(comparable::Lines<#ty>)
+		} else if self.comparable_reversible {
+			quote!(comparable::Reversible<#ty>)
+		} else if let Some(tolerance) = &self.comparable_tolerance {
+			let tolerance = &tolerance.tolerance;
+			quote!(comparable::Approx<#ty, #tolerance>)
+		} else if self.comparable_words {
+			quote!(comparable::Words<#ty>)
+		} else {
+			return None;
+		};
+		Some(syn::parse2(wrapper).expect("Failed to parse compared type"))
+	}
+
+	/// The type whose `Comparable` implementation compares the field.
+	pub fn compared_type(&self, ty: &syn::Type) -> syn::Type {
+		self.wrapper(ty).unwrap_or_else(|| ty.clone())
+	}
+
+	/// Compare the values of a field of type `ty` found at two place
+	/// expressions, reached through the path segment `segment` if it has one,
+	/// whose changes are left out if the options ignore it.
+	pub fn comparison(
+		&self,
+		ty: &syn::Type,
+		this: &TokenStream,
+		other: &TokenStream,
+		segment: Option<&TokenStream>,
+	) -> TokenStream {
+		let (this, other) = match self.wrapper(ty) {
+			Some(wrapper) => (quote!(<#wrapper>::from_ref(&#this)), quote!(<#wrapper>::from_ref(&#other))),
+			None => (quote!(&#this), quote!(&#other)),
+		};
+		match segment {
+			Some(segment) => quote!(options.compare_at(#segment, #this, #other)),
+			None => quote!((#this).comparison_with(#other, options)),
+		}
+	}
+
+	/// Apply a change to the value of a field of type `ty` found at a place
+	/// expression.
+	pub fn apply(&self, ty: &syn::Type, place: &TokenStream, change: &TokenStream) -> TokenStream {
+		match self.wrapper(ty) {
+			Some(wrapper) => quote!(comparable::Patch::apply(<#wrapper>::from_mut(&mut #place), #change)),
+			None => quote!(comparable::Patch::apply(&mut #place, #change)),
+		}
+	}
+
+	/// Invert a change to a field of type `ty`.
+	pub fn invert(&self, ty: &syn::Type, change: &TokenStream) -> TokenStream {
+		let compared_type = self.compared_type(ty);
+		quote!(<#compared_type as comparable::Invert>::invert(#change))
+	}
+
+	/// Invert a change to a field of type `ty` that may be
+	/// `comparable::Changed::Unchanged`.
+	pub fn invert_changed(&self, ty: &syn::Type, change: &TokenStream) -> TokenStream {
+		let compared_type = self.compared_type(ty);
+		quote!(comparable::invert_changed::<#compared_type>(#change))
+	}
+
+	/// Merge two changes to a field of type `ty`.
+	pub fn merge(&self, ty: &syn::Type, left: &TokenStream, right: &TokenStream) -> TokenStream {
+		let compared_type = self.compared_type(ty);
+		quote!(<#compared_type as comparable::Merge>::merge_changes(#left, #right))
+	}
+
+	/// Merge two changes to a field of type `ty` that may be
+	/// `comparable::Changed::Unchanged`, adding any conflicts, wrapped with
+	/// `wrap`, to `conflicts`.
+	pub fn merge_part(
+		&self,
+		ty: &syn::Type,
+		left: &TokenStream,
+		right: &TokenStream,
+		conflicts: &TokenStream,
+		wrap: &TokenStream,
+	) -> TokenStream {
+		let compared_type = self.compared_type(ty);
+		quote!(comparable::merge_part::<#compared_type, _>(#left, #right, &mut #conflicts, #wrap))
+	}
+
+	/// Compose two changes to a field of type `ty` made one after the other.
+	pub fn compose(&self, ty: &syn::Type, first: &TokenStream, second: &TokenStream) -> TokenStream {
+		let compared_type = self.compared_type(ty);
+		quote!(<#compared_type as comparable::Compose>::compose(#first, #second))
+	}
+
+	/// Compose two changes to a field of type `ty` that may be
+	/// `comparable::Changed::Unchanged`.
+	pub fn compose_changed(&self, ty: &syn::Type, first: &TokenStream, second: &TokenStream) -> TokenStream {
+		let compared_type = self.compared_type(ty);
+		quote!(comparable::compose_changed::<#compared_type>(#first, #second))
+	}
+
+	/// Render a change to a field of type `ty` into the `leaves` of the change
+	/// at `path`, as that of the value itself.
+	pub fn render(&self, ty: &syn::Type, change: &TokenStream) -> TokenStream {
+		let compared_type = self.compared_type(ty);
+		quote!(<#compared_type as comparable::Render>::render_change(#change, path, leaves))
+	}
+
+	/// Render a change to a field of type `ty`, reached from the value through
+	/// `segment`, into the `leaves` of the change at `path`.
+	pub fn render_at(&self, ty: &syn::Type, change: &TokenStream, segment: &TokenStream) -> TokenStream {
+		let compared_type = self.compared_type(ty);
+		quote!(comparable::render_at::<#compared_type, _>(#change, #segment, path, leaves))
+	}
+
+	/// Render a change to a field of type `ty` that may be
+	/// `comparable::Changed::Unchanged`.
+	pub fn render_changed(&self, ty: &syn::Type, change: &TokenStream) -> TokenStream {
+		let compared_type = self.compared_type(ty);
+		quote!(comparable::render_changed::<#compared_type, _>(#change, path, leaves))
+	}
+
+	/// Definitions of any helper types needed to compare a field of type `ty`.
+	pub fn helpers(&self, visibility: &syn::Visibility, ty: &syn::Type) -> TokenStream {
+		let tolerance = match &self.comparable_tolerance {
+			Some(ToleranceAttribute { tolerance, fields }) => quote! {
+				#[doc(hidden)]
+				#visibility struct #tolerance;
+
+				impl comparable::Tolerance for #tolerance {
+					const TOLERANCE: comparable::FloatTolerance =
+						comparable::FloatTolerance { #(#fields,)* ..comparable::FloatTolerance::EXACT };
+				}
+			},
+			None => quote!(),
+		};
+		let key = match &self.comparable_key {
+			Some(KeyAttribute { key_fn, closure, key_type, report_moves }) => quote! {
+				#[doc(hidden)]
+				#visibility struct #key_fn;
+
+				impl comparable::KeyFn<<#ty as IntoIterator>::Item> for #key_fn {
+					type Key = #key_type;
+					const REPORT_MOVES: bool = #report_moves;
+					fn key(value: &<#ty as IntoIterator>::Item) -> #key_type {
+						let key: fn(&<#ty as IntoIterator>::Item) -> #key_type = #closure;
+						key(value)
+					}
+				}
+			},
+			None => quote!(),
+		};
+		quote!(#tolerance #key)
+	}
+}
+
+impl ToleranceAttribute {
+	fn parse(tolerance: syn::Ident, attr: &syn::Attribute, ty: &syn::Type) -> Self {
+		// `Approx` is only implemented for the floating-point types themselves,
+		// and the error from using it on anything else would not name the
+		// attribute.
+		let is_float = match ty {
+			syn::Type::Path(path) => {
+				path.qself.is_none()
+					&& matches!(path.path.segments.last(), Some(last) if last.ident == "f32" || last.ident == "f64")
+			}
+			_ => false,
+		};
+		if !is_float {
+			panic!(
+				"#[comparable_tolerance] only applies to fields of type f32 or f64, not `{}`; wrap the numbers \
+				 inside other types in comparable::Approx instead",
+				quote!(#ty)
+			);
+		}
+		let args = attr
+			.parse_args_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated)
+			.expect("Failed to parse \"comparable_tolerance\" attribute");
+		let fields = args
+			.into_iter()
+			.map(|arg| match arg {
+				syn::Expr::Path(path) if path.path.is_ident("nan_equal") => quote!(nan_equal: true),
+				syn::Expr::Assign(syn::ExprAssign { left, right, .. }) => match *left {
+					syn::Expr::Path(path) if path.path.is_ident("abs") => quote!(abs: (#right) as f64),
+					syn::Expr::Path(path) if path.path.is_ident("rel") => quote!(rel: (#right) as f64),
+					syn::Expr::Path(path) if path.path.is_ident("ulps") => quote!(ulps: (#right) as u64),
+					_ => panic!("Unexpected argument to comparable_tolerance; expected abs, rel or ulps"),
+				},
+				_ => panic!(
+					"Unexpected argument to comparable_tolerance; expected {}",
+					"abs = ..., rel = ..., ulps = ... or nan_equal"
+				),
+			})
+			.collect();
+		ToleranceAttribute { tolerance, fields }
+	}
+}
+
+impl KeyAttribute {
+	fn parse(key_fn: syn::Ident, attr: &syn::Attribute) -> Self {
+		let args = attr
+			.parse_args_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated)
+			.expect("Failed to parse \"comparable_key\" attribute");
+		let mut args = args.into_iter();
+		let closure = match args.next() {
+			Some(syn::Expr::Closure(closure)) => closure,
+			_ => panic!(
+				"The argument to comparable_key must be a closure that declares its key type, such as {}",
+				"|x| -> u64 { x.id }"
+			),
+		};
+		let key_type = match &closure.output {
+			syn::ReturnType::Type(_, ty) => ty.as_ref().clone(),
+			syn::ReturnType::Default => panic!(
+				"The closure given to comparable_key must declare its return type, which cannot be inferred and {}",
+				"becomes the key type: write |x| -> u64 { x.id } rather than |x| x.id"
+			),
+		};
+		let report_moves = match args.next() {
+			None => false,
+			Some(syn::Expr::Path(path)) if path.path.is_ident("moves") => true,
+			Some(_) => panic!("Unexpected argument to comparable_key; only \"moves\" may follow the closure"),
+		};
+		if args.next().is_some() {
+			panic!("Too many arguments to comparable_key")
+		}
+		KeyAttribute { key_fn, closure, key_type, report_moves }
+	}
+}
@@ -31,6 +31,12 @@ impl Definition {
 			.unwrap_or_else(|_| panic!("Failed to parse associated type"))
 	}
 
+	/// The `Change` type of a field, taking any field attributes that alter
+	/// how it is compared into account.
+	pub fn field_change_type(owner: &syn::Ident, r: &FieldRef) -> syn::Type {
+		Self::assoc_type(&FieldAttributes::from(owner, r.index, r.field).compared_type(&r.field.ty), "Change")
+	}
+
 	pub fn changed_type(ty: &syn::Type) -> syn::Type {
 		syn::parse2(quote!(comparable::Changed<#ty>)).unwrap_or_else(|_| panic!("Failed to parse Changed type"))
 	}
@@ -184,7 +190,7 @@ impl Definition {
 		generics: &syn::Generics,
 	) -> Option<(syn::Data, Vec<(syn::Ident, syn::Data)>)> {
 		match data {
-			syn::Data::Struct(st) => create_change_type_for_structs(st).map(|x| (x, Vec::new())),
+			syn::Data::Struct(st) => create_change_type_for_structs(type_name, st).map(|x| (x, Vec::new())),
 			syn::Data::Enum(en) => Some(if attrs.variant_struct_fields {
 				create_change_type_for_enums_with_helpers(type_name, &attrs.comparable_change_suffix, en, generics)
 			} else {
@@ -207,7 +213,7 @@ impl Definition {
 		data: &syn::Data,
 	) -> TokenStream {
 		match data {
-			syn::Data::Struct(st) => generate_comparison_body_for_structs(change_name, generics, st),
+			syn::Data::Struct(st) => generate_comparison_body_for_structs(type_name, change_name, generics, st),
 			syn::Data::Enum(en) => {
 				if en.variants.is_empty() {
 					quote! {
@@ -222,4 +228,112 @@ impl Definition {
 			}
 		}
 	}
+
+	//
+	// apply method
+	//
+	pub fn generate_apply_method_body(inputs: &Inputs) -> TokenStream {
+		let type_name = &inputs.input.ident;
+		let change_name = format_ident!("{}{}", type_name, inputs.attrs.comparable_change_suffix);
+		match &inputs.input.data {
+			syn::Data::Struct(st) => generate_apply_body_for_structs(type_name, &change_name, st),
+			syn::Data::Enum(en) => generate_apply_body_for_enums(&inputs.attrs, type_name, &change_name, en),
+			syn::Data::Union(_un) => {
+				panic!("comparable_derive::generate_apply_body not implemented for unions")
+			}
+		}
+	}
+
+	//
+	// invert method
+	//
+	pub fn generate_invert_method_body(inputs: &Inputs) -> TokenStream {
+		let type_name = &inputs.input.ident;
+		let change_name = format_ident!("{}{}", type_name, inputs.attrs.comparable_change_suffix);
+		match &inputs.input.data {
+			syn::Data::Struct(st) => generate_invert_body_for_structs(type_name, &change_name, st),
+			syn::Data::Enum(en) => generate_invert_body_for_enums(&inputs.attrs, type_name, &change_name, en),
+			syn::Data::Union(_un) => {
+				panic!("comparable_derive::generate_invert_body not implemented for unions")
+			}
+		}
+	}
+
+	//
+	// merge_changes method
+	//
+	pub fn generate_merge_method_body(inputs: &Inputs) -> TokenStream {
+		let type_name = &inputs.input.ident;
+		let change_name = format_ident!("{}{}", type_name, inputs.attrs.comparable_change_suffix);
+		match &inputs.input.data {
+			syn::Data::Struct(st) => generate_merge_body_for_structs(type_name, &change_name, st),
+			syn::Data::Enum(en) => generate_merge_body_for_enums(&inputs.attrs, type_name, &change_name, en),
+			syn::Data::Union(_un) => {
+				panic!("comparable_derive::generate_merge_body not implemented for unions")
+			}
+		}
+	}
+
+	//
+	// compose method
+	//
+	pub fn generate_compose_method_body(inputs: &Inputs) -> TokenStream {
+		let type_name = &inputs.input.ident;
+		let change_name = format_ident!("{}{}", type_name, inputs.attrs.comparable_change_suffix);
+		match &inputs.input.data {
+			syn::Data::Struct(st) => generate_compose_body_for_structs(type_name, &change_name, st),
+			syn::Data::Enum(en) => generate_compose_body_for_enums(&inputs.attrs, type_name, &change_name, en),
+			syn::Data::Union(_un) => {
+				panic!("comparable_derive::generate_compose_body not implemented for unions")
+			}
+		}
+	}
+
+	//
+	// render_change method
+	//
+	pub fn generate_render_method_body(inputs: &Inputs) -> TokenStream {
+		let type_name = &inputs.input.ident;
+		let change_name = format_ident!("{}{}", type_name, inputs.attrs.comparable_change_suffix);
+		match &inputs.input.data {
+			syn::Data::Struct(st) => generate_render_body_for_structs(type_name, &change_name, st),
+			syn::Data::Enum(en) => generate_render_body_for_enums(&inputs.attrs, type_name, &change_name, en),
+			syn::Data::Union(_un) => {
+				panic!("comparable_derive::generate_render_body not implemented for unions")
+			}
+		}
+	}
+
+	//
+	// from_desc method
+	//
+	pub fn generate_from_desc_method_body(inputs: &Inputs) -> TokenStream {
+		let type_name = &inputs.input.ident;
+		let desc_name = format_ident!("{}{}", type_name, inputs.attrs.comparable_desc_suffix);
+		if inputs.attrs.describe_type.is_some() || inputs.attrs.describe_body.is_some() || inputs.attrs.no_description {
+			quote! {
+				let _ = desc;
+				Err(comparable::PatchError::Unsupported(format!(
+					"{} has a custom description, so it cannot be rebuilt from one",
+					stringify!(#type_name),
+				)))
+			}
+		} else if inputs.attrs.self_describing {
+			quote!(Ok(desc))
+		} else if inputs.attrs.compare_default {
+			quote! {
+				let mut value = #type_name::default();
+				comparable::Patch::apply(&mut value, desc)?;
+				Ok(value)
+			}
+		} else {
+			match &inputs.input.data {
+				syn::Data::Struct(st) => generate_from_desc_body_for_structs(type_name, &desc_name, st),
+				syn::Data::Enum(en) => generate_from_desc_body_for_enums(type_name, &desc_name, en),
+				syn::Data::Union(_un) => {
+					panic!("comparable_derive::generate_from_desc_body not implemented for unions")
+				}
+			}
+		}
+	}
 }
@@ -76,9 +76,10 @@ pub fn create_change_type_for_enums(type_name: &syn::Ident, en: &syn::DataEnum,
 		variants: FromIterator::from_iter(
 			map_variants(en.variants.iter(), |variant| {
 				let many_fields = variant.fields.len() > 1;
+				let owner = format_ident!("{}{}", type_name, &variant.ident);
 				let mapped_fields = map_on_fields(false, &variant.fields, |r| syn::Field {
 					ty: {
-						let change_type = Definition::assoc_type(&r.field.ty, "Change");
+						let change_type = Definition::field_change_type(&owner, r);
 						if many_fields {
 							Definition::changed_type(&change_type)
 						} else {
@@ -149,10 +150,9 @@ pub fn create_change_type_for_enums_with_helpers(
 				if variant.fields.is_empty() {
 					None
 				} else {
-					let apply_change_to_field = |r: &FieldRef| syn::Field {
-						ty: Definition::assoc_type(&r.field.ty, "Change"),
-						..r.field.clone()
-					};
+					let owner = format_ident!("{}{}", type_name, &variant.ident);
+					let apply_change_to_field =
+						|r: &FieldRef| syn::Field { ty: Definition::field_change_type(&owner, r), ..r.field.clone() };
 					Some(syn::Variant {
 						ident: format_ident!("Both{}", &variant.ident),
 						fields: {
@@ -161,13 +161,15 @@ pub fn create_change_type_for_enums_with_helpers(
 								map_on_fields(false, &variant.fields, apply_change_to_field)
 							} else {
 								let fields_struct = &data_from_variant(variant);
-								let fields_change_struct =
-									create_change_type_for_structs(if let syn::Data::Struct(st) = &fields_struct {
+								let fields_change_struct = create_change_type_for_structs(
+									&owner,
+									if let syn::Data::Struct(st) = &fields_struct {
 										st
 									} else {
 										panic!("field_struct is not a struct!")
-									})
-									.unwrap();
+									},
+								)
+								.unwrap();
 
 								let fields_change_name =
 									format_ident!("{}{}{}", type_name, &variant.ident, change_suffix);
@@ -229,14 +231,32 @@ struct FieldDetails {
 	other_var: syn::Ident,
 	changes_var: syn::Ident,
 	is_ignored: bool,
+	ty: syn::Type,
+	field_attrs: FieldAttributes,
+	// The path segment through which the field is reached, as rendered.
+	segment: Option<TokenStream>,
 }
 
 impl FieldDetails {
-	fn from(index: usize, is_ignored: bool) -> Self {
+	fn from(owner: &syn::Ident, index: usize, field: &syn::Field, segment: Option<TokenStream>) -> Self {
 		let self_var = format_ident!("self_var{}", index);
 		let other_var = format_ident!("other_var{}", index);
 		let changes_var = format_ident!("changes_var{}", index);
-		FieldDetails { self_var, other_var, changes_var, is_ignored }
+		let is_ignored = has_attr(&field.attrs, "comparable_ignore").is_some();
+		FieldDetails {
+			self_var,
+			other_var,
+			changes_var,
+			is_ignored,
+			ty: field.ty.clone(),
+			field_attrs: FieldAttributes::from(owner, index, field),
+			segment,
+		}
+	}
+
+	fn comparison(&self) -> TokenStream {
+		let FieldDetails { self_var, other_var, ty, segment, .. } = self;
+		self.field_attrs.comparison(ty, &quote!((*#self_var)), &quote!((*#other_var)), segment.as_ref())
 	}
 }
 
@@ -276,22 +296,12 @@ impl VariantFields {
 		self.field_details().iter().map(|d| d.self_var.clone()).collect()
 	}
 
-	fn self_vars_filtered(&self) -> Vec<syn::Ident> {
-		self.field_details()
-			.iter()
-			.filter_map(|d| if !d.is_ignored { Some(d.self_var.clone()) } else { None })
-			.collect()
-	}
-
 	fn other_vars(&self) -> Vec<syn::Ident> {
 		self.field_details().iter().map(|d| d.other_var.clone()).collect()
 	}
 
-	fn other_vars_filtered(&self) -> Vec<syn::Ident> {
-		self.field_details()
-			.iter()
-			.filter_map(|d| if !d.is_ignored { Some(d.other_var.clone()) } else { None })
-			.collect()
+	fn comparisons(&self) -> Vec<TokenStream> {
+		self.field_details().iter().filter_map(|d| if !d.is_ignored { Some(d.comparison()) } else { None }).collect()
 	}
 
 	fn changes_vars(&self) -> Vec<syn::Ident> {
@@ -322,13 +332,17 @@ struct VariantDetails {
 }
 
 impl VariantDetails {
-	fn from(variant: &syn::Variant) -> Self {
+	fn from(type_name: &syn::Ident, variant: &syn::Variant) -> Self {
+		let owner = format_ident!("{}{}", type_name, &variant.ident);
 		let fields = match &variant.fields {
 			syn::Fields::Named(named) => VariantFields::Named(
 				map_fields(false, named.named.iter(), false, |r| {
 					(
 						r.field.ident.as_ref().expect("Unexpected unnamed field").clone(),
-						FieldDetails::from(r.index, has_attr(&r.field.attrs, "comparable_ignore").is_some()),
+						FieldDetails::from(&owner, r.index, r.field, {
+							let name = field_path_name(r);
+							Some(quote!(comparable::PathSegment::Field(#name)))
+						}),
 					)
 				})
 				.into_iter()
@@ -336,7 +350,11 @@ impl VariantDetails {
 			),
 			syn::Fields::Unnamed(unnamed) => VariantFields::Unnamed(
 				map_fields(false, unnamed.unnamed.iter(), false, |r| {
-					FieldDetails::from(r.index, has_attr(&r.field.attrs, "comparable_ignore").is_some())
+					// Like in its rendering, the sole field of a newtype variant
+					// has no segment of its own.
+					let name = field_path_name(r);
+					let segment = (unnamed.unnamed.len() > 1).then(|| quote!(comparable::PathSegment::Field(#name)));
+					FieldDetails::from(&owner, r.index, r.field, segment)
 				})
 				.into_iter()
 				.collect(),
@@ -390,9 +408,8 @@ impl VariantDetails {
 			&self;
 
 		let both_ident = format_ident!("Both{}", variant_name);
-		let self_vars_without_ignored = fields.self_vars_filtered();
 		let changes_vars = fields.changes_vars();
-		let other_vars_without_ignored = fields.other_vars_filtered();
+		let comparisons = fields.comparisons();
 
 		let return_result = if changes_vars.is_empty() {
 			quote!(comparable::Changed::Unchanged)
@@ -430,11 +447,24 @@ impl VariantDetails {
 			}
 		};
 
+		let variant_str = variant_name.to_string();
+		let body = if changes_vars.is_empty() {
+			return_result
+		} else {
+			quote! {
+				let options = options.at(comparable::PathSegment::Variant(#variant_str));
+				if options.is_ignored() {
+					return comparable::Changed::Unchanged;
+				}
+				let options = &*options;
+				#(let #changes_vars = #comparisons;)*
+				#return_result
+			}
+		};
 		self.match_branch = quote! {
 			(#type_name::#variant_name #fields_self_capture,
 			 #type_name::#variant_name #fields_other_capture) => {
-				#(let #changes_vars = #self_vars_without_ignored.comparison(&#other_vars_without_ignored);)*
-				#return_result
+				#body
 			}
 		};
 		self
@@ -450,7 +480,7 @@ impl EnumDetails {
 	pub fn from(attrs: &Attributes, type_name: &syn::Ident, change_name: &syn::Ident, en: &syn::DataEnum) -> Self {
 		EnumDetails {
 			variants: map_variants(en.variants.iter(), |variant| {
-				VariantDetails::from(variant).derive_match_branch(attrs, type_name, change_name, variant)
+				VariantDetails::from(type_name, variant).derive_match_branch(attrs, type_name, change_name, variant)
 			})
 			.into_iter()
 			.collect(),
@@ -479,3 +509,546 @@ impl EnumDetails {
 		}
 	}
 }
+
+pub fn generate_apply_body_for_enums(
+	attrs: &Attributes,
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	en: &syn::DataEnum,
+) -> TokenStream {
+	let arms = map_variants(en.variants.iter(), |variant| {
+		let variant_name = &variant.ident;
+		let both_ident = format_ident!("Both{}", variant_name);
+		let owner = format_ident!("{}{}", type_name, variant_name);
+		let fields = map_fields(false, variant.fields.iter(), true, |r| {
+			let change_var = format_ident!("change_var{}", r.index);
+			let self_var = format_ident!("self_var{}", r.index);
+			let application = FieldAttributes::from(&owner, r.index, r.field).apply(
+				&r.field.ty,
+				&quote!((*#self_var)),
+				&quote!(#change_var),
+			);
+			(
+				r.field.ident.clone(),
+				Definition::variant_name_from_field(r.index, &r.field.ident),
+				self_var,
+				change_var,
+				application,
+			)
+		});
+		if fields.is_empty() {
+			return None;
+		}
+		let names: Vec<_> = fields.iter().map(|f| &f.0).collect();
+		let field_variants: Vec<_> = fields.iter().map(|f| &f.1).collect();
+		let self_vars: Vec<_> = fields.iter().map(|f| &f.2).collect();
+		let change_vars: Vec<_> = fields.iter().map(|f| &f.3).collect();
+		let applications: Vec<_> = fields.iter().map(|f| &f.4).collect();
+		let self_capture = match &variant.fields {
+			syn::Fields::Named(_) => quote!({ #(#names: #self_vars,)* .. }),
+			_ => {
+				let captures = map_fields(false, variant.fields.iter(), false, |r| {
+					if has_attr(&r.field.attrs, "comparable_ignore").is_some() {
+						quote!(_)
+					} else {
+						let self_var = format_ident!("self_var{}", r.index);
+						quote!(#self_var)
+					}
+				});
+				quote!((#(#captures),*))
+			}
+		};
+		let (change_capture, applications) = if variant.fields.len() == 1 {
+			let change_capture = match &variant.fields {
+				syn::Fields::Named(_) => quote!({ #(#names: #change_vars),* }),
+				_ => quote!((#(#change_vars),*)),
+			};
+			(change_capture, quote!(#(#applications?;)*))
+		} else if attrs.variant_struct_fields {
+			let fields_change_name = format_ident!("{}{}{}", type_name, variant_name, attrs.comparable_change_suffix);
+			(
+				quote!((changes)),
+				quote! {
+					for change in changes {
+						match change {
+							#(#fields_change_name::#field_variants(#change_vars) => #applications?,)*
+						}
+					}
+				},
+			)
+		} else {
+			let change_capture = match &variant.fields {
+				syn::Fields::Named(_) => quote!({ #(#names: #change_vars),* }),
+				_ => quote!((#(#change_vars),*)),
+			};
+			(
+				change_capture,
+				quote! {
+					#(
+						if let comparable::Changed::Changed(#change_vars) = #change_vars {
+							#applications?;
+						}
+					)*
+				},
+			)
+		};
+		Some(quote! {
+			#change_name::#both_ident #change_capture => match self {
+				#type_name::#variant_name #self_capture => {
+					#applications
+					Ok(())
+				}
+				#[allow(unreachable_patterns)]
+				_ => Err(comparable::PatchError::Mismatch(format!(
+					"expected {}::{}",
+					stringify!(#type_name),
+					stringify!(#variant_name),
+				))),
+			}
+		})
+	})
+	.into_iter()
+	.flatten()
+	.collect::<Vec<_>>();
+
+	let different_arm = if en.variants.len() < 2 {
+		quote!()
+	} else {
+		quote! {
+			#change_name::Different(old, new) => {
+				let current = comparable::Comparable::describe(self);
+				if current != old {
+					return Err(comparable::PatchError::Mismatch(format!("expected {:?}, found {:?}", old, current)));
+				}
+				*self = <Self as comparable::Patch>::from_desc(new)?;
+				Ok(())
+			}
+		}
+	};
+
+	quote! {
+		match change {
+			#(#arms,)*
+			#different_arm
+		}
+	}
+}
+
+pub fn generate_invert_body_for_enums(
+	attrs: &Attributes,
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	en: &syn::DataEnum,
+) -> TokenStream {
+	let arms = map_variants(en.variants.iter(), |variant| {
+		let variant_name = &variant.ident;
+		let both_ident = format_ident!("Both{}", variant_name);
+		let owner = format_ident!("{}{}", type_name, variant_name);
+		let fields = map_fields(false, variant.fields.iter(), true, |r| {
+			let change_var = format_ident!("change_var{}", r.index);
+			let field_attrs = FieldAttributes::from(&owner, r.index, r.field);
+			let inversion = field_attrs.invert(&r.field.ty, &quote!(#change_var));
+			let changed_inversion = field_attrs.invert_changed(&r.field.ty, &quote!(#change_var));
+			(
+				r.field.ident.clone(),
+				Definition::variant_name_from_field(r.index, &r.field.ident),
+				change_var,
+				inversion,
+				changed_inversion,
+			)
+		});
+		if fields.is_empty() {
+			return None;
+		}
+		let names: Vec<_> = fields.iter().map(|f| &f.0).collect();
+		let field_variants: Vec<_> = fields.iter().map(|f| &f.1).collect();
+		let change_vars: Vec<_> = fields.iter().map(|f| &f.2).collect();
+		let inversions: Vec<_> = fields.iter().map(|f| &f.3).collect();
+		let changed_inversions: Vec<_> = fields.iter().map(|f| &f.4).collect();
+		let (capture, inverted) = if attrs.variant_struct_fields && variant.fields.len() > 1 {
+			let fields_change_name = format_ident!("{}{}{}", type_name, variant_name, attrs.comparable_change_suffix);
+			(
+				quote!((changes)),
+				quote! {
+					(changes
+						.into_iter()
+						.map(|change| {
+							Ok(match change {
+								#(#fields_change_name::#field_variants(#change_vars) =>
+									#fields_change_name::#field_variants(#inversions?),)*
+							})
+						})
+						.collect::<Result<_, comparable::PatchError>>()?)
+				},
+			)
+		} else {
+			let inversions = if variant.fields.len() == 1 { inversions } else { changed_inversions };
+			match &variant.fields {
+				syn::Fields::Named(_) => (quote!({ #(#names: #change_vars),* }), quote!({ #(#names: #inversions?),* })),
+				_ => (quote!((#(#change_vars),*)), quote!((#(#inversions?),*))),
+			}
+		};
+		Some(quote! {
+			#change_name::#both_ident #capture => Ok(#change_name::#both_ident #inverted)
+		})
+	})
+	.into_iter()
+	.flatten()
+	.collect::<Vec<_>>();
+
+	let different_arm = if en.variants.len() < 2 {
+		quote!()
+	} else {
+		quote! {
+			#change_name::Different(old, new) => Ok(#change_name::Different(new, old)),
+		}
+	};
+
+	quote! {
+		match change {
+			#(#arms,)*
+			#different_arm
+		}
+	}
+}
+
+pub fn generate_merge_body_for_enums(
+	attrs: &Attributes,
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	en: &syn::DataEnum,
+) -> TokenStream {
+	let arms = map_variants(en.variants.iter(), |variant| {
+		let variant_name = &variant.ident;
+		let both_ident = format_ident!("Both{}", variant_name);
+		let owner = format_ident!("{}{}", type_name, variant_name);
+		let fields = map_fields(false, variant.fields.iter(), true, |r| {
+			(
+				r.field.ident.clone(),
+				Definition::variant_name_from_field(r.index, &r.field.ident),
+				FieldAttributes::from(&owner, r.index, r.field),
+				r.field.ty.clone(),
+				format_ident!("left_var{}", r.index),
+				format_ident!("right_var{}", r.index),
+			)
+		});
+		if fields.is_empty() {
+			return None;
+		}
+		let names: Vec<_> = fields.iter().map(|f| &f.0).collect();
+		let left_vars: Vec<_> = fields.iter().map(|f| &f.4).collect();
+		let right_vars: Vec<_> = fields.iter().map(|f| &f.5).collect();
+		let capture = |vars: &[&syn::Ident]| match &variant.fields {
+			syn::Fields::Named(_) => quote!({ #(#names: #vars),* }),
+			_ => quote!((#(#vars),*)),
+		};
+		let with_helper = attrs.variant_struct_fields && variant.fields.len() > 1;
+		let (left_capture, right_capture) =
+			if with_helper { (quote!((left)), quote!((right))) } else { (capture(&left_vars), capture(&right_vars)) };
+		let merged = if with_helper {
+			let fields_change_name = format_ident!("{}{}{}", type_name, variant_name, attrs.comparable_change_suffix);
+			let fields: Vec<_> = fields.iter().map(|f| (f.1.clone(), f.2.clone(), f.3.clone())).collect();
+			generate_merge_of_field_changes(&fields_change_name, &fields, &quote!(#change_name::#both_ident))
+		} else if variant.fields.len() == 1 {
+			let (_, _, field_attrs, ty, left_var, right_var) = &fields[0];
+			let merged = field_attrs.merge(ty, &quote!(#left_var), &quote!(#right_var));
+			let wrap = capture(&[&format_ident!("change")]);
+			quote! {
+				#merged
+					.map(|change| #change_name::#both_ident #wrap)
+					.map_err(|conflicts| conflicts.map(|change| #change_name::#both_ident #wrap))
+			}
+		} else {
+			// A conflict in one field is reported as a change to that field alone.
+			let merges = fields.iter().map(|(_, _, field_attrs, ty, left_var, right_var)| {
+				let others = fields.iter().map(|f| {
+					if &f.4 == left_var {
+						quote!(comparable::Changed::Changed(change))
+					} else {
+						quote!(comparable::Changed::Unchanged)
+					}
+				});
+				let wrap = match &variant.fields {
+					syn::Fields::Named(_) => quote!(|change| #change_name::#both_ident { #(#names: #others),* }),
+					_ => quote!(|change| #change_name::#both_ident(#(#others),*)),
+				};
+				field_attrs.merge_part(ty, &quote!(#left_var), &quote!(#right_var), &quote!(conflicts), &wrap)
+			});
+			let merged = match &variant.fields {
+				syn::Fields::Named(_) => quote!(#change_name::#both_ident { #(#names: #merges),* }),
+				_ => quote!(#change_name::#both_ident(#(#merges),*)),
+			};
+			quote! {
+				let mut conflicts = Vec::new();
+				let merged = #merged;
+				if conflicts.is_empty() {
+					Ok(merged)
+				} else {
+					Err(comparable::Conflicts(conflicts))
+				}
+			}
+		};
+		Some(quote! {
+			(#change_name::#both_ident #left_capture, #change_name::#both_ident #right_capture) => {
+				#merged
+			}
+		})
+	})
+	.into_iter()
+	.flatten()
+	.collect::<Vec<_>>();
+
+	quote! {
+		match (left, right) {
+			#(#arms,)*
+			// Changes to different variants, or to the variant as a whole,
+			// cannot be broken down any further.
+			#[allow(unreachable_patterns)]
+			(left, right) => {
+				if left == right {
+					Ok(left)
+				} else {
+					Err(comparable::Conflicts::between(left, right))
+				}
+			}
+		}
+	}
+}
+
+pub fn generate_from_desc_body_for_enums(
+	type_name: &syn::Ident,
+	desc_name: &syn::Ident,
+	en: &syn::DataEnum,
+) -> TokenStream {
+	let arms = map_variants(en.variants.iter(), |variant| {
+		let variant_name = &variant.ident;
+		if let Some(ignored) = variant.fields.iter().position(|f| has_attr(&f.attrs, "comparable_ignore").is_some()) {
+			let name = match variant.fields.iter().nth(ignored).and_then(|f| f.ident.as_ref()) {
+				Some(ident) => ident.to_string(),
+				None => ignored.to_string(),
+			};
+			let pattern = match &variant.fields {
+				syn::Fields::Named(_) => quote!({ .. }),
+				_ => quote!((..)),
+			};
+			return quote! {
+				#desc_name::#variant_name #pattern => Err(comparable::PatchError::Unsupported(format!(
+					"field `{}` of {}::{} is ignored, so it cannot be rebuilt from a description",
+					#name,
+					stringify!(#type_name),
+					stringify!(#variant_name),
+				)))
+			};
+		}
+		match &variant.fields {
+			syn::Fields::Named(named) => {
+				let (names, types): (Vec<&syn::Ident>, Vec<&syn::Type>) = named
+					.named
+					.iter()
+					.map(|f| (f.ident.as_ref().expect("Found unnamed field in named struct"), &f.ty))
+					.unzip();
+				quote! {
+					#desc_name::#variant_name { #(#names),* } => Ok(#type_name::#variant_name {
+						#(#names: <#types as comparable::Patch>::from_desc(#names)?),*
+					})
+				}
+			}
+			syn::Fields::Unnamed(unnamed) => {
+				let (vars, types): (Vec<syn::Ident>, Vec<&syn::Type>) =
+					unnamed.unnamed.iter().enumerate().map(|(i, f)| (format_ident!("desc{}", i), &f.ty)).unzip();
+				quote! {
+					#desc_name::#variant_name(#(#vars),*) => Ok(#type_name::#variant_name(
+						#(<#types as comparable::Patch>::from_desc(#vars)?),*
+					))
+				}
+			}
+			syn::Fields::Unit => quote! {
+				#desc_name::#variant_name => Ok(#type_name::#variant_name)
+			},
+		}
+	});
+	quote! {
+		match desc {
+			#(#arms),*
+		}
+	}
+}
+
+pub fn generate_compose_body_for_enums(
+	attrs: &Attributes,
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	en: &syn::DataEnum,
+) -> TokenStream {
+	let arms = map_variants(en.variants.iter(), |variant| {
+		let variant_name = &variant.ident;
+		let both_ident = format_ident!("Both{}", variant_name);
+		let owner = format_ident!("{}{}", type_name, variant_name);
+		let fields = map_fields(false, variant.fields.iter(), true, |r| {
+			(
+				r.field.ident.clone(),
+				Definition::variant_name_from_field(r.index, &r.field.ident),
+				FieldAttributes::from(&owner, r.index, r.field),
+				r.field.ty.clone(),
+				format_ident!("first_var{}", r.index),
+				format_ident!("second_var{}", r.index),
+			)
+		});
+		if fields.is_empty() {
+			return None;
+		}
+		let names: Vec<_> = fields.iter().map(|f| &f.0).collect();
+		let first_vars: Vec<_> = fields.iter().map(|f| &f.4).collect();
+		let second_vars: Vec<_> = fields.iter().map(|f| &f.5).collect();
+		let capture = |vars: &[&syn::Ident]| match &variant.fields {
+			syn::Fields::Named(_) => quote!({ #(#names: #vars),* }),
+			_ => quote!((#(#vars),*)),
+		};
+		let with_helper = attrs.variant_struct_fields && variant.fields.len() > 1;
+		let (first_capture, second_capture) = if with_helper {
+			(quote!((first)), quote!((second)))
+		} else {
+			(capture(&first_vars), capture(&second_vars))
+		};
+		let composed = if with_helper {
+			let fields_change_name = format_ident!("{}{}{}", type_name, variant_name, attrs.comparable_change_suffix);
+			let fields: Vec<_> = fields.iter().map(|f| (f.1.clone(), f.2.clone(), f.3.clone())).collect();
+			generate_compose_of_field_changes(&fields_change_name, &fields, &quote!(#change_name::#both_ident))
+		} else if variant.fields.len() == 1 {
+			let (_, _, field_attrs, ty, first_var, second_var) = &fields[0];
+			let composed = field_attrs.compose(ty, &quote!(#first_var), &quote!(#second_var));
+			let wrap = capture(&[&format_ident!("change")]);
+			quote!(Ok(#composed?.map(|change| #change_name::#both_ident #wrap)))
+		} else {
+			let compositions = fields.iter().map(|(_, _, field_attrs, ty, first_var, second_var)| {
+				field_attrs.compose_changed(ty, &quote!(#first_var), &quote!(#second_var))
+			});
+			let composed = capture(&first_vars);
+			quote! {
+				#(let #first_vars = #compositions?;)*
+				if #(#first_vars.is_unchanged())&&* {
+					Ok(comparable::Changed::Unchanged)
+				} else {
+					Ok(comparable::Changed::Changed(#change_name::#both_ident #composed))
+				}
+			}
+		};
+		Some(quote! {
+			(#change_name::#both_ident #first_capture, #change_name::#both_ident #second_capture) => {
+				#composed
+			}
+		})
+	})
+	.into_iter()
+	.flatten()
+	.collect::<Vec<_>>();
+
+	let replacement = if en.variants.len() < 2 {
+		quote!(|change| Err(change))
+	} else {
+		quote! {
+			|change| match change {
+				#change_name::Different(old, new) => Ok((old, new)),
+				change => Err(change),
+			}
+		}
+	};
+
+	quote! {
+		match (first, second) {
+			#(#arms,)*
+			// Changes that replace the value as a whole are composed by
+			// rebuilding the values at either end.
+			#[allow(unreachable_patterns)]
+			(first, second) => comparable::compose_replacing::<Self>(first, second, #replacement),
+		}
+	}
+}
+
+pub fn generate_render_body_for_enums(
+	attrs: &Attributes,
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	en: &syn::DataEnum,
+) -> TokenStream {
+	let arms = map_variants(en.variants.iter(), |variant| {
+		let variant_name = &variant.ident;
+		let variant_str = variant_name.to_string();
+		let both_ident = format_ident!("Both{}", variant_name);
+		let owner = format_ident!("{}{}", type_name, variant_name);
+		let fields = map_fields(false, variant.fields.iter(), true, |r| {
+			(
+				r.field.ident.clone(),
+				Definition::variant_name_from_field(r.index, &r.field.ident),
+				FieldAttributes::from(&owner, r.index, r.field),
+				r.field.ty.clone(),
+				format_ident!("var{}", r.index),
+				field_path_name(r),
+			)
+		});
+		if fields.is_empty() {
+			return None;
+		}
+		let (capture, rendered) = if attrs.variant_struct_fields && variant.fields.len() > 1 {
+			let fields_change_name = format_ident!("{}{}{}", type_name, variant_name, attrs.comparable_change_suffix);
+			let fields: Vec<_> = fields.iter().map(|f| (f.1.clone(), f.2.clone(), f.3.clone(), f.5.clone())).collect();
+			(quote!((change)), generate_render_of_field_changes(&fields_change_name, &fields))
+		} else {
+			let names: Vec<_> = fields.iter().map(|f| &f.0).collect();
+			let vars: Vec<_> = fields.iter().map(|f| &f.4).collect();
+			let renders = fields.iter().map(|(_, _, field_attrs, ty, var, name)| {
+				let segment = quote!(comparable::PathSegment::Field(#name));
+				if variant.fields.len() == 1 {
+					match &variant.fields {
+						// Like serde, treat the sole field of a newtype variant as
+						// the variant itself.
+						syn::Fields::Unnamed(_) => field_attrs.render(ty, &quote!(#var)),
+						_ => field_attrs.render_at(ty, &quote!(#var), &segment),
+					}
+				} else {
+					let rendered = field_attrs.render_changed(ty, &quote!(#var));
+					quote! {{
+						path.push(#segment);
+						#rendered;
+						path.pop();
+					}}
+				}
+			});
+			let capture = match &variant.fields {
+				syn::Fields::Named(_) => quote!({ #(#names: #vars),* }),
+				_ => quote!((#(#vars),*)),
+			};
+			(capture, quote!(#(#renders;)*))
+		};
+		Some(quote! {
+			#change_name::#both_ident #capture => {
+				path.push(comparable::PathSegment::Variant(#variant_str));
+				#rendered
+				path.pop();
+			}
+		})
+	})
+	.into_iter()
+	.flatten()
+	.collect::<Vec<_>>();
+
+	if en.variants.len() < 2 {
+		if arms.is_empty() {
+			// The change type has no values at all.
+			quote!(match *change {})
+		} else {
+			quote! {
+				match change {
+					#(#arms,)*
+				}
+			}
+		}
+	} else {
+		quote! {
+			match change {
+				#(#arms,)*
+				#change_name::Different(old, new) => comparable::render_replaced(old, new, path, leaves),
+			}
+		}
+	}
+}
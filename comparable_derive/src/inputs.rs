@@ -1,8 +1,11 @@
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
 
 use crate::attrs::*;
 use crate::definition::*;
 use crate::outputs::*;
+use crate::structs::*;
+use crate::utils::*;
 
 pub struct Inputs<'a> {
 	pub attrs: Attributes,
@@ -25,6 +28,24 @@ impl<'a> Inputs<'a> {
 		Inputs { attrs, input, visibility }
 	}
 
+	/// Check the attributes of every field, collecting the errors of all of
+	/// them.
+	pub fn check_fields(&self) -> Result<(), syn::Error> {
+		let fields: Vec<&syn::Field> = match &self.input.data {
+			syn::Data::Struct(st) => st.fields.iter().collect(),
+			syn::Data::Enum(en) => en.variants.iter().flat_map(|variant| variant.fields.iter()).collect(),
+			syn::Data::Union(_) => vec![],
+		};
+		fields.into_iter().map(FieldAttributes::check).fold(Ok(()), |result, checked| match (result, checked) {
+			(Err(mut error), Err(other)) => {
+				error.combine(other);
+				Err(error)
+			}
+			(Err(error), _) | (_, Err(error)) => Err(error),
+			(Ok(()), Ok(())) => Ok(()),
+		})
+	}
+
 	pub fn process_data(&self) -> Outputs {
 		let is_unitary = match &self.input.data {
 			syn::Data::Struct(st) => match &st.fields {
@@ -41,6 +62,71 @@ impl<'a> Inputs<'a> {
 		Outputs {
 			desc: if self.attrs.no_description { None } else { Some(Definition::generate_desc_type(self)) },
 			change: if is_unitary { None } else { Some(Definition::generate_change_type(self)) },
+			same_identity: match &self.input.data {
+				syn::Data::Struct(st) => generate_same_identity_body_for_structs(&self.input.ident, st),
+				_ => None,
+			},
+			apply: if is_unitary {
+				quote! {
+					let _ = change;
+					Ok(())
+				}
+			} else {
+				Definition::generate_apply_method_body(self)
+			},
+			from_desc: Definition::generate_from_desc_method_body(self),
+			invert: if is_unitary { quote!(Ok(change)) } else { Definition::generate_invert_method_body(self) },
+			merge: if is_unitary {
+				quote! {
+					let _ = right;
+					Ok(left)
+				}
+			} else {
+				Definition::generate_merge_method_body(self)
+			},
+			compose: if is_unitary {
+				quote! {
+					let _ = (first, second);
+					Ok(comparable::Changed::Unchanged)
+				}
+			} else {
+				Definition::generate_compose_method_body(self)
+			},
+			render: if is_unitary {
+				quote! {
+					let _ = (change, path, leaves);
+				}
+			} else {
+				Definition::generate_render_method_body(self)
+			},
+			field_helpers: self.generate_field_helpers(),
+		}
+	}
+
+	// Helper types needed by field attributes, such as the key functions of
+	// fields marked with `comparable_key`.
+	fn generate_field_helpers(&self) -> Vec<TokenStream> {
+		let type_name = &self.input.ident;
+		let helpers = |owner: &syn::Ident, inject_synthetics: bool, fields: &syn::Fields| {
+			map_fields(inject_synthetics, fields.iter(), true, |r| {
+				let field_attrs = FieldAttributes::from(owner, r.index, r.field);
+				if field_attrs.comparable_key.is_some() && !self.input.generics.params.is_empty() {
+					panic!(
+						"comparable_key is not supported on fields of generic types; {}",
+						"wrap the field in comparable::Keyed with a type implementing comparable::KeyFn instead"
+					);
+				}
+				field_attrs.helpers(&self.visibility, &r.field.ty)
+			})
+		};
+		match &self.input.data {
+			syn::Data::Struct(st) => helpers(type_name, true, &st.fields),
+			syn::Data::Enum(en) => en
+				.variants
+				.iter()
+				.flat_map(|variant| helpers(&format_ident!("{}{}", type_name, &variant.ident), false, &variant.fields))
+				.collect(),
+			syn::Data::Union(_un) => Vec::new(),
 		}
 	}
 }
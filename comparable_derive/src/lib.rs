@@ -20,12 +20,29 @@ mod utils;
 		comparable_synthetic,
 		comparable_desc_suffix,
 		comparable_change_suffix,
+		comparable_patch,
+		comparable_invert,
+		comparable_merge,
+		comparable_compose,
+		comparable_render,
 		comparable_ignore,
+		comparable_aligned,
+		comparable_delta,
+		comparable_graphemes,
+		comparable_identity,
+		comparable_key,
+		comparable_lines,
+		comparable_reversible,
+		comparable_tolerance,
+		comparable_words,
 		comparable_attribute,
 	)
 )]
 pub fn comparable_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	let input = syn::parse_macro_input!(input as syn::DeriveInput);
 	let inputs = crate::inputs::Inputs::from(&input);
+	if let Err(error) = inputs.check_fields() {
+		return error.to_compile_error().into();
+	}
 	inputs.process_data().generate(&inputs).into()
 }
@@ -8,11 +8,27 @@ use crate::utils::*;
 pub struct Outputs {
 	pub desc: Option<Definition>,
 	pub change: Option<Definition>,
+	// The body of `same_identity`, if any fields are marked as making up the
+	// identity of a value.
+	pub same_identity: Option<TokenStream>,
+	// The bodies of `Patch::apply` and `Patch::from_desc`.
+	pub apply: TokenStream,
+	pub from_desc: TokenStream,
+	// The body of `Invert::invert`.
+	pub invert: TokenStream,
+	// The body of `Merge::merge_changes`.
+	pub merge: TokenStream,
+	// The body of `Compose::compose`.
+	pub compose: TokenStream,
+	// The body of `Render::render_change`.
+	pub render: TokenStream,
+	pub field_helpers: Vec<TokenStream>,
 }
 
 impl Outputs {
 	pub fn generate(self, inputs: &Inputs) -> TokenStream {
-		let Outputs { desc, change } = self;
+		let Outputs { desc, change, same_identity, apply, from_desc, invert, merge, compose, render, field_helpers } =
+			self;
 
 		let impl_comparable = Self::impl_comparable(
 			&inputs.input.ident,
@@ -21,17 +37,45 @@ impl Outputs {
 			desc.as_ref().map(|d| &d.method_body).unwrap_or(&quote!()),
 			change.as_ref().and_then(|c| c.ty.as_ref()).unwrap_or(&unit_type()),
 			change.as_ref().map(|c| &c.method_body).unwrap_or(&quote!(comparable::Changed::Unchanged)),
+			same_identity.as_ref(),
 		);
 
+		// `Patch`, `Invert`, `Merge`, `Compose` and `Render` are only derived
+		// when asked for, since they require every field type to implement
+		// them. Asking for `Compose` implies the two it builds on.
+		let derive_compose = inputs.attrs.comparable_compose;
+		let derive_patch = inputs.attrs.comparable_patch || derive_compose;
+		let derive_invert = inputs.attrs.comparable_invert || derive_compose;
+		let impl_patch =
+			derive_patch.then(|| Self::impl_patch(&inputs.input.ident, &inputs.input.generics, &apply, &from_desc));
+		let impl_invert =
+			derive_invert.then(|| Self::impl_invert(&inputs.input.ident, &inputs.input.generics, &invert));
+		let impl_merge = inputs
+			.attrs
+			.comparable_merge
+			.then(|| Self::impl_merge(&inputs.input.ident, &inputs.input.generics, &merge));
+		let impl_compose =
+			derive_compose.then(|| Self::impl_compose(&inputs.input.ident, &inputs.input.generics, &compose));
+		let impl_render = inputs
+			.attrs
+			.comparable_render
+			.then(|| Self::impl_render(&inputs.input.ident, &inputs.input.generics, &render));
+
 		#[allow(unused_variables)] // compiler doesn't see the use of x
 		let desc = desc.map(|x| quote!(#x)).unwrap_or_default();
 		#[allow(unused_variables)] // compiler doesn't see the use of x
 		let change = change.map(|x| quote!(#x)).unwrap_or_default();
 
 		quote! {
+			#(#field_helpers)*
 			#desc
 			#change
 			#impl_comparable
+			#impl_patch
+			#impl_invert
+			#impl_merge
+			#impl_compose
+			#impl_render
 		}
 	}
 
@@ -42,19 +86,144 @@ impl Outputs {
 		describe_body: &TokenStream,
 		change_type: &syn::Type,
 		change_body: &TokenStream,
+		same_identity_body: Option<&TokenStream>,
 	) -> TokenStream {
-		let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+		let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+		let extended_where_clause = Self::where_clause(generics, quote!(comparable::Comparable));
+
+		let same_identity = same_identity_body.map(|body| {
+			quote! {
+				fn same_identity(&self, other: &Self) -> bool {
+					let options = &comparable::CompareOptions::default();
+					#body
+				}
+			}
+		});
+
+		quote! {
+			impl #impl_generics comparable::Comparable for #name #ty_generics #extended_where_clause {
+				type Desc = #describe_type;
+				fn describe(&self) -> Self::Desc {
+					#describe_body
+				}
+
+				type Change = #change_type;
+				fn comparison(&self, other: &Self) -> comparable::Changed<Self::Change> {
+					self.comparison_with(other, &comparable::CompareOptions::default())
+				}
+
+				#[allow(unused_variables)]
+				fn comparison_with(
+					&self,
+					other: &Self,
+					options: &comparable::CompareOptions,
+				) -> comparable::Changed<Self::Change> {
+					#change_body
+				}
+
+				#same_identity
+			}
+		}
+	}
+
+	fn impl_patch(
+		name: &syn::Ident,
+		generics: &syn::Generics,
+		apply_body: &TokenStream,
+		from_desc_body: &TokenStream,
+	) -> TokenStream {
+		let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+		let extended_where_clause = Self::where_clause(generics, quote!(comparable::Patch));
+
+		quote! {
+			impl #impl_generics comparable::Patch for #name #ty_generics #extended_where_clause {
+				fn apply(&mut self, change: Self::Change) -> Result<(), comparable::PatchError> {
+					#apply_body
+				}
+
+				fn from_desc(desc: Self::Desc) -> Result<Self, comparable::PatchError> {
+					#from_desc_body
+				}
+			}
+		}
+	}
+
+	fn impl_invert(name: &syn::Ident, generics: &syn::Generics, invert_body: &TokenStream) -> TokenStream {
+		let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+		let extended_where_clause = Self::where_clause(generics, quote!(comparable::Invert));
+
+		quote! {
+			impl #impl_generics comparable::Invert for #name #ty_generics #extended_where_clause {
+				fn invert(change: Self::Change) -> Result<Self::Change, comparable::PatchError> {
+					#invert_body
+				}
+			}
+		}
+	}
+
+	fn impl_merge(name: &syn::Ident, generics: &syn::Generics, merge_body: &TokenStream) -> TokenStream {
+		let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+		let extended_where_clause = Self::where_clause(generics, quote!(comparable::Merge));
+
+		quote! {
+			impl #impl_generics comparable::Merge for #name #ty_generics #extended_where_clause {
+				fn merge_changes(
+					left: Self::Change,
+					right: Self::Change,
+				) -> Result<Self::Change, comparable::Conflicts<Self::Change>> {
+					#merge_body
+				}
+			}
+		}
+	}
+
+	fn impl_compose(name: &syn::Ident, generics: &syn::Generics, compose_body: &TokenStream) -> TokenStream {
+		let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+		let extended_where_clause = Self::where_clause(generics, quote!(comparable::Compose));
+
+		quote! {
+			impl #impl_generics comparable::Compose for #name #ty_generics #extended_where_clause {
+				fn compose(
+					first: Self::Change,
+					second: Self::Change,
+				) -> Result<comparable::Changed<Self::Change>, comparable::PatchError> {
+					#compose_body
+				}
+			}
+		}
+	}
+
+	fn impl_render(name: &syn::Ident, generics: &syn::Generics, render_body: &TokenStream) -> TokenStream {
+		let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+		let extended_where_clause = Self::where_clause(generics, quote!(comparable::Render));
+
+		quote! {
+			impl #impl_generics comparable::Render for #name #ty_generics #extended_where_clause {
+				fn render_change<V: comparable::LeafValue>(
+					change: &Self::Change,
+					path: &mut comparable::ChangePath<V>,
+					leaves: &mut Vec<comparable::Leaf<V>>,
+				) {
+					#render_body
+				}
+			}
+		}
+	}
+
+	// The where clause of an implementation, extending that of the type with
+	// `T: #bound` for each of its generic type parameters.
+	fn where_clause(generics: &syn::Generics, bound: TokenStream) -> TokenStream {
+		let (_impl_generics, _ty_generics, where_clause) = generics.split_for_impl();
 
-		// Build where clause predicates for T: Comparable for each generic type parameter
 		let mut where_predicates = vec![];
 		for param in &generics.params {
 			if let syn::GenericParam::Type(type_param) = param {
 				let ident = &type_param.ident;
-				where_predicates.push(quote!(#ident: comparable::Comparable));
+				where_predicates.push(quote!(#ident: #bound));
 			}
 		}
 
-		let extended_where_clause = if !where_predicates.is_empty() || where_clause.is_some() {
+		if !where_predicates.is_empty() || where_clause.is_some() {
 			let existing_predicates = where_clause.map(|w| {
 				let predicates = &w.predicates;
 				quote!(#predicates,)
@@ -64,20 +233,6 @@ impl Outputs {
 			}
 		} else {
 			quote!()
-		};
-
-		quote! {
-			impl #impl_generics comparable::Comparable for #name #ty_generics #extended_where_clause {
-				type Desc = #describe_type;
-				fn describe(&self) -> Self::Desc {
-					#describe_body
-				}
-
-				type Change = #change_type;
-				fn comparison(&self, other: &Self) -> comparable::Changed<Self::Change> {
-					#change_body
-				}
-			}
 		}
 	}
 }
@@ -3,6 +3,7 @@ use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
 use std::iter::FromIterator;
 
+use crate::attrs::*;
 use crate::definition::*;
 use crate::utils::*;
 
@@ -36,7 +37,7 @@ pub fn generate_describe_body_for_structs(desc_name: &syn::Ident, st: &syn::Data
 	}
 }
 
-pub fn create_change_type_for_structs(st: &syn::DataStruct) -> Option<syn::Data> {
+pub fn create_change_type_for_structs(owner: &syn::Ident, st: &syn::DataStruct) -> Option<syn::Data> {
 	// Produce a vec that takes ignore fields into account.
 	match field_count(true, st.fields.iter()) {
 		0 => None,
@@ -46,7 +47,7 @@ pub fn create_change_type_for_structs(st: &syn::DataStruct) -> Option<syn::Data>
 			// change data, rather than the varying combinations that could occur
 			// in the case of multiple fields.
 			Some(map_on_fields_over_datastruct(true, st, |r| syn::Field {
-				ty: Definition::assoc_type(&r.field.ty, "Change"),
+				ty: Definition::field_change_type(owner, r),
 				..r.field.clone()
 			}))
 		}
@@ -57,7 +58,7 @@ pub fn create_change_type_for_structs(st: &syn::DataStruct) -> Option<syn::Data>
 					ident,
 					fields: syn::Fields::Unnamed(syn::FieldsUnnamed {
 						unnamed: FromIterator::from_iter(vec![syn::Field {
-							ty: Definition::assoc_type(&r.field.ty, "Change"),
+							ty: Definition::field_change_type(owner, r),
 							attrs: Default::default(),
 							vis: syn::Visibility::Inherited,
 							ident: Default::default(),
@@ -86,6 +87,7 @@ pub fn create_change_type_for_structs(st: &syn::DataStruct) -> Option<syn::Data>
 }
 
 pub fn generate_comparison_body_for_structs(
+	type_name: &syn::Ident,
 	change_name: &syn::Ident,
 	generics: &syn::Generics,
 	st: &syn::DataStruct,
@@ -101,7 +103,20 @@ pub fn generate_comparison_body_for_structs(
 			};
 			let self_value = (*r.accessor)(&format_ident!("self"));
 			let other_value = (*r.accessor)(&format_ident!("other"));
-			((name, quote!(#self_value.comparison(&#other_value))), variant)
+			// Like in its rendering, the sole field of a newtype struct has no
+			// segment of its own.
+			let path_name = field_path_name(r);
+			let segment = match &st.fields {
+				syn::Fields::Unnamed(_) if st.fields.len() == 1 => None,
+				_ => Some(quote!(comparable::PathSegment::Field(#path_name))),
+			};
+			let comparison = FieldAttributes::from(type_name, r.index, r.field).comparison(
+				&r.field.ty,
+				&quote!(#self_value),
+				&quote!(#other_value),
+				segment.as_ref(),
+			);
+			((name, comparison), variant)
 		})
 		.into_iter()
 		.unzip();
@@ -136,3 +151,418 @@ pub fn generate_comparison_body_for_structs(
 		}
 	}
 }
+
+pub fn generate_same_identity_body_for_structs(type_name: &syn::Ident, st: &syn::DataStruct) -> Option<TokenStream> {
+	let comparisons: Vec<TokenStream> = map_fields(false, st.fields.iter(), false, |r| {
+		if has_attr(&r.field.attrs, "comparable_identity").is_some() {
+			let self_value = (*r.accessor)(&format_ident!("self"));
+			let other_value = (*r.accessor)(&format_ident!("other"));
+			Some(FieldAttributes::from(type_name, r.index, r.field).comparison(
+				&r.field.ty,
+				&quote!(#self_value),
+				&quote!(#other_value),
+				None,
+			))
+		} else {
+			None
+		}
+	})
+	.into_iter()
+	.flatten()
+	.collect();
+	if comparisons.is_empty() {
+		None
+	} else {
+		Some(quote!(#(#comparisons.is_unchanged())&&*))
+	}
+}
+
+pub fn generate_apply_body_for_structs(
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	st: &syn::DataStruct,
+) -> TokenStream {
+	let (variants, applications): (Vec<syn::Ident>, Vec<TokenStream>) =
+		map_fields(true, st.fields.iter(), true, |r: &FieldRef| -> (syn::Ident, TokenStream) {
+			let variant = Definition::variant_name_from_field(r.index, &r.field.ident);
+			let application = if r.is_synthetic {
+				let name = r.field.ident.as_ref().expect("Found unnamed synthetic field").to_string();
+				quote! {
+					Err(comparable::PatchError::Unsupported(format!(
+						"synthetic field `{}` of {} cannot be changed",
+						#name,
+						stringify!(#type_name),
+					)))
+				}
+			} else {
+				let self_value = (*r.accessor)(&format_ident!("self"));
+				FieldAttributes::from(type_name, r.index, r.field).apply(
+					&r.field.ty,
+					&quote!(#self_value),
+					&quote!(change),
+				)
+			};
+			(variant, application)
+		})
+		.into_iter()
+		.unzip();
+
+	if applications.is_empty() {
+		quote! {
+			let _ = change;
+			Ok(())
+		}
+	} else if applications.len() == 1 {
+		let binding = match &st.fields {
+			syn::Fields::Named(named) => {
+				let name = map_fields(true, named.named.iter(), true, |r| r.field.ident.clone());
+				quote!(#change_name { #(#name)*: change })
+			}
+			_ => quote!(#change_name(change)),
+		};
+		quote! {
+			let #binding = change;
+			#(#applications)*
+		}
+	} else {
+		quote! {
+			for change in change {
+				match change {
+					#(#change_name::#variants(change) => #applications?,)*
+				}
+			}
+			Ok(())
+		}
+	}
+}
+
+pub fn generate_invert_body_for_structs(
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	st: &syn::DataStruct,
+) -> TokenStream {
+	let (variants, inversions): (Vec<syn::Ident>, Vec<TokenStream>) =
+		map_fields(true, st.fields.iter(), true, |r: &FieldRef| -> (syn::Ident, TokenStream) {
+			(
+				Definition::variant_name_from_field(r.index, &r.field.ident),
+				FieldAttributes::from(type_name, r.index, r.field).invert(&r.field.ty, &quote!(change)),
+			)
+		})
+		.into_iter()
+		.unzip();
+
+	if inversions.is_empty() {
+		quote!(Ok(change))
+	} else if inversions.len() == 1 {
+		let (binding, inverted) = match &st.fields {
+			syn::Fields::Named(named) => {
+				let name = map_fields(true, named.named.iter(), true, |r| r.field.ident.clone());
+				(quote!(#change_name { #(#name)*: change }), quote!(#change_name { #(#name)*: #(#inversions)*? }))
+			}
+			_ => (quote!(#change_name(change)), quote!(#change_name(#(#inversions)*?))),
+		};
+		quote! {
+			let #binding = change;
+			Ok(#inverted)
+		}
+	} else {
+		quote! {
+			change
+				.into_iter()
+				.map(|change| {
+					Ok(match change {
+						#(#change_name::#variants(change) => #change_name::#variants(#inversions?),)*
+					})
+				})
+				.collect()
+		}
+	}
+}
+
+pub fn generate_merge_body_for_structs(
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	st: &syn::DataStruct,
+) -> TokenStream {
+	let fields: Vec<(syn::Ident, FieldAttributes, syn::Type)> =
+		map_fields(true, st.fields.iter(), true, |r: &FieldRef| {
+			(
+				Definition::variant_name_from_field(r.index, &r.field.ident),
+				FieldAttributes::from(type_name, r.index, r.field),
+				r.field.ty.clone(),
+			)
+		});
+
+	if fields.is_empty() {
+		quote! {
+			let _ = right;
+			Ok(left)
+		}
+	} else if fields.len() == 1 {
+		let (_, field_attrs, ty) = &fields[0];
+		let merged = field_attrs.merge(ty, &quote!(left), &quote!(right));
+		let binding = |var: TokenStream| match &st.fields {
+			syn::Fields::Named(named) => {
+				let name = map_fields(true, named.named.iter(), true, |r| r.field.ident.clone());
+				quote!(#change_name { #(#name)*: #var })
+			}
+			_ => quote!(#change_name(#var)),
+		};
+		let (left_binding, right_binding, wrap) =
+			(binding(quote!(left)), binding(quote!(right)), binding(quote!(change)));
+		quote! {
+			let #left_binding = left;
+			let #right_binding = right;
+			#merged
+				.map(|change| #wrap)
+				.map_err(|conflicts| conflicts.map(|change| #wrap))
+		}
+	} else {
+		generate_merge_of_field_changes(change_name, &fields, &quote!())
+	}
+}
+
+/// Merge two lists of changes to the fields of a struct, or of an enum variant
+/// with `variant_struct_fields`, in which each field is changed at most once.
+/// `vec_wrap` turns such a list into the change being merged.
+pub fn generate_merge_of_field_changes(
+	change_name: &syn::Ident,
+	fields: &[(syn::Ident, FieldAttributes, syn::Type)],
+	vec_wrap: &TokenStream,
+) -> TokenStream {
+	let variants: Vec<&syn::Ident> = fields.iter().map(|f| &f.0).collect();
+	let left_vars: Vec<syn::Ident> = (0..fields.len()).map(|i| format_ident!("left_var{}", i)).collect();
+	let right_vars: Vec<syn::Ident> = (0..fields.len()).map(|i| format_ident!("right_var{}", i)).collect();
+	let merges = fields.iter().zip(left_vars.iter().zip(&right_vars)).map(|((variant, field_attrs, ty), (l, r))| {
+		let merged = field_attrs.merge_part(
+			ty,
+			&quote!(#l),
+			&quote!(#r),
+			&quote!(conflicts),
+			&quote!(|change| #vec_wrap(vec![#change_name::#variant(change)])),
+		);
+		quote!(merged.extend(#merged.map(#change_name::#variant));)
+	});
+	quote! {
+		#(let mut #left_vars = comparable::Changed::Unchanged;)*
+		#(let mut #right_vars = comparable::Changed::Unchanged;)*
+		for change in left {
+			match change {
+				#(#change_name::#variants(change) => #left_vars = comparable::Changed::Changed(change),)*
+			}
+		}
+		for change in right {
+			match change {
+				#(#change_name::#variants(change) => #right_vars = comparable::Changed::Changed(change),)*
+			}
+		}
+		let mut conflicts = Vec::new();
+		let mut merged = Vec::new();
+		#(#merges)*
+		if conflicts.is_empty() {
+			Ok(#vec_wrap(merged))
+		} else {
+			Err(comparable::Conflicts(conflicts))
+		}
+	}
+}
+
+pub fn generate_compose_body_for_structs(
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	st: &syn::DataStruct,
+) -> TokenStream {
+	let fields: Vec<(syn::Ident, FieldAttributes, syn::Type)> =
+		map_fields(true, st.fields.iter(), true, |r: &FieldRef| {
+			(
+				Definition::variant_name_from_field(r.index, &r.field.ident),
+				FieldAttributes::from(type_name, r.index, r.field),
+				r.field.ty.clone(),
+			)
+		});
+
+	if fields.is_empty() {
+		quote! {
+			let _ = (first, second);
+			Ok(comparable::Changed::Unchanged)
+		}
+	} else if fields.len() == 1 {
+		let (_, field_attrs, ty) = &fields[0];
+		let composed = field_attrs.compose(ty, &quote!(first), &quote!(second));
+		let binding = |var: TokenStream| match &st.fields {
+			syn::Fields::Named(named) => {
+				let name = map_fields(true, named.named.iter(), true, |r| r.field.ident.clone());
+				quote!(#change_name { #(#name)*: #var })
+			}
+			_ => quote!(#change_name(#var)),
+		};
+		let (first_binding, second_binding, wrap) =
+			(binding(quote!(first)), binding(quote!(second)), binding(quote!(change)));
+		quote! {
+			let #first_binding = first;
+			let #second_binding = second;
+			Ok(#composed?.map(|change| #wrap))
+		}
+	} else {
+		generate_compose_of_field_changes(change_name, &fields, &quote!())
+	}
+}
+
+/// Compose two lists of changes to the fields of a struct, or of an enum
+/// variant with `variant_struct_fields`, in which each field is changed at
+/// most once. `vec_wrap` turns such a list into the change being composed.
+pub fn generate_compose_of_field_changes(
+	change_name: &syn::Ident,
+	fields: &[(syn::Ident, FieldAttributes, syn::Type)],
+	vec_wrap: &TokenStream,
+) -> TokenStream {
+	let variants: Vec<&syn::Ident> = fields.iter().map(|f| &f.0).collect();
+	let first_vars: Vec<syn::Ident> = (0..fields.len()).map(|i| format_ident!("first_var{}", i)).collect();
+	let second_vars: Vec<syn::Ident> = (0..fields.len()).map(|i| format_ident!("second_var{}", i)).collect();
+	let compositions =
+		fields.iter().zip(first_vars.iter().zip(&second_vars)).map(|((variant, field_attrs, ty), (f, s))| {
+			let composed = field_attrs.compose_changed(ty, &quote!(#f), &quote!(#s));
+			quote!(composed.extend(#composed?.map(#change_name::#variant));)
+		});
+	quote! {
+		#(let mut #first_vars = comparable::Changed::Unchanged;)*
+		#(let mut #second_vars = comparable::Changed::Unchanged;)*
+		for change in first {
+			match change {
+				#(#change_name::#variants(change) => #first_vars = comparable::Changed::Changed(change),)*
+			}
+		}
+		for change in second {
+			match change {
+				#(#change_name::#variants(change) => #second_vars = comparable::Changed::Changed(change),)*
+			}
+		}
+		let mut composed = Vec::new();
+		#(#compositions)*
+		if composed.is_empty() {
+			Ok(comparable::Changed::Unchanged)
+		} else {
+			Ok(comparable::Changed::Changed(#vec_wrap(composed)))
+		}
+	}
+}
+
+pub fn generate_render_body_for_structs(
+	type_name: &syn::Ident,
+	change_name: &syn::Ident,
+	st: &syn::DataStruct,
+) -> TokenStream {
+	let fields: Vec<(syn::Ident, FieldAttributes, syn::Type, String)> =
+		map_fields(true, st.fields.iter(), true, |r: &FieldRef| {
+			(
+				Definition::variant_name_from_field(r.index, &r.field.ident),
+				FieldAttributes::from(type_name, r.index, r.field),
+				r.field.ty.clone(),
+				field_path_name(r),
+			)
+		});
+
+	if fields.is_empty() {
+		quote! {
+			let _ = (change, path, leaves);
+		}
+	} else if fields.len() == 1 {
+		let (_, field_attrs, ty, name) = &fields[0];
+		let (binding, rendered) = match &st.fields {
+			syn::Fields::Named(named) => {
+				let ident = map_fields(true, named.named.iter(), true, |r| r.field.ident.clone());
+				(
+					quote!(#change_name { #(#ident)*: change }),
+					field_attrs.render_at(ty, &quote!(change), &quote!(comparable::PathSegment::Field(#name))),
+				)
+			}
+			// Like serde, treat the sole field of a newtype struct as the value
+			// itself.
+			_ if st.fields.len() == 1 => (quote!(#change_name(change)), field_attrs.render(ty, &quote!(change))),
+			_ => (
+				quote!(#change_name(change)),
+				field_attrs.render_at(ty, &quote!(change), &quote!(comparable::PathSegment::Field(#name))),
+			),
+		};
+		quote! {
+			let #binding = change;
+			#rendered
+		}
+	} else {
+		generate_render_of_field_changes(change_name, &fields)
+	}
+}
+
+/// Render a list of changes to the fields of a struct, or of an enum variant
+/// with `variant_struct_fields`, each under the name of its field.
+pub fn generate_render_of_field_changes(
+	change_name: &syn::Ident,
+	fields: &[(syn::Ident, FieldAttributes, syn::Type, String)],
+) -> TokenStream {
+	let arms = fields.iter().map(|(variant, field_attrs, ty, name)| {
+		let rendered = field_attrs.render_at(ty, &quote!(change), &quote!(comparable::PathSegment::Field(#name)));
+		quote!(#change_name::#variant(change) => #rendered)
+	});
+	quote! {
+		for change in change {
+			match change {
+				#(#arms,)*
+			}
+		}
+	}
+}
+
+/// The name of a field in the path to a change: its own name, or its position
+/// for unnamed fields.
+pub fn field_path_name(r: &FieldRef) -> String {
+	match &r.field.ident {
+		Some(ident) => ident.to_string(),
+		None => r.index.to_string(),
+	}
+}
+
+pub fn generate_from_desc_body_for_structs(
+	type_name: &syn::Ident,
+	desc_name: &syn::Ident,
+	st: &syn::DataStruct,
+) -> TokenStream {
+	if let Some(ignored) = st.fields.iter().position(|f| has_attr(&f.attrs, "comparable_ignore").is_some()) {
+		let name = match &st.fields.iter().nth(ignored).and_then(|f| f.ident.as_ref()) {
+			Some(ident) => ident.to_string(),
+			None => ignored.to_string(),
+		};
+		return quote! {
+			let _ = desc;
+			Err(comparable::PatchError::Unsupported(format!(
+				"field `{}` of {} is ignored, so it cannot be rebuilt from a description",
+				#name,
+				stringify!(#type_name),
+			)))
+		};
+	}
+	match &st.fields {
+		syn::Fields::Named(named) => {
+			let (names, types): (Vec<&syn::Ident>, Vec<&syn::Type>) = named
+				.named
+				.iter()
+				.map(|f| (f.ident.as_ref().expect("Found unnamed field in named struct"), &f.ty))
+				.unzip();
+			quote! {
+				let #desc_name { #(#names,)* .. } = desc;
+				Ok(Self { #(#names: <#types as comparable::Patch>::from_desc(#names)?),* })
+			}
+		}
+		syn::Fields::Unnamed(unnamed) => {
+			let (vars, types): (Vec<syn::Ident>, Vec<&syn::Type>) =
+				unnamed.unnamed.iter().enumerate().map(|(i, f)| (format_ident!("desc{}", i), &f.ty)).unzip();
+			quote! {
+				let #desc_name(#(#vars),*) = desc;
+				Ok(Self(#(<#types as comparable::Patch>::from_desc(#vars)?),*))
+			}
+		}
+		syn::Fields::Unit => quote! {
+			let _ = desc;
+			Ok(Self)
+		},
+	}
+}
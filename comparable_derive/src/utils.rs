@@ -89,6 +89,9 @@ pub fn map_on_fields(
 pub struct FieldRef<'a> {
 	pub index: usize,
 	pub field: &'a syn::Field,
+	// Synthetic fields are computed from the value, and have no place of
+	// their own in it.
+	pub is_synthetic: bool,
 	pub accessor: Box<dyn Fn(&syn::Ident) -> syn::Expr>,
 }
 
@@ -130,6 +133,7 @@ pub fn map_fields<'a, 'b: 'a, R>(
 							vis: syn::Visibility::Inherited,
 							colon_token: Default::default(),
 						},
+						is_synthetic: true,
 						accessor: Box::new(move |x| {
 							syn::parse2(quote!((#closure)(&#x))).expect("Could not create synthetic accessor!")
 						}),
@@ -139,7 +143,7 @@ pub fn map_fields<'a, 'b: 'a, R>(
 			}
 		}
 		if has_attr(&field.attrs, "comparable_ignore").is_none() || !allow_ignore {
-			result.push(f(&FieldRef { index, field, accessor: standard_accessor(index, field) }));
+			result.push(f(&FieldRef { index, field, is_synthetic: false, accessor: standard_accessor(index, field) }));
 		}
 		index += 1;
 	});
@@ -14,6 +14,69 @@ pub fn impl_for_tuple(tup: syn::TypeTuple) -> proc_macro2::TokenStream {
 			quote!(#i)
 		})
 		.collect::<Vec<_>>();
+	let descs =
+		enumerated_elems.iter().map(|(i, t)| syn::Ident::new(&format!("desc{}", i,)[..], t.span())).collect::<Vec<_>>();
+	// A tuple of one element is described by, and changes as, that element.
+	let (change_pattern, desc_pattern, inverted) = if results.len() == 1 {
+		(quote!(#(#results)*), quote!(#(#descs)*), quote!(#(invert_changed::<#field_types>(#results))*))
+	} else {
+		(quote!((#(#results),*)), quote!((#(#descs),*)), quote!(Ok((#(invert_changed::<#field_types>(#results)?),*))))
+	};
+	let lefts =
+		enumerated_elems.iter().map(|(i, t)| syn::Ident::new(&format!("left{}", i,)[..], t.span())).collect::<Vec<_>>();
+	let rights = enumerated_elems
+		.iter()
+		.map(|(i, t)| syn::Ident::new(&format!("right{}", i,)[..], t.span()))
+		.collect::<Vec<_>>();
+	let merged = if results.len() == 1 {
+		quote!(#(merge_part::<#field_types, _>(left, right, &mut conflicts, Changed::Changed))*)
+	} else {
+		// A conflict in one element is reported as a change to that element alone.
+		let wraps = (0..results.len()).map(|i| {
+			let elems = (0..results.len()).map(|j| {
+				if i == j {
+					quote!(Changed::Changed(change))
+				} else {
+					quote!(Changed::Unchanged)
+				}
+			});
+			quote!(|change| (#(#elems),*))
+		});
+		quote! {{
+			let (#(#lefts),*) = left;
+			let (#(#rights),*) = right;
+			(#(merge_part::<#field_types, _>(#lefts, #rights, &mut conflicts, #wraps)),*)
+		}}
+	};
+	let merged = quote! {
+		let mut conflicts = Vec::new();
+		let merged = #merged;
+		if conflicts.is_empty() {
+			Ok(merged)
+		} else {
+			Err(Conflicts(conflicts))
+		}
+	};
+	let composed = if results.len() == 1 {
+		quote! {
+			Ok(match #(compose_changed::<#field_types>(first, second)?)* {
+				Changed::Unchanged => Changed::Unchanged,
+				change => Changed::Changed(change),
+			})
+		}
+	} else {
+		quote! {
+			let (#(#lefts),*) = first;
+			let (#(#rights),*) = second;
+			#(let #results = compose_changed::<#field_types>(#lefts, #rights)?;)*
+			if #(#results.is_unchanged())&&* {
+				Ok(Changed::Unchanged)
+			} else {
+				Ok(Changed::Changed((#(#results),*)))
+			}
+		}
+	};
+	let names = (0..results.len()).map(|i| i.to_string()).collect::<Vec<_>>();
 	quote! {
 		#[automatically_derived]
 		impl <#(#field_types: Comparable,)*> Comparable for (#(#field_types,)*) {
@@ -26,9 +89,13 @@ pub fn impl_for_tuple(tup: syn::TypeTuple) -> proc_macro2::TokenStream {
 			type Change = (#(Changed<#field_types::Change>),*);
 
 			fn comparison(&self, other: &Self) -> Changed<Self::Change> {
+				self.comparison_with(other, &CompareOptions::default())
+			}
+
+			fn comparison_with(&self, other: &Self, options: &CompareOptions) -> Changed<Self::Change> {
 				let mut has_change = false;
 				#(
-					let #results = self.#indexes.comparison(&other.#indexes);
+					let #results = options.compare_at(PathSegment::Field(#names), &self.#indexes, &other.#indexes);
 					has_change = has_change || !#results.is_unchanged();
 				)*
 				if has_change {
@@ -38,5 +105,57 @@ pub fn impl_for_tuple(tup: syn::TypeTuple) -> proc_macro2::TokenStream {
 				}
 			}
 		}
+
+		#[automatically_derived]
+		impl <#(#field_types: Patch,)*> Patch for (#(#field_types,)*) {
+			fn apply(&mut self, change: Self::Change) -> Result<(), PatchError> {
+				let #change_pattern = change;
+				#(
+					if let Changed::Changed(change) = #results {
+						self.#indexes.apply(change)?;
+					}
+				)*
+				Ok(())
+			}
+
+			fn from_desc(desc: Self::Desc) -> Result<Self, PatchError> {
+				let #desc_pattern = desc;
+				Ok((#(#field_types::from_desc(#descs)?,)*))
+			}
+		}
+
+		#[automatically_derived]
+		impl <#(#field_types: Invert,)*> Invert for (#(#field_types,)*) {
+			fn invert(change: Self::Change) -> Result<Self::Change, PatchError> {
+				let #change_pattern = change;
+				#inverted
+			}
+		}
+
+		#[automatically_derived]
+		impl <#(#field_types: Merge,)*> Merge for (#(#field_types,)*) {
+			fn merge_changes(left: Self::Change, right: Self::Change) -> Result<Self::Change, Conflicts<Self::Change>> {
+				#merged
+			}
+		}
+
+		#[automatically_derived]
+		impl <#(#field_types: Compose,)*> Compose for (#(#field_types,)*) {
+			fn compose(first: Self::Change, second: Self::Change) -> Result<Changed<Self::Change>, PatchError> {
+				#composed
+			}
+		}
+
+		#[automatically_derived]
+		impl <#(#field_types: Render,)*> Render for (#(#field_types,)*) {
+			fn render_change<V: LeafValue>(change: &Self::Change, path: &mut ChangePath<V>, leaves: &mut Vec<Leaf<V>>) {
+				let #change_pattern = change;
+				#(
+					path.push(PathSegment::Field(#names));
+					render_changed::<#field_types, _>(#results, path, leaves);
+					path.pop();
+				)*
+			}
+		}
 	}
 }
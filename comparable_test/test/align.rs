@@ -0,0 +1,168 @@
+use comparable::{Changed::*, *};
+
+#[test]
+fn test_aligned_unchanged() {
+	assert_changes!(&Aligned(vec![1, 2, 3]), &Aligned(vec![1, 2, 3]), Unchanged);
+	assert_changes!(&Aligned(Vec::<i32>::new()), &Aligned(vec![]), Unchanged);
+}
+
+#[test]
+fn test_aligned_insert_front() {
+	assert_changes!(&Aligned(vec![1, 2, 3]), &Aligned(vec![0, 1, 2, 3]), Changed(vec![VecChange::Added(0, 0)]));
+}
+
+#[test]
+fn test_aligned_insert_middle() {
+	assert_changes!(
+		&Aligned(vec![1, 2, 3, 4, 5]),
+		&Aligned(vec![1, 2, 10, 11, 3, 4, 5]),
+		Changed(vec![VecChange::Added(2, 10), VecChange::Added(3, 11)]),
+	);
+}
+
+#[test]
+fn test_aligned_remove_middle() {
+	assert_changes!(&Aligned(vec![1, 2, 3, 4, 5]), &Aligned(vec![1, 2, 4, 5]), Changed(vec![VecChange::Removed(2, 3)]),);
+}
+
+#[test]
+fn test_aligned_changed_pairs() {
+	// A removal and an addition at the same place pair up as a change, which
+	// carries the element's index in the original vector.
+	assert_changes!(
+		&Aligned(vec![1, 2, 3, 4]),
+		&Aligned(vec![0, 1, 20, 3, 4]),
+		Changed(vec![VecChange::Added(0, 0), VecChange::Changed(1, I32Change(2, 20))]),
+	);
+	assert_changes!(
+		&Aligned(vec![1, 2, 3, 4]),
+		&Aligned(vec![1, 20, 30, 40, 4]),
+		Changed(vec![
+			VecChange::Changed(1, I32Change(2, 20)),
+			VecChange::Changed(2, I32Change(3, 30)),
+			VecChange::Added(3, 40),
+		]),
+	);
+}
+
+#[test]
+fn test_aligned_everything_replaced() {
+	assert_changes!(
+		&Aligned(vec![1, 2]),
+		&Aligned(vec![]),
+		Changed(vec![VecChange::Removed(0, 1), VecChange::Removed(1, 2)])
+	);
+	assert_changes!(
+		&Aligned(vec![1, 2]),
+		&Aligned(vec![3, 4, 5]),
+		Changed(vec![
+			VecChange::Changed(0, I32Change(1, 3)),
+			VecChange::Changed(1, I32Change(2, 4)),
+			VecChange::Added(2, 5),
+		]),
+	);
+}
+
+#[test]
+fn test_aligned_describe() {
+	assert_eq!(Aligned(vec![1u8, 2]).describe(), vec![1u8, 2]);
+}
+
+#[test]
+fn test_aligned_field_attribute() {
+	#[derive(Comparable)]
+	struct Fixture {
+		name: String,
+		#[comparable_aligned]
+		values: Vec<u32>,
+	}
+
+	assert_changes!(
+		&Fixture { name: "a".to_string(), values: vec![1, 2, 3] },
+		&Fixture { name: "a".to_string(), values: vec![1, 5, 2, 3] },
+		Changed(vec![FixtureChange::Values(vec![VecChange::Added(1, 5)])]),
+	);
+}
+
+#[test]
+fn test_aligned_enum_field_attribute() {
+	#[derive(Comparable)]
+	enum Fixture {
+		Values(#[comparable_aligned] Vec<u32>),
+		Named {
+			#[comparable_aligned]
+			values: Vec<u32>,
+			count: u32,
+		},
+	}
+
+	assert_changes!(
+		&Fixture::Values(vec![1, 2, 3]),
+		&Fixture::Values(vec![2, 3]),
+		Changed(FixtureChange::BothValues(vec![VecChange::Removed(0, 1)])),
+	);
+	assert_changes!(
+		&Fixture::Named { values: vec![1, 2, 3], count: 1 },
+		&Fixture::Named { values: vec![2, 3], count: 1 },
+		Changed(FixtureChange::BothNamed { values: Changed(vec![VecChange::Removed(0, 1)]), count: Unchanged }),
+	);
+}
+
+#[test]
+fn test_aligned_moved() {
+	assert_changes!(
+		&Aligned(vec![1, 2, 3]),
+		&Aligned(vec![3, 1, 2]),
+		Changed(vec![VecChange::Moved { from: 2, to: 0, change: Unchanged }]),
+	);
+	assert_changes!(
+		&Aligned(vec![1, 2, 3, 4]),
+		&Aligned(vec![2, 3, 4, 1]),
+		Changed(vec![VecChange::Moved { from: 0, to: 3, change: Unchanged }]),
+	);
+}
+
+#[test]
+fn test_aligned_moved_and_edited() {
+	#[derive(Comparable)]
+	struct Job {
+		#[comparable_identity]
+		id: u32,
+		priority: u8,
+	}
+
+	let job = |id, priority| Job { id, priority };
+
+	// Matching by identity follows an element that was edited in place...
+	assert_changes!(
+		&Aligned(vec![job(1, 0), job(2, 0), job(3, 0)]),
+		&Aligned(vec![job(1, 0), job(2, 5), job(3, 0)]),
+		Changed(vec![VecChange::Changed(1, vec![JobChange::Priority(U8Change(0, 5))])]),
+	);
+
+	// ...or that was both edited and moved.
+	assert_changes!(
+		&Aligned(vec![job(1, 0), job(2, 0), job(3, 0)]),
+		&Aligned(vec![job(3, 9), job(1, 0), job(2, 0)]),
+		Changed(vec![VecChange::Moved { from: 2, to: 0, change: Changed(vec![JobChange::Priority(U8Change(0, 9))]) }]),
+	);
+
+	// Elements whose identities differ are not matched up, even when they sit
+	// at the same place.
+	assert_changes!(
+		&Aligned(vec![job(1, 0), job(2, 0)]),
+		&Aligned(vec![job(1, 0), job(4, 0)]),
+		Changed(vec![VecChange::Changed(1, vec![JobChange::Id(U32Change(2, 4))])]),
+	);
+	assert!(job(1, 0).same_identity(&job(1, 7)));
+	assert!(!job(1, 0).same_identity(&job(2, 0)));
+}
+
+#[test]
+fn test_vec_never_moves() {
+	assert_changes!(
+		&vec![1, 2],
+		&vec![2, 1],
+		Changed(vec![VecChange::Changed(0, I32Change(1, 2)), VecChange::Changed(1, I32Change(2, 1))]),
+	);
+}
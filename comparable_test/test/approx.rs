@@ -0,0 +1,109 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+
+#[test]
+fn test_float_tolerance() {
+	let abs = FloatTolerance { abs: 0.5, ..FloatTolerance::EXACT };
+	assert!(abs.eq_f64(1.0, 1.5));
+	assert!(!abs.eq_f64(1.0, 1.6));
+	assert!(!abs.eq_f64(f64::INFINITY, f64::MAX));
+	assert!(abs.eq_f64(f64::INFINITY, f64::INFINITY));
+	assert!(!abs.eq_f64(f64::NAN, f64::NAN));
+
+	let rel = FloatTolerance { rel: 0.01, ..FloatTolerance::EXACT };
+	assert!(rel.eq_f64(1000.0, 1010.0));
+	assert!(!rel.eq_f64(1.0, 1.1));
+	assert!(rel.eq_f32(1e30, 1.005e30));
+
+	let ulps = FloatTolerance { ulps: 2, ..FloatTolerance::EXACT };
+	let next = |x: f64| f64::from_bits(x.to_bits() + 1);
+	assert!(ulps.eq_f64(1.0, next(next(1.0))));
+	assert!(!ulps.eq_f64(1.0, next(next(next(1.0)))));
+	assert!(ulps.eq_f32(1.0, f32::from_bits(1f32.to_bits() - 2)));
+	assert!(ulps.eq_f64(0.0, -0.0));
+	assert!(!ulps.eq_f64(f64::MIN_POSITIVE, -f64::MIN_POSITIVE));
+
+	let nan = FloatTolerance { nan_equal: true, ..FloatTolerance::EXACT };
+	assert!(nan.eq_f32(f32::NAN, -f32::NAN));
+	assert!(!nan.eq_f32(f32::NAN, 0.0));
+	assert_eq!(FloatTolerance::default(), FloatTolerance::EXACT);
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_patch]
+#[comparable_merge]
+struct Reading {
+	sensor: String,
+	#[comparable_tolerance(abs = 1e-9)]
+	value: f64,
+	#[comparable_tolerance(rel = 0.01, ulps = 4, nan_equal)]
+	calibration: f32,
+	raw: f64,
+}
+
+#[test]
+fn test_tolerance_attribute() {
+	let reading = |value, calibration, raw| Reading { sensor: "s".to_string(), value, calibration, raw };
+	assert_changes!(&reading(0.1 + 0.2, 1.0, 0.0), &reading(0.3, 1.005, 0.0), Unchanged);
+	assert_changes!(&reading(0.0, f32::NAN, 0.0), &reading(0.0, f32::NAN, 0.0), Unchanged);
+	// Fields without the attribute are compared exactly.
+	assert_changes!(
+		&reading(0.0, 1.0, 0.1 + 0.2),
+		&reading(0.0, 1.0, 0.3),
+		Changed(vec![ReadingChange::Raw(F64Change(0.1 + 0.2, 0.3))])
+	);
+	assert_changes!(
+		&reading(0.3, 1.0, 0.0),
+		&reading(0.4, 1.5, 0.0),
+		Changed(vec![ReadingChange::Value(F64Change(0.3, 0.4)), ReadingChange::Calibration(F32Change(1.0, 1.5))])
+	);
+
+	// A change applies to a value within the tolerance of its old value.
+	let mut value = reading(0.3, 1.0, 0.0);
+	value.apply(reading(0.1 + 0.2, 1.0, 0.0).comparison(&reading(0.5, 1.0, 0.0)).unwrap_or_default()).unwrap();
+	assert_eq!(value, reading(0.5, 1.0, 0.0));
+	assert!(value.apply(vec![ReadingChange::Value(F64Change(0.4, 0.6))]).is_err());
+
+	assert_eq!(
+		merge(&reading(0.0, 1.0, 0.0), &reading(1.0, 1.0, 0.0), &reading(1.0 + 1e-12, 1.0, 0.0)),
+		Ok(reading(1.0, 1.0, 0.0))
+	);
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_render]
+enum Measure {
+	Length(#[comparable_tolerance(abs = 1)] f64),
+}
+
+#[test]
+fn test_tolerance_attribute_in_enum() {
+	assert_changes!(&Measure::Length(1.0), &Measure::Length(1.75), Unchanged);
+	assert_eq!(
+		render::<Measure>(&Measure::Length(1.0).comparison(&Measure::Length(2.5))).to_string(),
+		"::Length: 1.0 -> 2.5"
+	);
+}
+
+struct NanEqual;
+
+impl Tolerance for NanEqual {
+	const TOLERANCE: FloatTolerance = FloatTolerance { nan_equal: true, ..FloatTolerance::EXACT };
+}
+
+proptest! {
+
+#[test]
+fn test_approx_proptest(a in any::<f64>(), b in any::<f64>()) {
+	let (a, b) = (Approx::<f64, NanEqual>::new(a), Approx::<f64, NanEqual>::new(b));
+	prop_assert!(a.comparison(&a).is_unchanged());
+	let mut value = a;
+	if let Changed(change) = a.comparison(&b) {
+		value.apply(change).unwrap();
+	}
+	prop_assert!(value.comparison(&b).is_unchanged());
+	prop_assert!(compose_changed::<Approx<f64, NanEqual>>(a.comparison(&b), b.comparison(&a)).unwrap().is_unchanged());
+	prop_assert!(merge(&a, &a, &b).unwrap().comparison(&b).is_unchanged());
+}
+
+}
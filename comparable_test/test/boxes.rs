@@ -1,7 +1,10 @@
 use comparable::{
-	assert_changes, pretty_assert_changes, prop_assert_changes, prop_pretty_assert_changes, Changed::*, I32Change,
+	assert_changes, pretty_assert_changes, prop_assert_changes, prop_pretty_assert_changes, Changed::*, I32Change, *,
 };
 use proptest::prelude::*;
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
 
 #[test]
 fn test_box() {
@@ -9,6 +12,60 @@ fn test_box() {
 	pretty_assert_changes!(&Box::new(100), &Box::new(200), Changed(I32Change(100, 200)));
 }
 
+#[test]
+fn test_rc_arc() {
+	assert_changes!(&Rc::new(100), &Rc::new(200), Changed(I32Change(100, 200)));
+	assert_changes!(&Arc::new(100), &Arc::new(100), Unchanged);
+
+	let mut shared = Rc::new(1);
+	let other = shared.clone();
+	shared.apply(I32Change(1, 2)).unwrap();
+	assert_eq!((*shared, *other), (2, 1));
+	assert_eq!(Arc::<i32>::from_desc(3), Ok(Arc::new(3)));
+}
+
+#[test]
+fn test_pointer_identity() {
+	let nan = Rc::new(f64::NAN);
+	assert!(!nan.comparison(&nan).is_unchanged());
+	let options = CompareOptions { pointer_identity: true, ..CompareOptions::default() };
+	assert_eq!(nan.comparison_with(&nan, &options), Unchanged);
+	assert!(!nan.comparison_with(&Rc::new(f64::NAN), &options).is_unchanged());
+
+	let shared = Arc::new(vec![1.0, f64::NAN]);
+	assert_eq!(shared.comparison_with(&shared.clone(), &options), Unchanged);
+}
+
+#[test]
+fn test_cow() {
+	let borrowed: Cow<str> = Cow::Borrowed("foo");
+	let owned: Cow<str> = Cow::Owned("bar".to_string());
+	assert_changes!(&borrowed, &owned, Changed(StringChange("foo".to_string(), "bar".to_string())));
+	assert_changes!(&borrowed, &Cow::Owned("foo".to_string()), Unchanged);
+
+	let mut patched = borrowed.clone();
+	patched.apply(StringChange("foo".to_string(), "bar".to_string())).unwrap();
+	assert_eq!(patched, owned);
+	assert_eq!(render::<Cow<str>>(&borrowed.comparison(&owned)).to_string(), "\"foo\" -> \"bar\"");
+
+	let number: Cow<i32> = Cow::Owned(1);
+	assert_changes!(&number, &Cow::Borrowed(&2), Changed(I32Change(1, 2)));
+}
+
+#[derive(Comparable, Clone, PartialEq, Debug)]
+struct Shared {
+	name: Rc<String>,
+	count: Arc<i32>,
+	label: Cow<'static, str>,
+}
+
+#[test]
+fn test_derive_shared() {
+	let old = Shared { name: Rc::new("a".to_string()), count: Arc::new(1), label: Cow::Borrowed("x") };
+	let new = Shared { count: Arc::new(2), ..old.clone() };
+	assert_changes!(&old, &new, Changed(vec![SharedChange::Count(I32Change(1, 2))]));
+}
+
 proptest! {
 
 #[test]
@@ -0,0 +1,111 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::sync::{Arc, Mutex, RwLock};
+
+#[test]
+fn test_refcell_cell() {
+	assert_changes!(&RefCell::new(1), &RefCell::new(2), Changed(I32Change(1, 2)));
+	assert_changes!(&Cell::new(1), &Cell::new(1), Unchanged);
+	assert_changes!(&Cell::new('a'), &Cell::new('b'), Changed(CharChange('a', 'b')));
+
+	let mut cell = RefCell::new(vec![1]);
+	cell.apply(vec![VecChange::Added(1, 2)]).unwrap();
+	assert_eq!(cell.into_inner(), vec![1, 2]);
+	assert_eq!(Cell::<u8>::from_desc(3).map(|cell| cell.get()), Ok(3));
+}
+
+#[test]
+#[should_panic(expected = "cannot compare a RefCell that is mutably borrowed")]
+fn test_refcell_mutably_borrowed() {
+	let borrowed = RefCell::new(1);
+	let _guard = borrowed.borrow_mut();
+	borrowed.comparison(&RefCell::new(2));
+}
+
+#[test]
+#[should_panic(expected = "cannot compare a RefCell that is mutably borrowed")]
+fn test_refcell_mutably_borrowed_describe() {
+	let borrowed = RefCell::new(1);
+	let _guard = borrowed.borrow_mut();
+	borrowed.describe();
+}
+
+#[test]
+#[should_panic(expected = "cannot compare a Mutex that is locked")]
+fn test_mutex_locked() {
+	let locked = Mutex::new(1);
+	let _guard = locked.lock().unwrap();
+	locked.comparison(&Mutex::new(2));
+}
+
+#[test]
+#[should_panic(expected = "cannot compare an RwLock that is locked for writing")]
+fn test_rwlock_write_locked() {
+	let locked = RwLock::new(1);
+	let _guard = locked.write().unwrap();
+	locked.describe();
+}
+
+#[test]
+fn test_mutex_rwlock() {
+	assert_changes!(&Mutex::new(1), &Mutex::new(2), Changed(I32Change(1, 2)));
+	assert_changes!(&RwLock::new(1), &RwLock::new(2), Changed(I32Change(1, 2)));
+
+	// Comparing a lock with itself must not lock it twice.
+	let mutex = Mutex::new(f64::NAN);
+	assert_eq!(mutex.comparison(&mutex), Unchanged);
+	let rwlock = RwLock::new(1);
+	assert_eq!(rwlock.comparison(&rwlock), Unchanged);
+	assert_eq!(rwlock.describe(), 1);
+
+	let mut mutex = Mutex::new(1);
+	mutex.apply(I32Change(1, 5)).unwrap();
+	assert_eq!(mutex.into_inner().unwrap(), 5);
+}
+
+#[test]
+fn test_poisoned_lock() {
+	let poisoned = Arc::new(Mutex::new(1));
+	let clone = poisoned.clone();
+	let _ = std::thread::spawn(move || {
+		let _guard = clone.lock().unwrap();
+		panic!("poison the lock");
+	})
+	.join();
+	assert!(poisoned.is_poisoned());
+
+	assert_changes!(&*poisoned, &Mutex::new(2), Changed(I32Change(1, 2)));
+	assert_eq!(poisoned.describe(), 1);
+
+	let mut poisoned = Arc::try_unwrap(poisoned).unwrap();
+	assert_eq!(poisoned.apply(I32Change(1, 2)), Err(PatchError::Mismatch("the lock is poisoned".to_string())));
+}
+
+#[derive(Comparable, Debug)]
+struct State {
+	hits: Cell<u32>,
+	log: RefCell<Vec<String>>,
+	total: Mutex<i64>,
+}
+
+#[test]
+fn test_derive_cells() {
+	let state = || State { hits: Cell::new(0), log: RefCell::new(vec![]), total: Mutex::new(0) };
+	let (old, new) = (state(), state());
+	assert_changes!(&old, &new, Unchanged);
+	new.hits.set(2);
+	*new.total.lock().unwrap() += 7;
+	assert_changes!(&old, &new, Changed(vec![StateChange::Hits(U32Change(0, 2)), StateChange::Total(I64Change(0, 7))]));
+}
+
+proptest! {
+
+#[test]
+fn test_cell_proptest(a in any::<i32>(), b in any::<i32>()) {
+	prop_assert_eq!(RefCell::new(a).comparison(&RefCell::new(b)), a.comparison(&b));
+	prop_assert_eq!(Mutex::new(a).comparison(&Mutex::new(b)), a.comparison(&b));
+	prop_assert_eq!(RwLock::new(a).comparison(&RwLock::new(b)), a.comparison(&b));
+}
+
+}
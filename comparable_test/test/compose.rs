@@ -0,0 +1,233 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Debug;
+
+// Compose the changes from `a` to `b` and from `b` to `c`, checking that
+// applying the result to `a` yields `c`.
+fn chain<T: Compose + PartialEq + Debug + Clone>(a: &T, b: &T, c: &T) -> comparable::Changed<T::Change> {
+	let mut value = a.clone();
+	if let Changed(change) = compose_changed::<T>(a.comparison(b), b.comparison(c)).unwrap() {
+		value.apply(change).unwrap();
+	}
+	assert_eq!(&value, c);
+	compose_changed::<T>(a.comparison(b), b.comparison(c)).unwrap()
+}
+
+#[test]
+fn test_compose_scalars() {
+	assert_eq!(i32::compose(I32Change(1, 2), I32Change(2, 3)), Ok(Changed(I32Change(1, 3))));
+	assert_eq!(i32::compose(I32Change(1, 2), I32Change(2, 1)), Ok(Unchanged));
+	assert!(i32::compose(I32Change(1, 2), I32Change(3, 4)).is_err());
+	assert_eq!(
+		chain(&"a".to_string(), &"b".to_string(), &"c".to_string()),
+		"a".to_string().comparison(&"c".to_string())
+	);
+	assert_eq!(chain(&1.5f64, &2.5f64, &3.5f64), Changed(F64Change(1.5, 3.5)));
+	assert_eq!(chain(&'a', &'b', &'a'), Unchanged);
+	assert_eq!(
+		chain(&std::path::PathBuf::from("/a"), &std::path::PathBuf::from("/b"), &std::path::PathBuf::from("/c")),
+		std::path::PathBuf::from("/a").comparison(&std::path::PathBuf::from("/c"))
+	);
+}
+
+#[test]
+fn test_compose_option_box_tuple() {
+	assert_eq!(chain(&Some(1), &Some(2), &Some(3)), Changed(OptionChange::BothSome(I32Change(1, 3))));
+	assert_eq!(chain(&Some(1), &None, &Some(1)), Unchanged);
+	assert_eq!(chain(&Some(1), &None, &Some(2)), Changed(OptionChange::BothSome(I32Change(1, 2))));
+	assert_eq!(chain(&None, &Some(1), &Some(2)), Changed(OptionChange::Different(None, Some(2))));
+	assert_eq!(chain(&Box::new(1), &Box::new(2), &Box::new(3)), Changed(I32Change(1, 3)));
+	assert_eq!(chain(&(1,), &(2,), &(1,)), Unchanged);
+	assert_eq!(chain(&(1, 'a'), &(2, 'a'), &(2, 'b')), (1, 'a').comparison(&(2, 'b')));
+	assert_eq!(chain(&(1, 'a'), &(2, 'b'), &(1, 'b')), (1, 'a').comparison(&(1, 'b')));
+	assert_eq!(chain(&[1, 2, 3], &[4, 2, 3], &[4, 2, 5]), [1, 2, 3].comparison(&[4, 2, 5]));
+}
+
+#[test]
+fn test_compose_vec() {
+	// An element added and then removed leaves no trace.
+	assert_eq!(chain(&vec![1, 2], &vec![1, 2, 3], &vec![1, 2]), Unchanged);
+	assert_eq!(chain(&vec![1, 2], &vec![1, 2, 3], &vec![4, 2, 3]), vec![1, 2].comparison(&vec![4, 2, 3]));
+	chain(&Aligned(vec![1, 2, 3]), &Aligned(vec![3, 1, 2]), &Aligned(vec![2, 3, 1]));
+	chain(&Aligned(vec![1, 2, 3]), &Aligned(vec![1, 3]), &Aligned(vec![0, 1, 5, 3]));
+	// Elements removed by the first change shorten the vector the second
+	// one applies to.
+	chain(&Aligned(vec![3, 1, 4, 4, 1]), &Aligned(vec![4, 4, 1]), &Aligned(vec![1, 0]));
+	assert_eq!(
+		Vec::<i32>::compose(vec![VecChange::Added(0, 1)], vec![VecChange::Removed(0, 2)]),
+		Err(PatchError::Mismatch("expected 2, found 1".to_string()))
+	);
+}
+
+#[test]
+fn test_compose_sets_and_maps() {
+	let set = |v: Vec<i32>| v.into_iter().collect::<BTreeSet<_>>();
+	assert_eq!(
+		chain(&set(vec![1, 2]), &set(vec![1, 2, 3]), &set(vec![2, 3])),
+		Changed(vec![SetChange::Added(3), SetChange::Removed(1)])
+	);
+	assert_eq!(chain(&set(vec![1]), &set(vec![1, 2]), &set(vec![1])), Unchanged);
+	assert!(BTreeSet::<i32>::compose(vec![SetChange::Added(1)], vec![SetChange::Added(1)]).is_err());
+
+	let map = |v: Vec<(u8, i32)>| v.into_iter().collect::<BTreeMap<_, _>>();
+	assert_eq!(
+		chain(&map(vec![(0, 1)]), &map(vec![(0, 2), (1, 3)]), &map(vec![(0, 4)])),
+		Changed(vec![MapChange::Changed(0, I32Change(1, 4))])
+	);
+	assert_eq!(
+		chain(&map(vec![(0, 1)]), &map(vec![]), &map(vec![(0, 5)])),
+		Changed(vec![MapChange::Removed(0), MapChange::Added(0, 5)])
+	);
+	assert!(BTreeMap::<u8, i32>::compose(vec![MapChange::Removed(0)], vec![MapChange::Changed(0, I32Change(1, 2))])
+		.is_err());
+	let hash_map = |v: Vec<(u8, i32)>| v.into_iter().collect::<HashMap<_, _>>();
+	chain(&hash_map(vec![(0, 1)]), &hash_map(vec![(1, 2)]), &hash_map(vec![(0, 1), (1, 3)]));
+
+	let reversible = |v: Vec<(u8, i32)>| Reversible(map(v));
+	assert_eq!(
+		chain(&reversible(vec![(0, 1)]), &reversible(vec![]), &reversible(vec![(0, 5)])),
+		Changed(vec![ReversibleMapChange::Changed(0, I32Change(1, 5))])
+	);
+	assert_eq!(
+		chain(&reversible(vec![(0, 1)]), &reversible(vec![(0, 2)]), &reversible(vec![])),
+		Changed(vec![ReversibleMapChange::Removed(0, 1)])
+	);
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_compose]
+struct Config {
+	name: String,
+	width: u32,
+	tags: BTreeSet<String>,
+}
+
+fn config(name: &str, width: u32, tags: &[&str]) -> Config {
+	Config { name: name.to_string(), width, tags: tags.iter().map(|tag| tag.to_string()).collect() }
+}
+
+#[test]
+fn test_compose_derived_structs() {
+	assert_eq!(
+		chain(&config("a", 80, &["x"]), &config("b", 80, &["x"]), &config("c", 90, &["x"])),
+		Changed(vec![
+			ConfigChange::Name(StringChange("a".to_string(), "c".to_string())),
+			ConfigChange::Width(U32Change(80, 90))
+		])
+	);
+	assert_eq!(chain(&config("a", 80, &["x"]), &config("b", 80, &["y"]), &config("a", 80, &["x"])), Unchanged);
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_compose]
+	struct Wrapper {
+		inner: Config,
+	}
+
+	assert_eq!(
+		chain(
+			&Wrapper { inner: config("a", 1, &[]) },
+			&Wrapper { inner: config("a", 2, &[]) },
+			&Wrapper { inner: config("a", 3, &[]) }
+		),
+		Changed(WrapperChange { inner: vec![ConfigChange::Width(U32Change(1, 3))] })
+	);
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_compose]
+	struct Keys {
+		#[comparable_key(|c| -> String { c.name.clone() })]
+		configs: Vec<Config>,
+	}
+
+	chain(
+		&Keys { configs: vec![config("a", 1, &[]), config("b", 2, &[])] },
+		&Keys { configs: vec![config("b", 2, &[]), config("a", 5, &[]), config("c", 3, &[])] },
+		&Keys { configs: vec![config("b", 2, &[]), config("c", 4, &[])] },
+	);
+	// An element cannot be removed and then added back by key.
+	assert!(compose_changed::<Keys>(
+		Keys { configs: vec![config("a", 1, &[])] }.comparison(&Keys { configs: vec![] }),
+		Keys { configs: vec![] }.comparison(&Keys { configs: vec![config("a", 2, &[])] }),
+	)
+	.is_err());
+}
+
+#[test]
+fn test_compose_derived_enums() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_compose]
+	enum Shape {
+		Empty,
+		Circle(f64),
+		Rect { width: u32, height: u32 },
+	}
+
+	let rect = |width, height| Shape::Rect { width, height };
+	assert_eq!(
+		chain(&rect(1, 2), &rect(3, 2), &rect(3, 4)),
+		Changed(ShapeChange::BothRect { width: Changed(U32Change(1, 3)), height: Changed(U32Change(2, 4)) })
+	);
+	assert_eq!(chain(&rect(1, 2), &rect(3, 2), &rect(1, 2)), Unchanged);
+	assert_eq!(
+		chain(&Shape::Circle(1.0), &Shape::Empty, &Shape::Circle(2.0)),
+		Changed(ShapeChange::BothCircle(F64Change(1.0, 2.0)))
+	);
+	assert_eq!(
+		chain(&Shape::Circle(1.0), &Shape::Circle(2.0), &Shape::Empty),
+		Changed(ShapeChange::Different(ShapeDesc::Circle(1.0), ShapeDesc::Empty))
+	);
+	assert_eq!(chain(&Shape::Empty, &Shape::Circle(2.0), &Shape::Empty), Unchanged);
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_compose]
+	#[variant_struct_fields]
+	enum Fields {
+		Pair { left: u8, right: u8 },
+	}
+
+	let pair = |left, right| Fields::Pair { left, right };
+	assert_eq!(
+		chain(&pair(1, 2), &pair(3, 2), &pair(5, 4)),
+		Changed(FieldsChange::BothPair(vec![
+			FieldsPairChange::Left(U8Change(1, 5)),
+			FieldsPairChange::Right(U8Change(2, 4))
+		]))
+	);
+}
+
+#[test]
+fn test_compose_all() {
+	let snapshots = [config("a", 1, &[]), config("b", 1, &["x"]), config("b", 2, &[]), config("c", 2, &["y"])];
+	assert_eq!(
+		compose_all::<Config>(snapshots.windows(2).map(|pair| pair[0].comparison(&pair[1]))),
+		Ok(snapshots[0].comparison(&snapshots[3]))
+	);
+	assert_eq!(compose_all::<Config>(vec![]), Ok(Unchanged));
+}
+
+proptest! {
+
+#[test]
+fn test_compose_vec_proptest(
+	a in prop::collection::vec(0u8..4, 0..6),
+	b in prop::collection::vec(0u8..4, 0..6),
+	c in prop::collection::vec(0u8..4, 0..6),
+) {
+	chain(&a, &b, &c);
+	chain(&Aligned(a), &Aligned(b), &Aligned(c));
+}
+
+#[test]
+fn test_compose_map_proptest(
+	a in prop::collection::btree_map(0u8..6, 0u8..4, 0..6),
+	b in prop::collection::btree_map(0u8..6, 0u8..4, 0..6),
+	c in prop::collection::btree_map(0u8..6, 0u8..4, 0..6),
+) {
+	chain(&a, &b, &c);
+	chain(&Reversible(a.clone()), &Reversible(b.clone()), &Reversible(c.clone()));
+	let set = |m: &BTreeMap<u8, u8>| m.keys().cloned().collect::<BTreeSet<_>>();
+	chain(&set(&a), &set(&b), &set(&c));
+}
+
+}
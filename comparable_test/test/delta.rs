@@ -0,0 +1,116 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+
+#[test]
+fn test_int_delta() {
+	assert_changes!(Delta(5i32), Delta(5i32), Unchanged);
+	assert_changes!(Delta(5i32), Delta(8i32), Changed(IntDelta::from(3)));
+	assert_changes!(Delta(u64::MAX), Delta(0u64), Changed(IntDelta::from(-(u64::MAX as i128))));
+	let widest = Delta(i128::MIN).comparison(&Delta(i128::MAX)).unwrap_or_default();
+	assert_eq!((widest.is_negative(), widest.magnitude()), (false, u128::MAX));
+	let widest = Delta(u128::MAX).comparison(&Delta(0)).unwrap_or_default();
+	assert_eq!((widest.is_negative(), widest.magnitude()), (true, u128::MAX));
+
+	assert_eq!(IntDelta::from(3).to_string(), "+3");
+	assert_eq!(format!("{:?}", IntDelta::from(-3i8)), "IntDelta(-3)");
+	assert_eq!(IntDelta::from(-3).checked_add(IntDelta::from(3u8)), Some(IntDelta::default()));
+	assert_eq!(IntDelta::from(u128::MAX).checked_add(IntDelta::from(1)), None);
+}
+
+#[test]
+fn test_float_delta() {
+	assert_changes!(Delta(1.5f64), Delta(1.5f64), Unchanged);
+	assert_changes!(Delta(1.5f64), Delta(-1.0f64), Changed(-2.5));
+	assert_changes!(Delta(f32::MAX), Delta(-f32::MAX), Changed(-2.0 * f32::MAX as f64));
+	assert_delta!(&0.25f32, &1.0f32, 0.75);
+}
+
+#[test]
+fn test_assert_delta_rounded() {
+	// 0.3 - 0.1 is 0.19999999999999998 in `f64`.
+	assert_delta!(&0.1f64, &0.3f64, 0.2);
+	assert_delta!(&0.3f64, &0.1f64, -0.2);
+	assert_delta!(&0.1f32, &0.3f32, 0.2);
+	assert_delta!(&1e20f64, &(1e20 + 1e5), 1e5);
+	assert!(check_delta(&0.1f64, &0.3f64, 0.2001).is_err());
+	assert!(check_delta(&0.1f32, &0.3f32, 0.20001).is_err());
+}
+
+#[test]
+fn test_assert_delta() {
+	assert_delta!(&10u8, &13u8, 3);
+	assert_delta!(&10u8, &7u8, -3);
+	assert_delta!(&10u8, &10u8, 0);
+	assert_delta!(&i64::MIN, &i64::MAX, u64::MAX);
+	assert_eq!(check_delta(&10u8, &12u8, 3), Err("expected a change of +3, found +2 from 10 to 12".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "assert_delta! failed: expected a change of -1.5, found +1.5 from 1.0 to 2.5")]
+fn test_assert_delta_failure() {
+	assert_delta!(&1.0f64, &2.5f64, -1.5);
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_patch]
+#[comparable_merge]
+#[comparable_render]
+struct Stats {
+	name: String,
+	#[comparable_delta]
+	requests: u64,
+	#[comparable_delta]
+	load: f32,
+}
+
+#[test]
+fn test_delta_attribute() {
+	let old = Stats { name: "web".to_string(), requests: 100, load: 0.5 };
+	let new = Stats { name: "web".to_string(), requests: 103, load: 0.25 };
+	assert_changes!(&old, &new, Changed(vec![StatsChange::Requests(IntDelta::from(3)), StatsChange::Load(-0.25)]));
+	assert_eq!(render::<Stats>(&old.comparison(&new)).to_string(), ".requests: +3\n.load: -0.25");
+
+	// Changes made to counters by either side merge into their sum.
+	let (left, right) = (Stats { requests: 110, ..old.clone() }, Stats { requests: 95, ..old.clone() });
+	assert_eq!(merge(&old, &left, &right), Ok(Stats { requests: 105, ..old.clone() }));
+
+	let mut value = Stats { requests: 0, ..old.clone() };
+	value.apply(old.comparison(&new).unwrap_or_default()).unwrap();
+	assert_eq!(value, Stats { requests: 3, load: 0.25, ..old });
+}
+
+#[test]
+fn test_delta_patch() {
+	let mut value = Delta(250u8);
+	assert_eq!(
+		value.apply(IntDelta::from(10)),
+		Err(PatchError::Mismatch("250 +10 is out of range for u8".to_string()))
+	);
+	value.apply(IntDelta::from(-250)).unwrap();
+	assert_eq!(value, Delta(0));
+	let mut value = Delta(i128::MAX);
+	value.apply(Delta(i128::MAX).comparison(&Delta(i128::MIN)).unwrap_or_default()).unwrap();
+	assert_eq!(value, Delta(i128::MIN));
+	assert!(Delta(-1i128).apply(Delta(i128::MAX).comparison(&Delta(i128::MIN)).unwrap_or_default()).is_err());
+	assert_eq!(Delta::<i8>::compose(IntDelta::from(3), IntDelta::from(-3)), Ok(Unchanged));
+	assert_eq!(Delta::<i8>::invert(IntDelta::from(3)), Ok(IntDelta::from(-3)));
+	assert!(Delta::<u128>::merge_changes(IntDelta::from(u128::MAX), IntDelta::from(1)).is_err());
+}
+
+proptest! {
+
+#[test]
+fn test_delta_proptest(a in any::<i64>(), b in any::<i64>(), c in any::<u16>(), d in any::<u16>()) {
+	let mut value = Delta(a);
+	if let Changed(change) = Delta(a).comparison(&Delta(b)) {
+		prop_assert_eq!(change, IntDelta::from(b as i128 - a as i128));
+		value.apply(change).unwrap();
+	}
+	prop_assert_eq!(value, Delta(b));
+	prop_assert_delta!(&c, &d, d as i32 - c as i32);
+	if let Changed(change) = compose_changed::<Delta<u16>>(Delta(c).comparison(&Delta(d)), Delta(d).comparison(&Delta(c))).unwrap() {
+		prop_assert!(false, "{} is not zero", change);
+	}
+}
+
+}
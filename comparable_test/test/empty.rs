@@ -1,6 +1,25 @@
-use comparable::{assert_changes, Changed::*};
+use comparable::{assert_changes, Changed::*, Comparable, U32Change};
 
 #[test]
 fn test_empty() {
 	assert_changes!(&std::iter::empty::<()>(), &std::iter::empty::<()>(), Unchanged,);
 }
+
+#[test]
+fn test_phantom_data() {
+	use std::marker::PhantomData;
+
+	assert_changes!(&PhantomData::<String>, &PhantomData::<String>, Unchanged);
+
+	#[derive(PartialEq, Debug, Comparable)]
+	struct Tagged {
+		id: u32,
+		tag: PhantomData<String>,
+	}
+
+	assert_changes!(
+		&Tagged { id: 1, tag: PhantomData },
+		&Tagged { id: 2, tag: PhantomData },
+		Changed(vec![TaggedChange::Id(U32Change(1, 2))])
+	);
+}
@@ -0,0 +1,16 @@
+use comparable::{Changed::*, *};
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
+
+#[test]
+fn test_os_string() {
+	let (a, b) = (OsString::from("eth0"), OsString::from("eth1"));
+	assert_changes!(&a, &a.clone(), Unchanged);
+	assert_changes!(&a, &b, Changed(OsStringChange(a.clone(), b.clone())));
+	assert_changes!(OsStr::new("lo"), OsStr::new("lo"), Unchanged);
+
+	let mut patched = Cow::Borrowed(a.as_os_str());
+	patched.apply(OsStringChange(a.clone(), b.clone())).unwrap();
+	assert_eq!(patched, b);
+	assert_eq!(render::<OsString>(&a.comparison(&b)).to_string(), "\"eth0\" -> \"eth1\"");
+}
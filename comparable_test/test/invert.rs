@@ -0,0 +1,219 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Debug;
+
+// Invert the change from `a` to `b` and apply it to `b`, checking that this
+// yields `a`.
+fn undo<T: Patch + Invert + PartialEq + Debug + Clone>(a: &T, b: &T) {
+	let mut undone = b.clone();
+	if let Changed(change) = a.comparison(b) {
+		undone.apply(T::invert(change).unwrap()).unwrap();
+	}
+	assert_eq!(&undone, a);
+}
+
+#[test]
+#[allow(clippy::needless_borrow)] // `"a".comparison(&"b")` resolves to `str`'s implementation
+fn test_invert_scalars() {
+	assert_eq!(i32::invert(I32Change(1, 2)), Ok(I32Change(2, 1)));
+	assert_eq!(bool::invert(BoolChange(true, false)), Ok(BoolChange(false, true)));
+	assert_eq!(
+		String::invert(StringChange("a".to_string(), "b".to_string())),
+		Ok(StringChange("b".to_string(), "a".to_string()))
+	);
+	assert_eq!("a".comparison(&"b").map(|change| <&str>::invert(change).unwrap()), "b".comparison(&"a"));
+	undo(&1.5f64, &2.5f64);
+	undo(&'a', &'b');
+	undo(&std::path::PathBuf::from("/a"), &std::path::PathBuf::from("/b"));
+}
+
+#[test]
+fn test_invert_option_box_tuple() {
+	assert_eq!(
+		Option::<u8>::invert(OptionChange::Different(None, Some(1))),
+		Ok(OptionChange::Different(Some(1), None))
+	);
+	undo(&Some(1), &Some(2));
+	undo(&Box::new(1), &Box::new(2));
+	undo(&(1,), &(2,));
+	undo(&(1, 'a', true), &(1, 'b', false));
+	undo(&[1, 2, 3], &[1, 5, 3]);
+}
+
+#[test]
+fn test_invert_vec() {
+	let a = vec![1, 2, 3];
+	let b = vec![1, 5];
+	assert_eq!(a.comparison(&b).map(|change| Vec::<i32>::invert(change).unwrap()), b.comparison(&a));
+	undo(&vec![], &vec![1, 2]);
+	undo(&vec![1, 2], &vec![]);
+}
+
+#[test]
+fn test_invert_aligned() {
+	// The index of an element changed in place refers to the original sequence,
+	// so it shifts when the change is inverted.
+	let a = Aligned(vec![1, 2, 3, 4]);
+	let b = Aligned(vec![0, 1, 20, 4]);
+	assert_eq!(
+		a.comparison(&b),
+		Changed(vec![VecChange::Added(0, 0), VecChange::Changed(1, I32Change(2, 20)), VecChange::Removed(2, 3)])
+	);
+	assert_eq!(
+		Aligned::<Vec<i32>>::invert(a.comparison(&b).unwrap_or_default()),
+		Ok(vec![VecChange::Removed(0, 0), VecChange::Changed(2, I32Change(20, 2)), VecChange::Added(2, 3)])
+	);
+	undo(&a, &b);
+	undo(&Aligned(vec![1, 2, 3, 4, 5]), &Aligned(vec![5, 2, 9, 1, 4]));
+}
+
+#[test]
+fn test_invert_sets() {
+	let set = |v: Vec<i32>| v.into_iter().collect::<BTreeSet<_>>();
+	assert_eq!(
+		BTreeSet::<i32>::invert(vec![SetChange::Added(4), SetChange::Removed(1)]),
+		Ok(vec![SetChange::Removed(4), SetChange::Added(1)])
+	);
+	undo(&set(vec![1, 2, 3]), &set(vec![2, 3, 4]));
+}
+
+#[test]
+fn test_invert_maps() {
+	let map = |v: Vec<(u8, i32)>| v.into_iter().collect::<BTreeMap<_, _>>();
+	assert_eq!(
+		BTreeMap::<u8, i32>::invert(vec![MapChange::Added(1, 2), MapChange::Changed(0, I32Change(1, 3))]),
+		Ok(vec![MapChange::Removed(1), MapChange::Changed(0, I32Change(3, 1))])
+	);
+	assert_eq!(
+		BTreeMap::<u8, i32>::invert(vec![MapChange::Removed(1)]),
+		Err(PatchError::Unsupported(
+			"the value removed at key 1 is not recorded, so it cannot be restored".to_string()
+		))
+	);
+
+	let reversible = |v: Vec<(u8, i32)>| Reversible(map(v));
+	assert_changes!(
+		&reversible(vec![(0, 1), (1, 2)]),
+		&reversible(vec![(1, 3), (2, 4)]),
+		Changed(vec![
+			ReversibleMapChange::Changed(1, I32Change(2, 3)),
+			ReversibleMapChange::Added(2, 4),
+			ReversibleMapChange::Removed(0, 1),
+		]),
+	);
+	undo(&reversible(vec![(0, 1), (1, 2)]), &reversible(vec![(1, 3), (2, 4)]));
+
+	let hash_map = |v: Vec<(u8, i32)>| Reversible(v.into_iter().collect::<HashMap<_, _>>());
+	undo(&hash_map(vec![(0, 1), (1, 2)]), &hash_map(vec![(1, 3), (2, 4)]));
+
+	// The value recorded for a removed entry is checked when it is applied.
+	assert_eq!(
+		reversible(vec![(0, 1)]).apply(vec![ReversibleMapChange::Removed(0, 2)]),
+		Err(PatchError::Mismatch("expected 2, found 1".to_string()))
+	);
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_patch]
+#[comparable_invert]
+struct Record {
+	id: u64,
+	name: String,
+}
+
+fn record(id: u64, name: &str) -> Record {
+	Record { id, name: name.to_string() }
+}
+
+#[test]
+fn test_invert_derived() {
+	undo(&record(1, "a"), &record(2, "b"));
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	#[comparable_invert]
+	struct Document {
+		title: String,
+		#[comparable_reversible]
+		sections: BTreeMap<u32, String>,
+		#[comparable_aligned]
+		paragraphs: Vec<String>,
+	}
+
+	let a = Document {
+		title: "a".to_string(),
+		sections: vec![(1, "one".to_string()), (2, "two".to_string())].into_iter().collect(),
+		paragraphs: vec!["x".to_string(), "y".to_string()],
+	};
+	let b = Document {
+		title: "b".to_string(),
+		sections: vec![(2, "deux".to_string())].into_iter().collect(),
+		paragraphs: vec!["w".to_string(), "x".to_string()],
+	};
+	undo(&a, &b);
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_compose]
+	enum Shape {
+		Empty,
+		Circle(f64),
+		Rect { width: u32, height: u32 },
+	}
+
+	assert_eq!(
+		Shape::invert(ShapeChange::BothRect { width: Changed(U32Change(1, 2)), height: Unchanged }),
+		Ok(ShapeChange::BothRect { width: Changed(U32Change(2, 1)), height: Unchanged })
+	);
+	undo(&Shape::Circle(1.0), &Shape::Circle(2.0));
+	undo(&Shape::Empty, &Shape::Rect { width: 1, height: 2 });
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_compose]
+	#[variant_struct_fields]
+	enum Fields {
+		Pair { left: u8, right: u8 },
+	}
+
+	undo(&Fields::Pair { left: 1, right: 2 }, &Fields::Pair { left: 3, right: 2 });
+}
+
+#[test]
+fn test_invert_keyed() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	#[comparable_invert]
+	struct Team {
+		#[comparable_key(|r| -> u64 { r.id }, moves)]
+		members: Vec<Record>,
+	}
+
+	// Reordering and changing elements can be undone, but removing one cannot.
+	undo(
+		&Team { members: vec![record(1, "a"), record(2, "b"), record(3, "c")] },
+		&Team { members: vec![record(3, "c"), record(1, "x"), record(2, "b")] },
+	);
+	let team = Team { members: vec![record(1, "a")] };
+	if let Changed(change) = team.comparison(&Team { members: vec![] }) {
+		assert!(Team::invert(change).is_err());
+	}
+}
+
+proptest! {
+
+#[test]
+fn test_invert_vec_proptest(a in prop::collection::vec(0u8..4, 0..8), b in prop::collection::vec(0u8..4, 0..8)) {
+	let mut undone = b.clone();
+	if let Changed(change) = a.comparison(&b) {
+		undone.apply(Vec::<u8>::invert(change).unwrap()).unwrap();
+	}
+	prop_assert_eq!(&undone, &a);
+
+	let mut undone = Aligned(b.clone());
+	if let Changed(change) = Aligned(a.clone()).comparison(&Aligned(b)) {
+		undone.apply(Aligned::<Vec<u8>>::invert(change).unwrap()).unwrap();
+	}
+	prop_assert_eq!(undone.0, a);
+}
+
+}
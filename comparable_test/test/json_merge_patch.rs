@@ -0,0 +1,107 @@
+//! Tests of `comparable::json_merge_patch`, only built when the
+//! `json_merge_patch` feature is on (see `required-features` in
+//! `comparable_test/Cargo.toml`).
+
+use comparable::*;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+// The merge patch for the change between two values, checking that merging it
+// into the JSON of the first value yields that of the second.
+fn merge_patch_between<T: Render + Serialize>(a: &T, b: &T) -> Value {
+	let patch = json_merge_patch::<T>(&a.comparison(b)).expect("change should be expressible");
+	let mut value = serde_json::to_value(a).unwrap();
+	::json_patch::merge(&mut value, &patch);
+	assert_eq!(value, serde_json::to_value(b).unwrap());
+	patch
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Comparable)]
+#[comparable_render]
+struct Server {
+	name: String,
+	limits: Limits,
+	labels: BTreeMap<String, String>,
+	// A merge patch removes a member to set it to `null`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	note: Option<String>,
+	ports: Vec<u16>,
+	shape: Shape,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Comparable)]
+#[comparable_render]
+struct Limits {
+	cpu: u32,
+	memory: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Comparable)]
+#[comparable_render]
+enum Shape {
+	Empty,
+	Rect { width: u32, height: u32 },
+	Square { side: u32 },
+}
+
+fn server(name: &str, cpu: u32, labels: &[(&str, &str)]) -> Server {
+	Server {
+		name: name.to_string(),
+		limits: Limits { cpu, memory: 512 },
+		labels: labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+		note: Some("n".to_string()),
+		ports: vec![80],
+		shape: Shape::Rect { width: 1, height: 2 },
+	}
+}
+
+#[test]
+fn test_merge_patch_of_derived_structs() {
+	let a = server("a", 1, &[("k", "v"), ("old", "x")]);
+	let b = server("b", 2, &[("k", "w"), ("new", "y")]);
+	assert_eq!(
+		merge_patch_between(&a, &b),
+		json!({
+			"name": "b",
+			"limits": { "cpu": 2 },
+			"labels": { "k": "w", "new": "y", "old": null },
+		})
+	);
+	assert_eq!(merge_patch_between(&a, &a), json!({}));
+
+	let c = Server { note: None, shape: Shape::Square { side: 3 }, ..a.clone() };
+	assert_eq!(
+		merge_patch_between(&a, &c),
+		json!({ "note": null, "shape": { "Rect": null, "Square": { "side": 3 } } })
+	);
+	let d = Server { shape: Shape::Rect { width: 5, height: 2 }, ..a.clone() };
+	assert_eq!(merge_patch_between(&a, &d), json!({ "shape": { "Rect": { "width": 5 } } }));
+	let e = Server { shape: Shape::Empty, ..a.clone() };
+	assert_eq!(merge_patch_between(&a, &e), json!({ "shape": "Empty" }));
+}
+
+#[test]
+fn test_merge_patch_of_maps() {
+	let map = |v: Vec<(&str, Vec<u8>)>| v.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<BTreeMap<_, _>>();
+	assert_eq!(
+		merge_patch_between(&map(vec![("a", vec![1]), ("b", vec![])]), &map(vec![("a", vec![1]), ("c", vec![2])])),
+		json!({ "b": null, "c": [2] })
+	);
+	let numbers = |v: Vec<(u8, bool)>| v.into_iter().collect::<BTreeMap<_, _>>();
+	assert_eq!(merge_patch_between(&numbers(vec![(1, true)]), &numbers(vec![(1, false)])), json!({ "1": false }));
+	assert_eq!(merge_patch_between(&1, &2), json!(2));
+}
+
+#[test]
+fn test_merge_patch_errors() {
+	let a = server("a", 1, &[]);
+	let b = Server { ports: vec![80, 443], ..a.clone() };
+	assert_eq!(
+		json_merge_patch::<Server>(&a.comparison(&b)),
+		Err(JsonPatchError("the element at \"/ports/1\" of an array cannot be merged".to_string()))
+	);
+
+	let set = |v: Vec<u8>| v.into_iter().collect::<BTreeSet<_>>();
+	assert!(json_merge_patch::<BTreeSet<u8>>(&set(vec![1]).comparison(&set(vec![2]))).is_err());
+}
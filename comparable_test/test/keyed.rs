@@ -0,0 +1,192 @@
+use comparable::{Changed::*, *};
+
+#[derive(Clone, PartialEq, Comparable)]
+#[comparable_patch]
+struct Record {
+	id: u64,
+	name: String,
+}
+
+fn record(id: u64, name: &str) -> Record {
+	Record { id, name: name.to_string() }
+}
+
+struct ById;
+
+impl KeyFn<Record> for ById {
+	type Key = u64;
+	fn key(value: &Record) -> u64 {
+		value.id
+	}
+}
+
+struct ByIdWithMoves;
+
+impl KeyFn<Record> for ByIdWithMoves {
+	type Key = u64;
+	const REPORT_MOVES: bool = true;
+	fn key(value: &Record) -> u64 {
+		value.id
+	}
+}
+
+fn by_id(records: Vec<Record>) -> Keyed<Vec<Record>, ById> {
+	Keyed::new(records)
+}
+
+fn by_id_with_moves(records: Vec<Record>) -> Keyed<Vec<Record>, ByIdWithMoves> {
+	Keyed::new(records)
+}
+
+#[test]
+fn test_keyed_unchanged() {
+	assert_changes!(
+		&by_id(vec![record(1, "a"), record(2, "b")]),
+		&by_id(vec![record(1, "a"), record(2, "b")]),
+		Unchanged
+	);
+	assert_changes!(&by_id(vec![]), &by_id(vec![]), Unchanged);
+}
+
+#[test]
+fn test_keyed_added_removed_changed() {
+	assert_changes!(
+		&by_id(vec![record(1, "a"), record(2, "b"), record(3, "c")]),
+		&by_id(vec![record(4, "d"), record(1, "a"), record(3, "C")]),
+		Changed(vec![
+			MapChange::Changed(3, vec![RecordChange::Name(StringChange("c".to_string(), "C".to_string()))]),
+			MapChange::Added(4, RecordDesc { id: 4, name: "d".to_string() }),
+			MapChange::Removed(2),
+		]),
+	);
+}
+
+#[test]
+fn test_keyed_ignores_order() {
+	assert_changes!(
+		&by_id(vec![record(1, "a"), record(2, "b"), record(3, "c")]),
+		&by_id(vec![record(3, "c"), record(1, "a"), record(2, "b")]),
+		Unchanged,
+	);
+}
+
+#[test]
+fn test_keyed_moves() {
+	assert_changes!(
+		&by_id_with_moves(vec![record(1, "a"), record(2, "b"), record(3, "c")]),
+		&by_id_with_moves(vec![record(3, "c"), record(1, "a"), record(2, "b")]),
+		Changed(vec![MapChange::Moved { key: 3, from: 2, to: 0 }]),
+	);
+
+	// Elements that only shift because of an insertion or removal elsewhere
+	// have not moved.
+	assert_changes!(
+		&by_id_with_moves(vec![record(1, "a"), record(2, "b"), record(3, "c")]),
+		&by_id_with_moves(vec![record(0, "z"), record(1, "a"), record(3, "c")]),
+		Changed(vec![MapChange::Added(0, RecordDesc { id: 0, name: "z".to_string() }), MapChange::Removed(2)]),
+	);
+
+	// A moved element may also have changed.
+	assert_changes!(
+		&by_id_with_moves(vec![record(1, "a"), record(2, "b")]),
+		&by_id_with_moves(vec![record(2, "B"), record(1, "a")]),
+		Changed(vec![
+			MapChange::Changed(2, vec![RecordChange::Name(StringChange("b".to_string(), "B".to_string()))]),
+			MapChange::Moved { key: 1, from: 0, to: 1 },
+		]),
+	);
+}
+
+#[test]
+fn test_keyed_duplicate_key() {
+	// The occurrences of a duplicate key are matched up by position.
+	assert_changes!(
+		&by_id(vec![record(1, "a"), record(1, "b"), record(1, "c")]),
+		&by_id(vec![record(1, "a"), record(1, "x")]),
+		Changed(vec![
+			MapChange::Changed(1, vec![RecordChange::Name(StringChange("b".to_string(), "x".to_string()))]),
+			MapChange::Removed(1),
+		]),
+	);
+	assert_changes!(
+		&by_id(vec![record(1, "a")]),
+		&by_id(vec![record(1, "a"), record(1, "b")]),
+		Changed(vec![MapChange::Added(1, RecordDesc { id: 1, name: "b".to_string() })]),
+	);
+	assert_changes!(
+		&by_id_with_moves(vec![record(1, "a"), record(2, "b"), record(1, "c")]),
+		&by_id_with_moves(vec![record(1, "a"), record(1, "c"), record(2, "b")]),
+		Changed(vec![MapChange::Moved { key: 2, from: 1, to: 2 }]),
+	);
+
+	// Which of them a change applies to is ambiguous.
+	let mut records = by_id(vec![record(1, "a"), record(1, "b")]);
+	assert_eq!(
+		records.apply(vec![MapChange::Removed(1)]),
+		Err(PatchError::Mismatch("key 1 occurs more than once".to_string()))
+	);
+}
+
+#[test]
+fn test_keyed_describe() {
+	assert_eq!(by_id(vec![record(1, "a")]).describe(), vec![RecordDesc { id: 1, name: "a".to_string() }]);
+}
+
+#[test]
+fn test_keyed_field_attribute() {
+	#[derive(Comparable)]
+	struct Fixture {
+		name: String,
+		#[comparable_key(|r| -> u64 { r.id })]
+		records: Vec<Record>,
+		#[comparable_key(|r| -> String { r.name.clone() }, moves)]
+		by_name: Vec<Record>,
+	}
+
+	assert_changes!(
+		&Fixture {
+			name: "x".to_string(),
+			records: vec![record(1, "a"), record(2, "b")],
+			by_name: vec![record(1, "a"), record(2, "b")],
+		},
+		&Fixture {
+			name: "x".to_string(),
+			records: vec![record(2, "b"), record(1, "c")],
+			by_name: vec![record(2, "b"), record(3, "a")],
+		},
+		Changed(vec![
+			FixtureChange::Records(vec![MapChange::Changed(
+				1,
+				vec![RecordChange::Name(StringChange("a".to_string(), "c".to_string()))],
+			)]),
+			FixtureChange::ByName(vec![
+				MapChange::Changed("a".to_string(), vec![RecordChange::Id(U64Change(1, 3))]),
+				MapChange::Moved { key: "a".to_string(), from: 0, to: 1 },
+			]),
+		]),
+	);
+}
+
+#[test]
+fn test_keyed_enum_field_attribute() {
+	#[derive(Comparable)]
+	enum Fixture {
+		Records(#[comparable_key(|r| -> u64 { r.id })] Vec<Record>),
+		Named {
+			#[comparable_key(|r| -> u64 { r.id })]
+			records: Vec<Record>,
+			count: u32,
+		},
+	}
+
+	assert_changes!(
+		&Fixture::Records(vec![record(1, "a"), record(2, "b")]),
+		&Fixture::Records(vec![record(2, "b")]),
+		Changed(FixtureChange::BothRecords(vec![MapChange::Removed(1)])),
+	);
+	assert_changes!(
+		&Fixture::Named { records: vec![record(1, "a")], count: 1 },
+		&Fixture::Named { records: vec![], count: 1 },
+		Changed(FixtureChange::BothNamed { records: Changed(vec![MapChange::Removed(1)]), count: Unchanged }),
+	);
+}
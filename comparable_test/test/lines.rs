@@ -0,0 +1,147 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+
+fn lines(text: &str) -> Lines<String> {
+	Lines(text.to_string())
+}
+
+fn hunk(old_line: usize, new_line: usize, removed: &[&str], added: &[&str]) -> LineHunk {
+	let owned = |lines: &[&str]| lines.iter().map(|line| line.to_string()).collect();
+	LineHunk { old_line, new_line, removed: owned(removed), added: owned(added) }
+}
+
+#[test]
+fn test_lines_comparison() {
+	assert_changes!(&lines("a\nb\n"), &lines("a\nb\n"), Unchanged);
+	assert_changes!(&lines("a\nb\nc\n"), &lines("a\nB\nc\n"), Changed(vec![hunk(2, 2, &["b\n"], &["B\n"])]));
+	assert_changes!(
+		&lines("a\nb\nc\nd\n"),
+		&lines("x\na\nc\nd"),
+		Changed(vec![hunk(1, 1, &[], &["x\n"]), hunk(2, 3, &["b\n"], &[]), hunk(4, 4, &["d\n"], &["d"])])
+	);
+	assert_changes!(&lines(""), &lines("a"), Changed(vec![hunk(1, 1, &[], &["a"])]));
+}
+
+#[test]
+fn test_lines_render() {
+	let document: String = (1..=500).map(|i| format!("line {}\n", i)).collect();
+	let edited = document.replace("line 250\n", "line two hundred and fifty\n");
+	assert_eq!(
+		render::<Lines<String>>(&lines(&document).comparison(&lines(&edited))).to_string(),
+		"@@ -250 +250 @@\n-line 250\n+line two hundred and fifty"
+	);
+	assert_eq!(
+		render::<Lines<String>>(&lines("a\nb\n").comparison(&lines("b"))).to_string(),
+		"@@ -1,2 +1 @@\n-a\n-b\n+b\n\\ No newline at end of file"
+	);
+	assert_eq!(hunk(3, 4, &["a\n", "b\n"], &[]).to_string(), "@@ -3,2 +3,0 @@\n-a\n-b");
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_patch]
+#[comparable_render]
+struct Template {
+	name: String,
+	#[comparable_lines]
+	body: String,
+}
+
+#[test]
+fn test_lines_attribute() {
+	let template = |body: &str| Template { name: "t".to_string(), body: body.to_string() };
+	let (old, new) = (template("<p>\nhello\n</p>\n"), template("<p>\nworld\n</p>\n"));
+	assert_changes!(&old, &new, Changed(vec![TemplateChange::Body(vec![hunk(2, 2, &["hello\n"], &["world\n"])])]));
+	assert_eq!(render::<Template>(&old.comparison(&new)).to_string(), ".body: @@ -2 +2 @@\n-hello\n+world");
+
+	let report =
+		ChangeReport::between::<Template>(&old.comparison(&template("<p>\n</p>\n")), &old.comparison(&new)).unwrap();
+	assert_eq!(
+		report.to_string(),
+		"actual changes:\n\
+		 \x20   .body: @@ -2 +2 @@\n\
+		 \x20   -hello\n\
+		 \x20   +world\n\
+		 missing changes, expected but not found:\n\
+		 \x20 - .body: @@ -2 +1,0 @@\n\
+		 \x20   -hello\n\
+		 unexpected changes, found but not expected:\n\
+		 \x20 + .body: @@ -2 +2 @@\n\
+		 \x20   -hello\n\
+		 \x20   +world\n"
+	);
+
+	let mut patched = old.clone();
+	patched.apply(old.comparison(&new).unwrap_or_default()).unwrap();
+	assert_eq!(patched, new);
+}
+
+#[test]
+fn test_lines_patch_and_invert() {
+	let (old, new) = (lines("a\nb\nc\n"), lines("a\nc\nd\n"));
+	let change = old.comparison(&new).unwrap_or_default();
+	let mut value = old.clone();
+	value.apply(Lines::<String>::invert(Lines::<String>::invert(change).unwrap()).unwrap()).unwrap();
+	assert_eq!(value, new);
+	assert!(lines("a\nx\nc\n").apply(vec![hunk(2, 2, &["b\n"], &[])]).is_err());
+}
+
+#[test]
+fn test_lines_merge() {
+	let base = lines("a\nb\nc\nd\ne\n");
+	assert_eq!(merge(&base, &lines("A\nb\nc\nd\ne\n"), &lines("a\nb\nc\nd\nE\nf\n")), Ok(lines("A\nb\nc\nd\nE\nf\n")));
+	assert_eq!(
+		merge(&base, &lines("a\nb\nx\ny\nc\nd\ne\n"), &lines("a\nd\ne\n")),
+		Err(MergeError::Conflicts(Conflicts::between(
+			vec![hunk(3, 3, &[], &["x\n", "y\n"])],
+			vec![hunk(2, 2, &["b\n", "c\n"], &[])]
+		)))
+	);
+	assert_eq!(merge(&base, &lines("a\nB\nc\nd\ne\n"), &lines("a\nB\nc\nd\ne\n")), Ok(lines("a\nB\nc\nd\ne\n")));
+}
+
+#[test]
+fn test_lines_compose() {
+	let (a, b, c) = (lines("a\nb\nc\n"), lines("a\nB\nc\n"), lines("a\nb\nc\n"));
+	assert_eq!(
+		Lines::<String>::compose(a.comparison(&b).unwrap_or_default(), b.comparison(&c).unwrap_or_default()),
+		Ok(Unchanged)
+	);
+	let c = lines("x\na\nB\n");
+	assert_eq!(
+		Lines::<String>::compose(a.comparison(&b).unwrap_or_default(), b.comparison(&c).unwrap_or_default()),
+		Ok(a.comparison(&c))
+	);
+}
+
+fn text() -> impl Strategy<Value = String> {
+	prop::collection::vec(prop::sample::select(vec!["a\n", "b\n", "c\n", "d"]), 0..8).prop_map(|lines| lines.concat())
+}
+
+proptest! {
+
+#[test]
+fn test_lines_proptest(a in text(), b in text(), c in text()) {
+	let (a, b, c) = (Lines(a), Lines(b), Lines(c));
+	let mut value = a.clone();
+	if let Changed(change) = a.comparison(&b) {
+		value.apply(change).unwrap();
+	}
+	prop_assert_eq!(&value, &b);
+	if let Changed(change) = b.comparison(&a) {
+		value.apply(Lines::<String>::invert(Lines::<String>::invert(change).unwrap()).unwrap()).unwrap();
+	}
+	prop_assert_eq!(&value, &a);
+
+	let mut value = a.clone();
+	if let Changed(change) = compose_changed::<Lines<String>>(a.comparison(&b), b.comparison(&c)).unwrap() {
+		value.apply(change).unwrap();
+	}
+	prop_assert_eq!(&value, &c);
+
+	prop_assert_eq!(merge(&a, &b, &a), Ok(b.clone()));
+	prop_assert_eq!(merge(&a, &a, &c), Ok(c.clone()));
+	prop_assert_eq!(merge(&a, &b, &b), Ok(b.clone()));
+	prop_assert_eq!(merge(&a, &b, &c).is_ok(), merge(&a, &c, &b).is_ok());
+}
+
+}
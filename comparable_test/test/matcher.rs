@@ -0,0 +1,178 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+struct Order {
+	id: String,
+	quantity: i64,
+	updated_at: u64,
+	tags: Vec<String>,
+}
+
+fn order(id: &str, quantity: i64, updated_at: u64) -> Order {
+	Order { id: id.to_string(), quantity, updated_at, tags: vec!["new".to_string()] }
+}
+
+#[test]
+fn test_matcher_scalars() {
+	assert!(Matcher::<I64Change>::increased().matches(&I64Change(1, 2)));
+	assert!(!Matcher::<I64Change>::increased().matches(&I64Change(2, 1)));
+	assert!(Matcher::<F64Change>::decreased().matches(&F64Change(2.0, 1.5)));
+	assert!(Matcher::<U8Change>::new_value(Matcher::equals(7)).matches(&U8Change(1, 7)));
+	assert!(!Matcher::<U8Change>::old_value(Matcher::equals(7)).matches(&U8Change(1, 7)));
+	assert!(Matcher::<StringChange>::new_value(Matcher::regex("^id-[0-9]+$"))
+		.matches(&StringChange("id-1".to_string(), "id-22".to_string())));
+	assert!(!Matcher::<str>::regex("^[a-z]+$").matches("abc1"));
+	assert!(Matcher::<bool>::anything().matches(&false));
+	assert!(Matcher::<comparable::Changed<bool>>::unchanged().matches(&Unchanged));
+	assert!(!Matcher::<comparable::Changed<bool>>::any_change().matches(&Unchanged));
+}
+
+#[test]
+fn test_assert_changes_match() {
+	let (old, new) = (order("id-1", 2, 100), order("id-1", 5, 250));
+	assert_changes_match!(&old, &old, Matcher::unchanged());
+	assert_changes_match!(&old, &new, Matcher::any_change());
+	assert_changes_match!(
+		&old,
+		&new,
+		Matcher::changed(Matcher::elements(vec![
+			Matcher::satisfies(|change| match change {
+				OrderChange::Quantity(change) => Matcher::increased().matches(change),
+				_ => false,
+			}),
+			Matcher::satisfies(|change| matches!(change, OrderChange::UpdatedAt(_))),
+		]))
+	);
+	assert_changes_match!(
+		&old,
+		&Order { id: "id-7".to_string(), ..old.clone() },
+		Matcher::changed(Matcher::elements(vec![Matcher::satisfies(|change| match change {
+			OrderChange::Id(change) => Matcher::new_value(Matcher::regex("^id-[0-9]$")).matches(change),
+			_ => false,
+		})]))
+	);
+
+	let mut map = BTreeMap::new();
+	map.insert("k".to_string(), 1u8);
+	let mut other = map.clone();
+	other.insert("k".to_string(), 3);
+	assert_changes_match!(
+		&map,
+		&other,
+		Matcher::changed(Matcher::elements(vec![Matcher::equals(MapChange::Changed("k".to_string(), U8Change(1, 3)))]))
+	);
+}
+
+#[test]
+fn test_assert_changes_match_failure() {
+	let (old, new) = (order("id-1", 2, 100), order("id-1", 1, 100));
+	let matcher = Matcher::changed(Matcher::elements(vec![Matcher::anything(), Matcher::anything()]));
+	assert_eq!(matcher.to_string(), "Changed([_, _])");
+	assert_eq!(
+		check_changes_match(&old, &new, &matcher),
+		Err("expected changes matching Changed([_, _]), found Changed([Quantity(I64Change(2, 1))])".to_string())
+	);
+	assert_eq!(
+		Matcher::<comparable::Changed<U8Change>>::changed(Matcher::equals(U8Change(1, 2))).to_string(),
+		"Changed(U8Change(1, 2))"
+	);
+	assert_eq!(Matcher::<StringChange>::new_value(Matcher::regex("x+")).to_string(), "a change to /x+/");
+}
+
+#[test]
+#[should_panic(expected = "assert_changes_match! failed")]
+fn test_assert_changes_match_panics() {
+	assert_changes_match!(&1u8, &2u8, Matcher::changed(Matcher::decreased()));
+}
+
+#[test]
+fn test_assert_changes_include_and_only() {
+	let old = order("id-1", 2, 100);
+	let new = Order { tags: vec!["new".to_string(), "paid".to_string()], ..order("id-1", 5, 250) };
+	let quantity = || Matcher::equals(OrderChange::Quantity(I64Change(2, 5)));
+	let updated = || Matcher::satisfies(|change| matches!(change, OrderChange::UpdatedAt(_)));
+	let tags = || Matcher::satisfies(|change| matches!(change, OrderChange::Tags(_)));
+
+	assert_changes_include!(&old, &new, [quantity()]);
+	assert_changes_include!(&old, &new, [updated(), quantity()]);
+	assert_changes_include!(&old, &old, []);
+	assert_changes_only!(&old, &new, [tags(), updated(), quantity()]);
+	assert_changes_only!(&old, &old, []);
+
+	// Each matcher takes a change of its own.
+	assert!(check_changes_include(&old, &new, &[Matcher::anything(), quantity()], false).is_ok());
+	assert!(check_changes_include(&old, &new, &[quantity(), quantity()], false).is_err());
+
+	assert_eq!(
+		check_changes_include(&old, &new, &[quantity(), Matcher::satisfies(|c| matches!(c, OrderChange::Id(_)))], true),
+		Err("actual changes:\n    \
+			 Quantity(I64Change(2, 5))\n    \
+			 UpdatedAt(U64Change(100, 250))\n    \
+			 Tags([Added(1, \"paid\")])\n\
+			 missing changes, expected but not found:\n  \
+			 - <predicate>\n\
+			 unexpected changes, found but not expected:\n  \
+			 + UpdatedAt(U64Change(100, 250))\n  \
+			 + Tags([Added(1, \"paid\")])\n"
+			.to_string())
+	);
+	assert_eq!(
+		check_changes_include(&old, &new, &[Matcher::satisfies(|c| matches!(c, OrderChange::Id(_)))], false),
+		Err("actual changes:\n    \
+			 Quantity(I64Change(2, 5))\n    \
+			 UpdatedAt(U64Change(100, 250))\n    \
+			 Tags([Added(1, \"paid\")])\n\
+			 missing changes, expected but not found:\n  \
+			 - <predicate>\n"
+			.to_string())
+	);
+}
+
+#[test]
+fn test_assert_changes_include_collections() {
+	let map = |v: Vec<(&str, u8)>| v.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<BTreeMap<_, _>>();
+	let old = map(vec![("a", 1), ("b", 2), ("c", 3)]);
+	let new = map(vec![("a", 1), ("b", 20), ("d", 4)]);
+	assert_changes_include!(&old, &new, [Matcher::equals(MapChange::Removed("c".to_string()))]);
+	assert_changes_only!(
+		&old,
+		&new,
+		[
+			Matcher::equals(MapChange::Removed("c".to_string())),
+			Matcher::satisfies(|change| matches!(change, MapChange::Added(key, _) if key == "d")),
+			Matcher::anything(),
+		]
+	);
+	assert_changes_only!(
+		&vec![1, 2],
+		&vec![1, 3, 4],
+		[Matcher::equals(VecChange::Added(2, 4)), Matcher::equals(VecChange::Changed(1, I32Change(2, 3)))]
+	);
+}
+
+#[test]
+#[should_panic(expected = "assert_changes_only! failed")]
+fn test_assert_changes_only_panics() {
+	assert_changes_only!(&vec![1], &vec![2, 3], [Matcher::equals(VecChange::Added(1, 3))]);
+}
+
+proptest! {
+
+#[test]
+fn test_matcher_proptest(a in any::<i64>(), b in any::<i64>()) {
+	prop_assert_changes_match!(
+		&a,
+		&b,
+		if a < b {
+			Matcher::changed(Matcher::increased())
+		} else if a > b {
+			Matcher::changed(Matcher::decreased())
+		} else {
+			Matcher::unchanged()
+		}
+	);
+}
+
+}
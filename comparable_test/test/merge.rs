@@ -0,0 +1,247 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[test]
+fn test_merge_scalars() {
+	assert_eq!(merge(&1, &2, &1), Ok(2));
+	assert_eq!(merge(&1, &1, &3), Ok(3));
+	assert_eq!(merge(&1, &2, &2), Ok(2));
+	assert_eq!(merge(&1, &2, &3), Err(MergeError::Conflicts(Conflicts::between(I32Change(1, 2), I32Change(1, 3)))));
+	assert_eq!(merge(&"a".to_string(), &"b".to_string(), &"a".to_string()), Ok("b".to_string()));
+}
+
+#[test]
+fn test_merge_option_tuple_array() {
+	assert_eq!(merge(&Some((1, 2)), &Some((5, 2)), &Some((1, 6))), Ok(Some((5, 6))));
+	assert_eq!(
+		merge(&Some(1), &None, &Some(2)),
+		Err(MergeError::Conflicts(Conflicts::between(
+			OptionChange::Different(Some(1), None),
+			OptionChange::BothSome(I32Change(1, 2))
+		)))
+	);
+	assert_eq!(
+		merge(&(1, 'a'), &(2, 'a'), &(3, 'b')),
+		Err(MergeError::Conflicts(Conflicts::between(
+			(Changed(I32Change(1, 2)), Unchanged),
+			(Changed(I32Change(1, 3)), Unchanged)
+		)))
+	);
+	assert_eq!(merge(&[1, 2, 3], &[4, 2, 3], &[1, 2, 5]), Ok([4, 2, 5]));
+}
+
+#[test]
+fn test_merge_vec() {
+	// Elements changed in place are merged, as long as the length is unchanged
+	// on one side.
+	assert_eq!(merge(&vec![1, 2, 3], &vec![4, 2, 3], &vec![1, 2, 5, 6]), Ok(vec![4, 2, 5, 6]));
+	assert_eq!(merge(&vec![1, 2, 3], &vec![1, 2], &vec![1, 2]), Ok(vec![1, 2]));
+	assert!(merge(&vec![1, 2, 3], &vec![1, 2], &vec![1, 2, 3, 4]).is_err());
+
+	let aligned = |v: Vec<i32>| Aligned(v);
+	assert_eq!(
+		merge(&aligned(vec![1, 2, 3]), &aligned(vec![0, 1, 2, 3]), &aligned(vec![1, 5, 3])),
+		Ok(aligned(vec![0, 1, 5, 3]))
+	);
+	assert_eq!(
+		merge(&aligned(vec![1, 2, 3]), &aligned(vec![1, 3]), &aligned(vec![1, 5, 3])),
+		Err(MergeError::Conflicts(Conflicts::between(
+			vec![VecChange::Removed(1, 2)],
+			vec![VecChange::Changed(1, I32Change(2, 5))]
+		)))
+	);
+}
+
+#[test]
+fn test_merge_sets_and_maps() {
+	let set = |v: Vec<i32>| v.into_iter().collect::<BTreeSet<_>>();
+	assert_eq!(merge(&set(vec![1, 2]), &set(vec![1, 2, 3]), &set(vec![2, 4])), Ok(set(vec![2, 3, 4])));
+
+	let map = |v: Vec<(u8, i32)>| v.into_iter().collect::<BTreeMap<_, _>>();
+	assert_eq!(
+		merge(&map(vec![(0, 1), (1, 2)]), &map(vec![(0, 1), (1, 2), (2, 3)]), &map(vec![(1, 5)])),
+		Ok(map(vec![(1, 5), (2, 3)]))
+	);
+	assert_eq!(
+		merge(&map(vec![(0, 1)]), &map(vec![]), &map(vec![(0, 2)])),
+		Err(MergeError::Conflicts(Conflicts::between(
+			vec![MapChange::Removed(0)],
+			vec![MapChange::Changed(0, I32Change(1, 2))]
+		)))
+	);
+
+	let reversible = |v: Vec<(u8, i32)>| Reversible(map(v));
+	assert_eq!(
+		merge(&reversible(vec![(0, 1), (1, 2)]), &reversible(vec![(1, 2)]), &reversible(vec![(0, 1), (1, 3)])),
+		Ok(reversible(vec![(1, 3)]))
+	);
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_patch]
+#[comparable_merge]
+struct Config {
+	name: String,
+	width: u32,
+	tags: BTreeSet<String>,
+}
+
+fn config(name: &str, width: u32, tags: &[&str]) -> Config {
+	Config { name: name.to_string(), width, tags: tags.iter().map(|tag| tag.to_string()).collect() }
+}
+
+#[test]
+fn test_merge_derived_structs() {
+	let base = config("a", 80, &["x"]);
+	assert_eq!(
+		merge(&base, &config("b", 80, &["x", "y"]), &config("a", 100, &["z"])),
+		Ok(config("b", 100, &["y", "z"]))
+	);
+	// Only the conflicting field is reported.
+	assert_eq!(
+		merge(&base, &config("b", 90, &["x"]), &config("c", 100, &["x"])),
+		Err(MergeError::Conflicts(Conflicts(vec![
+			Conflict {
+				left: vec![ConfigChange::Name(StringChange("a".to_string(), "b".to_string()))],
+				right: vec![ConfigChange::Name(StringChange("a".to_string(), "c".to_string()))],
+			},
+			Conflict {
+				left: vec![ConfigChange::Width(U32Change(80, 90))],
+				right: vec![ConfigChange::Width(U32Change(80, 100))]
+			},
+		])))
+	);
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	#[comparable_merge]
+	struct Wrapper {
+		inner: Config,
+	}
+
+	assert_eq!(
+		merge(
+			&Wrapper { inner: base.clone() },
+			&Wrapper { inner: config("b", 80, &["x"]) },
+			&Wrapper { inner: config("a", 90, &["x"]) }
+		),
+		Ok(Wrapper { inner: config("b", 90, &["x"]) })
+	);
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	#[comparable_merge]
+	struct Keys {
+		#[comparable_key(|c| -> String { c.name.clone() })]
+		configs: Vec<Config>,
+	}
+
+	assert_eq!(
+		merge(
+			&Keys { configs: vec![config("a", 1, &[]), config("b", 2, &[])] },
+			&Keys { configs: vec![config("a", 5, &[]), config("b", 2, &[])] },
+			&Keys { configs: vec![config("a", 1, &[]), config("b", 2, &["t"]), config("c", 3, &[])] },
+		),
+		Ok(Keys { configs: vec![config("a", 5, &[]), config("b", 2, &["t"]), config("c", 3, &[])] })
+	);
+}
+
+#[test]
+fn test_merge_derived_enums() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	#[comparable_merge]
+	enum Shape {
+		Empty,
+		Circle(f64),
+		Rect { width: u32, height: u32 },
+	}
+
+	let rect = |width, height| Shape::Rect { width, height };
+	assert_eq!(merge(&rect(1, 2), &rect(3, 2), &rect(1, 4)), Ok(rect(3, 4)));
+	assert_eq!(
+		merge(&rect(1, 2), &rect(3, 2), &rect(5, 2)),
+		Err(MergeError::Conflicts(Conflicts::between(
+			ShapeChange::BothRect { width: Changed(U32Change(1, 3)), height: Unchanged },
+			ShapeChange::BothRect { width: Changed(U32Change(1, 5)), height: Unchanged },
+		)))
+	);
+	assert_eq!(merge(&Shape::Circle(1.0), &Shape::Empty, &Shape::Empty), Ok(Shape::Empty));
+	assert!(merge(&Shape::Circle(1.0), &Shape::Empty, &Shape::Circle(2.0)).is_err());
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	#[comparable_merge]
+	#[variant_struct_fields]
+	enum Fields {
+		Pair { left: u8, right: u8 },
+	}
+
+	let pair = |left, right| Fields::Pair { left, right };
+	assert_eq!(merge(&pair(1, 2), &pair(3, 2), &pair(1, 4)), Ok(pair(3, 4)));
+	assert_eq!(
+		merge(&pair(1, 2), &pair(3, 2), &pair(5, 4)),
+		Err(MergeError::Conflicts(Conflicts::between(
+			FieldsChange::BothPair(vec![FieldsPairChange::Left(U8Change(1, 3))]),
+			FieldsChange::BothPair(vec![FieldsPairChange::Left(U8Change(1, 5))]),
+		)))
+	);
+}
+
+#[test]
+fn test_merge_unsupported() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	#[comparable_merge]
+	struct Ignoring {
+		value: u8,
+		#[comparable_ignore]
+		cache: u8,
+	}
+
+	let base = vec![Ignoring { value: 1, cache: 0 }];
+	let left = vec![Ignoring { value: 1, cache: 0 }, Ignoring { value: 2, cache: 0 }];
+	assert_eq!(
+		merge(&base, &left, &base),
+		Err(MergeError::Patch(PatchError::Unsupported(
+			"field `cache` of Ignoring is ignored, so it cannot be rebuilt from a description".to_string()
+		)))
+	);
+}
+
+proptest! {
+
+#[test]
+fn test_merge_proptest(
+	base in prop::collection::btree_map(0u8..6, 0u8..4, 0..6),
+	left in prop::collection::btree_map(0u8..6, 0u8..4, 0..6),
+	right in prop::collection::btree_map(0u8..6, 0u8..4, 0..6),
+) {
+	// Merging with an unchanged side yields the other side.
+	prop_assert_eq!(merge(&base, &left, &base), Ok(left.clone()));
+	prop_assert_eq!(merge(&base, &base, &right), Ok(right.clone()));
+	// A successful merge keeps every entry changed on one side only.
+	if let Ok(merged) = merge(&base, &left, &right) {
+		for key in base.keys().chain(left.keys()).chain(right.keys()) {
+			if left.get(key) != base.get(key) {
+				prop_assert_eq!(merged.get(key), left.get(key));
+			} else {
+				prop_assert_eq!(merged.get(key), right.get(key));
+			}
+		}
+	}
+}
+
+#[test]
+fn test_merge_vec_proptest(
+	base in prop::collection::vec(0u8..4, 0..6),
+	left in prop::collection::vec(0u8..4, 0..6),
+	right in prop::collection::vec(0u8..4, 0..6),
+) {
+	prop_assert_eq!(merge(&base, &left, &base), Ok(left.clone()));
+	prop_assert_eq!(merge(&Aligned(base.clone()), &Aligned(base.clone()), &Aligned(right.clone())), Ok(Aligned(right.clone())));
+	prop_assert_eq!(merge(&Aligned(base.clone()), &Aligned(left.clone()), &Aligned(left.clone())), Ok(Aligned(left)));
+}
+
+}
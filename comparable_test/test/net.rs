@@ -0,0 +1,111 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+fn v4(text: &str) -> Ipv4Addr {
+	text.parse().unwrap()
+}
+
+fn addr(text: &str) -> SocketAddr {
+	text.parse().unwrap()
+}
+
+#[test]
+fn test_ip_addr() {
+	assert_changes!(&v4("10.0.0.1"), &v4("10.0.0.1"), Unchanged);
+	assert_changes!(&v4("10.0.0.1"), &v4("10.0.0.2"), Changed(Ipv4AddrChange(v4("10.0.0.1"), v4("10.0.0.2"))));
+	assert_changes!(
+		&IpAddr::V4(v4("10.0.0.1")),
+		&IpAddr::V4(v4("10.0.0.2")),
+		Changed(IpAddrChange::BothV4(Ipv4AddrChange(v4("10.0.0.1"), v4("10.0.0.2"))))
+	);
+	assert_changes!(
+		&IpAddr::V4(v4("127.0.0.1")),
+		&IpAddr::V6(Ipv6Addr::LOCALHOST),
+		Changed(IpAddrChange::Different(IpAddr::V4(v4("127.0.0.1")), IpAddr::V6(Ipv6Addr::LOCALHOST)))
+	);
+
+	let mut ip = IpAddr::V4(v4("10.0.0.1"));
+	assert_eq!(
+		ip.apply(IpAddrChange::BothV6(Ipv6AddrChange(Ipv6Addr::LOCALHOST, Ipv6Addr::UNSPECIFIED))),
+		Err(PatchError::Mismatch("BothV6(Ipv6AddrChange(::1, ::)) cannot apply to 10.0.0.1".to_string()))
+	);
+	assert_eq!(render::<IpAddr>(&ip.comparison(&IpAddr::V6(Ipv6Addr::LOCALHOST))).to_string(), "10.0.0.1 -> ::1");
+}
+
+#[test]
+fn test_socket_addr() {
+	assert_changes!(&addr("10.0.0.1:80"), &addr("10.0.0.1:80"), Unchanged);
+	assert_changes!(
+		&addr("10.0.0.1:80"),
+		&addr("10.0.0.2:8080"),
+		Changed(vec![
+			SocketAddrChange::Ip(IpAddrChange::BothV4(Ipv4AddrChange(v4("10.0.0.1"), v4("10.0.0.2")))),
+			SocketAddrChange::Port(U16Change(80, 8080)),
+		])
+	);
+	assert_eq!(
+		render::<SocketAddr>(&addr("10.0.0.1:80").comparison(&addr("10.0.0.1:443"))).to_string(),
+		".port: 80 -> 443"
+	);
+
+	let options = CompareOptions::default().ignoring(".port");
+	assert_eq!(addr("10.0.0.1:80").comparison_with(&addr("10.0.0.1:443"), &options), Unchanged);
+}
+
+#[test]
+fn test_socket_addr_patch_merge() {
+	let (base, scoped) = (addr("10.0.0.1:80"), addr("[fe80::1%3]:80"));
+	let mut patched = scoped;
+	if let Changed(change) = scoped.comparison(&base) {
+		patched.apply(change).unwrap();
+	}
+	assert_eq!(patched, base);
+
+	assert_eq!(merge(&base, &addr("10.0.0.2:80"), &addr("10.0.0.1:81")), Ok(addr("10.0.0.2:81")));
+	assert!(merge(&base, &addr("10.0.0.1:81"), &addr("10.0.0.1:82")).is_err());
+
+	let chain = |a: &str, b: &str, c: &str| {
+		compose_changed::<SocketAddr>(addr(a).comparison(&addr(b)), addr(b).comparison(&addr(c)))
+	};
+	assert_eq!(chain("10.0.0.1:80", "[::1]:81", "10.0.0.1:80"), Ok(Unchanged));
+	assert_eq!(
+		chain("10.0.0.1:80", "10.0.0.1:81", "10.0.0.1:82"),
+		Ok(Changed(vec![SocketAddrChange::Port(U16Change(80, 82))]))
+	);
+}
+
+#[derive(Comparable)]
+struct Listener {
+	bind: SocketAddr,
+	peers: Vec<IpAddr>,
+}
+
+#[test]
+fn test_derive_net() {
+	let old = Listener { bind: addr("0.0.0.0:80"), peers: vec![IpAddr::V4(v4("10.0.0.1"))] };
+	let new = Listener { bind: addr("0.0.0.0:81"), peers: vec![IpAddr::V4(v4("10.0.0.1"))] };
+	assert_changes!(&old, &new, Changed(vec![ListenerChange::Bind(vec![SocketAddrChange::Port(U16Change(80, 81))])]));
+}
+
+fn any_addr() -> impl Strategy<Value = SocketAddr> {
+	prop_oneof![
+		(any::<[u8; 4]>(), 0u16..4).prop_map(|(ip, port)| SocketAddr::from((ip, port))),
+		(any::<[u16; 8]>(), 0u16..4, 0u32..2, 0u32..2).prop_map(|(ip, port, flowinfo, scope_id)| {
+			SocketAddr::V6(std::net::SocketAddrV6::new(ip.into(), port, flowinfo, scope_id))
+		}),
+	]
+}
+
+proptest! {
+
+#[test]
+fn test_socket_addr_proptest(a in any_addr(), b in any_addr()) {
+	let mut patched = a;
+	if let Changed(change) = a.comparison(&b) {
+		patched.apply(change).unwrap();
+	}
+	prop_assert_eq!(patched, b);
+}
+
+}
@@ -0,0 +1,191 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+fn lenient() -> CompareOptions {
+	CompareOptions {
+		float_tolerance: FloatTolerance { abs: 1e-9, nan_equal: true, ..FloatTolerance::EXACT },
+		..CompareOptions::default()
+	}
+}
+
+#[test]
+fn test_options_scalars() {
+	assert_eq!((0.1 + 0.2f64).comparison_with(&0.3, &lenient()), Unchanged);
+	assert_eq!(f32::NAN.comparison_with(&f32::NAN, &lenient()), Unchanged);
+	assert_eq!(0.3f64.comparison_with(&0.4, &lenient()), Changed(F64Change(0.3, 0.4)));
+	assert_eq!((0.1 + 0.2f64).comparison_with(&0.3, &CompareOptions::default()), Changed(F64Change(0.1 + 0.2, 0.3)));
+	// Values of other types are compared as usual.
+	assert_eq!(1u8.comparison_with(&2, &lenient()), Changed(U8Change(1, 2)));
+}
+
+#[test]
+fn test_options_collections() {
+	let (a, b) = (0.1 + 0.2, 0.3);
+	assert_eq!(vec![a, 1.0].comparison_with(&vec![b, 1.0], &lenient()), Unchanged);
+	assert_eq!(vec![a].comparison_with(&vec![b, 1.0], &lenient()), Changed(vec![VecChange::Added(1, 1.0)]));
+	assert_eq!(Some(a).comparison_with(&Some(b), &lenient()), Unchanged);
+	assert_eq!(Box::new(a).comparison_with(&Box::new(b), &lenient()), Unchanged);
+	assert_eq!([a, a].comparison_with(&[b, b], &lenient()), Unchanged);
+	assert_eq!((1u8, a).comparison_with(&(1u8, b), &lenient()), Unchanged);
+	assert_eq!((1u8, a).comparison_with(&(2u8, b), &lenient()), Changed((Changed(U8Change(1, 2)), Unchanged)));
+	assert_eq!(BTreeMap::from([(1, a)]).comparison_with(&BTreeMap::from([(1, b)]), &lenient()), Unchanged);
+	assert_eq!(HashMap::from([(1, a)]).comparison_with(&HashMap::from([(1, b)]), &lenient()), Unchanged);
+	assert_eq!(Aligned(vec![1.0, a]).comparison_with(&Aligned(vec![1.0, b]), &lenient()), Unchanged);
+	let reversible = |v| Reversible(BTreeMap::from([("x".to_string(), v)]));
+	assert_eq!(reversible(a).comparison_with(&reversible(b), &lenient()), Unchanged);
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+struct Sample {
+	name: String,
+	readings: Vec<Option<f64>>,
+	#[comparable_tolerance(abs = 0.5)]
+	coarse: f64,
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+enum Measurement {
+	Scalar(f64),
+	Point { x: f32, y: f32 },
+	Sample(Sample),
+}
+
+#[test]
+fn test_options_derived() {
+	let sample = |reading, coarse| Sample { name: "s".to_string(), readings: vec![Some(reading), None], coarse };
+	let (old, new) = (sample(0.1 + 0.2, 1.0), sample(0.3, 1.4));
+	assert_eq!(old.comparison_with(&new, &lenient()), Unchanged);
+	assert_eq!(
+		old.comparison(&new),
+		Changed(vec![SampleChange::Readings(vec![VecChange::Changed(
+			0,
+			OptionChange::BothSome(F64Change(0.1 + 0.2, 0.3))
+		)])])
+	);
+	// A field with its own tolerance keeps it, whatever the options say.
+	assert_eq!(
+		old.comparison_with(&sample(0.1 + 0.2, 1.6), &lenient()),
+		Changed(vec![SampleChange::Coarse(F64Change(1.0, 1.6))])
+	);
+
+	let point = |x: f32| Measurement::Point { x, y: f32::NAN };
+	assert_eq!(point(0.5).comparison_with(&point(0.5), &lenient()), Unchanged);
+	assert!(!point(0.5).comparison(&point(0.5)).is_unchanged());
+	assert_eq!(Measurement::Scalar(0.1 + 0.2).comparison_with(&Measurement::Scalar(0.3), &lenient()), Unchanged);
+	assert_eq!(Measurement::Sample(old.clone()).comparison_with(&Measurement::Sample(new), &lenient()), Unchanged);
+	assert_eq!(
+		Measurement::Scalar(0.0).comparison_with(&Measurement::Point { x: 0.0, y: 1.0 }, &lenient()),
+		Changed(MeasurementChange::Different(MeasurementDesc::Scalar(0.0), MeasurementDesc::Point { x: 0.0, y: 1.0 }))
+	);
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_render]
+struct Meta {
+	version: u32,
+	updated_at: u64,
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_render]
+struct Item {
+	name: String,
+	etag: String,
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_render]
+struct Document {
+	meta: Meta,
+	items: Vec<Item>,
+	attributes: BTreeMap<String, String>,
+}
+
+fn document(updated_at: u64, etag: &str, trace_id: &str) -> Document {
+	let item = |name: &str| Item { name: name.to_string(), etag: format!("{}-{}", name, etag) };
+	Document {
+		meta: Meta { version: 1, updated_at },
+		items: vec![item("a"), item("b")],
+		attributes: BTreeMap::from([
+			("owner".to_string(), "me".to_string()),
+			("trace_id".to_string(), trace_id.to_string()),
+		]),
+	}
+}
+
+#[test]
+fn test_options_ignore() {
+	let (old, new) = (document(1, "x", "t1"), document(2, "y", "t2"));
+	let options = CompareOptions::default()
+		.ignoring(".meta.updated_at")
+		.ignoring(".items[*].etag")
+		.ignoring(r#".attributes["trace_id"]"#);
+	assert_eq!(old.comparison_with(&new, &options), Unchanged);
+	assert_eq!(render::<Document>(&old.comparison(&new)).to_string().lines().count(), 4);
+
+	// Only the parts matching a pattern are left out.
+	let mut changed = new.clone();
+	changed.meta.version = 2;
+	changed.items[1].name = "c".to_string();
+	changed.attributes.insert("owner".to_string(), "you".to_string());
+	assert_eq!(
+		render::<Document>(&old.comparison_with(&changed, &options)).to_string(),
+		".meta.version: 1 -> 2\n.items[1].name: \"b\" -> \"c\"\n.attributes[\"owner\"]: \"me\" -> \"you\""
+	);
+
+	// So are elements and entries added or removed there.
+	changed.items.push(Item { name: "d".to_string(), etag: String::new() });
+	changed.attributes.remove("trace_id");
+	let options = CompareOptions::default().ignoring(".meta").ignoring(".items[2]").ignoring(".attributes[*]");
+	assert_eq!(
+		render::<Document>(&old.comparison_with(&changed, &options)).to_string(),
+		".items[0].etag: \"a-x\" -> \"a-y\"\n.items[1].name: \"b\" -> \"c\"\n.items[1].etag: \"b-x\" -> \"b-y\""
+	);
+	assert_eq!(old.comparison_with(&changed, &CompareOptions::default().ignoring(".*")), Unchanged);
+}
+
+#[test]
+fn test_options_ignore_enums_and_collections() {
+	let point = |x: f32, y: f32| Measurement::Point { x, y };
+	let options = CompareOptions::default().ignoring("::Point.y");
+	assert_eq!(point(0.0, 1.0).comparison_with(&point(0.0, 2.0), &options), Unchanged);
+	assert_eq!(
+		point(0.0, 1.0).comparison_with(&point(1.0, 2.0), &options),
+		Changed(MeasurementChange::BothPoint { x: Changed(F32Change(0.0, 1.0)), y: Unchanged })
+	);
+	let options = CompareOptions::default().ignoring("::Scalar");
+	assert_eq!(Measurement::Scalar(0.0).comparison_with(&Measurement::Scalar(1.0), &options), Unchanged);
+	assert!(!Measurement::Scalar(0.0).comparison_with(&point(0.0, 0.0), &options).is_unchanged());
+
+	let options = CompareOptions::default().ignoring(".1").ignoring("[0][\"k\"]");
+	assert_eq!((1, 2).comparison_with(&(1, 3), &options), Unchanged);
+	assert_eq!(
+		vec![HashMap::from([("k".to_string(), 1), ("j".to_string(), 1)])]
+			.comparison_with(&vec![HashMap::from([("k".to_string(), 2), ("j".to_string(), 2)])], &options),
+		Changed(vec![VecChange::Changed(0, vec![MapChange::Changed("j".to_string(), I32Change(1, 2))])])
+	);
+	let options = CompareOptions::default().ignoring("[1]");
+	assert_eq!([1, 2, 3].comparison_with(&[1, 5, 3], &options), Unchanged);
+	assert_eq!(Aligned(vec![1, 2]).comparison_with(&Aligned(vec![1]), &options), Unchanged);
+}
+
+#[test]
+fn test_path_pattern_parse() {
+	assert!(".meta.updated_at".parse::<PathPattern>().is_ok());
+	assert!(r#"::Rect.0[*]["a]b"]"#.parse::<PathPattern>().is_ok());
+	assert_eq!("meta".parse::<PathPattern>(), Err(PathPatternError("expected `.`, `::` or `[`: meta".to_string())));
+	assert_eq!("".parse::<PathPattern>().unwrap_err().to_string(), "invalid path pattern: empty pattern: ");
+	assert!(".items[0".parse::<PathPattern>().is_err());
+	assert!(".items[]".parse::<PathPattern>().is_err());
+	assert!(".items..etag".parse::<PathPattern>().is_err());
+}
+
+proptest! {
+
+#[test]
+fn test_options_default_proptest(a in prop::collection::vec(any::<f64>(), 0..4), b in prop::collection::vec(any::<f64>(), 0..4)) {
+	prop_assert_eq!(a.comparison_with(&b, &CompareOptions::default()), a.comparison(&b));
+}
+
+}
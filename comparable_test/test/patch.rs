@@ -0,0 +1,298 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Debug;
+
+// Apply the change from `a` to `b` to `a`, and check that this yields `b`.
+fn round_trip<T: Patch + PartialEq + Debug + Clone>(a: &T, b: &T) {
+	let mut patched = a.clone();
+	if let Changed(change) = a.comparison(b) {
+		patched.apply(change).unwrap();
+	}
+	assert_eq!(&patched, b);
+}
+
+#[test]
+fn test_patch_scalars() {
+	round_trip(&1u8, &2u8);
+	round_trip(&-1i64, &1i64);
+	round_trip(&true, &false);
+	round_trip(&'a', &'b');
+	round_trip(&1.5f64, &-2.5f64);
+	round_trip(&"foo".to_string(), &"bar".to_string());
+	round_trip(&std::path::PathBuf::from("/a"), &std::path::PathBuf::from("/b"));
+
+	let mut nan = f32::NAN;
+	nan.apply(F32Change(f32::NAN, 1.0)).unwrap();
+	assert_eq!(nan, 1.0);
+}
+
+#[test]
+fn test_patch_scalar_mismatch() {
+	let mut value = 3i32;
+	assert_eq!(value.apply(I32Change(1, 2)), Err(PatchError::Mismatch("expected 1, found 3".to_string())));
+	assert_eq!(value, 3);
+}
+
+#[test]
+fn test_patch_option_box() {
+	round_trip(&Some(1), &Some(2));
+	round_trip(&Some(1), &None);
+	round_trip(&None, &Some(2));
+	round_trip(&Box::new(1), &Box::new(2));
+	assert!(Some(1).apply(OptionChange::Different(None, Some(2))).is_err());
+	assert!(None::<i32>.apply(OptionChange::BothSome(I32Change(1, 2))).is_err());
+}
+
+#[test]
+fn test_patch_vec() {
+	round_trip(&vec![1, 2, 3], &vec![1, 5, 3, 4]);
+	round_trip(&vec![1, 2, 3], &vec![]);
+	round_trip(&vec![], &vec![1, 2]);
+	assert_eq!(
+		vec![1, 2].apply(vec![VecChange::Removed(5, 1)]),
+		Err(PatchError::Mismatch("no element at index 5".to_string()))
+	);
+	assert!(vec![1, 2].apply(vec![VecChange::Removed(0, 2)]).is_err());
+}
+
+#[test]
+fn test_patch_aligned() {
+	round_trip(&Aligned(vec![1, 2, 3]), &Aligned(vec![0, 1, 2, 3]));
+	round_trip(&Aligned(vec![1, 2, 3, 4]), &Aligned(vec![1, 20, 4]));
+	round_trip(&Aligned(vec![1, 2, 3]), &Aligned(vec![3, 1, 2]));
+	round_trip(&Aligned(vec![1, 2, 3, 4, 5]), &Aligned(vec![5, 2, 9, 1, 4]));
+}
+
+#[test]
+fn test_patch_maps_and_sets() {
+	let map = |v: Vec<(u8, i32)>| v.into_iter().collect::<BTreeMap<_, _>>();
+	round_trip(&map(vec![(0, 1), (1, 2)]), &map(vec![(1, 3), (2, 4)]));
+	let hash_map = |v: Vec<(u8, i32)>| v.into_iter().collect::<HashMap<_, _>>();
+	round_trip(&hash_map(vec![(0, 1), (1, 2)]), &hash_map(vec![(1, 3), (2, 4)]));
+	assert_eq!(
+		map(vec![]).apply(vec![MapChange::Removed(7)]),
+		Err(PatchError::Mismatch("key 7 is not present".to_string()))
+	);
+
+	let set = |v: Vec<i32>| v.into_iter().collect::<BTreeSet<_>>();
+	round_trip(&set(vec![1, 2, 3]), &set(vec![2, 3, 4]));
+	let hash_set = |v: Vec<i32>| v.into_iter().collect::<HashSet<_>>();
+	round_trip(&hash_set(vec![1, 2, 3]), &hash_set(vec![2, 3, 4]));
+	assert!(set(vec![1]).apply(vec![SetChange::Added(1)]).is_err());
+}
+
+#[test]
+fn test_patch_tuples_and_arrays() {
+	round_trip(&(1,), &(2,));
+	round_trip(&(1, "a".to_string(), true), &(1, "b".to_string(), false));
+	round_trip(&[1, 2, 3], &[1, 5, 3]);
+	assert_eq!(<(u8, char)>::from_desc((1, 'a')), Ok((1, 'a')));
+	assert_eq!(<[u8; 2]>::from_desc([1, 2]), Ok([1, 2]));
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_patch]
+struct Record {
+	id: u64,
+	name: String,
+	tags: Vec<String>,
+}
+
+fn record(id: u64, name: &str, tags: &[&str]) -> Record {
+	Record { id, name: name.to_string(), tags: tags.iter().map(|t| t.to_string()).collect() }
+}
+
+#[test]
+fn test_patch_derived_structs() {
+	round_trip(&record(1, "a", &["x"]), &record(2, "b", &["x", "y"]));
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	struct Single {
+		value: Option<Record>,
+	}
+	round_trip(&Single { value: None }, &Single { value: Some(record(1, "a", &[])) });
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	struct Pair(u8, String);
+	round_trip(&Pair(1, "a".to_string()), &Pair(2, "b".to_string()));
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	struct Unit;
+	round_trip(&Unit, &Unit);
+	assert_eq!(Unit::from_desc(UnitDesc), Ok(Unit));
+}
+
+#[test]
+fn test_patch_field_attributes() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	struct Fixture {
+		#[comparable_aligned]
+		values: Vec<u32>,
+		#[comparable_key(|r| -> u64 { r.id })]
+		records: Vec<Record>,
+	}
+
+	round_trip(
+		&Fixture { values: vec![1, 2, 3], records: vec![record(1, "a", &[]), record(2, "b", &[])] },
+		&Fixture { values: vec![3, 1, 2], records: vec![record(1, "c", &[]), record(3, "d", &[])] },
+	);
+}
+
+#[test]
+fn test_patch_keyed() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	struct Team {
+		#[comparable_key(|r| -> u64 { r.id }, moves)]
+		members: Vec<Record>,
+	}
+
+	round_trip(
+		&Team { members: vec![record(1, "a", &[]), record(2, "b", &[]), record(3, "c", &[])] },
+		&Team { members: vec![record(3, "c", &[]), record(1, "x", &[]), record(2, "b", &[])] },
+	);
+
+	// Added elements are appended, since their position is not recorded.
+	let mut team = Team { members: vec![record(1, "a", &[])] };
+	let other = Team { members: vec![record(2, "b", &[]), record(1, "a", &[])] };
+	if let Changed(change) = team.comparison(&other) {
+		team.apply(change).unwrap();
+	}
+	assert_eq!(team.members, vec![record(1, "a", &[]), record(2, "b", &[])]);
+}
+
+#[test]
+fn test_patch_derived_enums() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_compose]
+	enum Shape {
+		Empty,
+		Circle(f64),
+		Rect { width: u32, height: u32 },
+		Labeled(String, Box<Option<u8>>),
+	}
+
+	round_trip(&Shape::Circle(1.0), &Shape::Circle(2.0));
+	round_trip(&Shape::Rect { width: 1, height: 2 }, &Shape::Rect { width: 3, height: 2 });
+	round_trip(&Shape::Empty, &Shape::Rect { width: 3, height: 2 });
+	round_trip(&Shape::Circle(1.0), &Shape::Labeled("c".to_string(), Box::new(None)));
+	round_trip(&Shape::Labeled("c".to_string(), Box::new(None)), &Shape::Labeled("d".to_string(), Box::new(Some(1))));
+
+	assert_eq!(
+		Shape::Empty.apply(ShapeChange::BothCircle(F64Change(1.0, 2.0))),
+		Err(PatchError::Mismatch("expected Shape::Circle".to_string()))
+	);
+	assert!(Shape::Empty.apply(ShapeChange::Different(ShapeDesc::Circle(1.0), ShapeDesc::Empty)).is_err());
+}
+
+#[test]
+fn test_patch_variant_struct_fields() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_compose]
+	#[variant_struct_fields]
+	enum Shape {
+		Point,
+		Rect { width: u32, height: u32 },
+	}
+
+	round_trip(&Shape::Rect { width: 1, height: 2 }, &Shape::Rect { width: 3, height: 4 });
+	round_trip(&Shape::Point, &Shape::Rect { width: 3, height: 4 });
+}
+
+#[test]
+fn test_patch_unsupported() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	struct Ignoring {
+		value: u8,
+		#[comparable_ignore]
+		cache: u8,
+	}
+
+	let mut values = vec![Ignoring { value: 1, cache: 0 }];
+	round_trip(&values, &vec![Ignoring { value: 2, cache: 0 }]);
+	assert_eq!(
+		values.apply(vec![VecChange::Added(1, IgnoringDesc { value: 2 })]),
+		Err(PatchError::Unsupported(
+			"field `cache` of Ignoring is ignored, so it cannot be rebuilt from a description".to_string()
+		))
+	);
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	#[describe_type(String)]
+	#[describe_body(self.0.to_string())]
+	struct Custom(u8);
+
+	assert!(Custom::from_desc("1".to_string()).is_err());
+
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	struct Synthetics {
+		#[comparable_synthetic {
+			let total = |x: &Self| -> u8 { x.values.iter().sum() };
+		}]
+		#[comparable_ignore]
+		values: Vec<u8>,
+	}
+
+	let mut synthetics = Synthetics { values: vec![1] };
+	assert!(synthetics.apply(SyntheticsChange { total: U8Change(1, 2) }).is_err());
+}
+
+#[test]
+fn test_patch_borrowed() {
+	// Without `comparable_patch`, a type's fields need not implement `Patch`.
+	#[derive(Comparable)]
+	struct Borrowed<'a> {
+		name: &'a str,
+	}
+
+	assert_changes!(
+		&Borrowed { name: "a" },
+		&Borrowed { name: "b" },
+		Changed(BorrowedChange { name: StringChange("a".to_string(), "b".to_string()) })
+	);
+
+	// With it, references can be compared but not changed.
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	#[comparable_patch]
+	struct Labelled<'a> {
+		label: &'a str,
+		count: &'a u8,
+	}
+
+	let mut labelled = Labelled { label: "a", count: &1 };
+	assert_eq!(
+		labelled.apply(vec![LabelledChange::Label(StringChange("a".to_string(), "b".to_string()))]),
+		Err(PatchError::Unsupported("a borrowed string cannot be changed".to_string()))
+	);
+	assert_eq!(
+		labelled.apply(vec![LabelledChange::Count(U8Change(1, 2))]),
+		Err(PatchError::Unsupported("a reference cannot be changed".to_string()))
+	);
+}
+
+proptest! {
+
+#[test]
+fn test_patch_vec_proptest(a in prop::collection::vec(0u8..4, 0..8), b in prop::collection::vec(0u8..4, 0..8)) {
+	let mut patched = a.clone();
+	if let Changed(change) = a.comparison(&b) {
+		patched.apply(change).unwrap();
+	}
+	prop_assert_eq!(&patched, &b);
+
+	let mut patched = Aligned(a.clone());
+	if let Changed(change) = Aligned(a).comparison(&Aligned(b.clone())) {
+		patched.apply(change).unwrap();
+	}
+	prop_assert_eq!(patched.0, b);
+}
+
+}
@@ -0,0 +1,130 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::collections::{BinaryHeap, LinkedList, VecDeque};
+
+fn heap(values: Vec<i32>) -> BinaryHeap<i32> {
+	values.into_iter().collect()
+}
+
+#[test]
+fn test_vecdeque() {
+	let deque = |v: Vec<i32>| v.into_iter().collect::<VecDeque<_>>();
+	assert_changes!(&deque(vec![]), &deque(vec![]), Unchanged);
+	assert_changes!(
+		&deque(vec![1, 2, 3]),
+		&deque(vec![1, 4]),
+		Changed(vec![VecChange::Changed(1, I32Change(2, 4)), VecChange::Removed(2, 3)]),
+	);
+
+	let mut rotated = deque(vec![2, 3]);
+	rotated.push_front(1);
+	assert_changes!(&rotated, &deque(vec![1, 2, 3]), Unchanged);
+	assert_eq!(rotated.describe(), vec![1, 2, 3]);
+
+	let options = CompareOptions::default().ignoring("[1]");
+	assert_eq!(deque(vec![1, 2, 3]).comparison_with(&deque(vec![1, 5, 3]), &options), Unchanged);
+}
+
+#[test]
+fn test_linkedlist() {
+	let list = |v: Vec<i32>| v.into_iter().collect::<LinkedList<_>>();
+	assert_changes!(&list(vec![1, 2]), &list(vec![1, 2]), Unchanged);
+	assert_changes!(
+		&list(vec![1]),
+		&list(vec![0, 1]),
+		Changed(vec![VecChange::Changed(0, I32Change(1, 0)), VecChange::Added(1, 1)]),
+	);
+
+	let mut patched = list(vec![1, 2, 3]);
+	if let Changed(change) = patched.comparison(&list(vec![3, 2])) {
+		patched.apply(change).unwrap();
+	}
+	assert_eq!(patched, list(vec![3, 2]));
+	assert_eq!(render::<LinkedList<i32>>(&list(vec![1]).comparison(&list(vec![1, 2]))).to_string(), "[1]: added 2");
+}
+
+#[test]
+fn test_binaryheap() {
+	assert_changes!(&heap(vec![3, 1, 2]), &heap(vec![1, 2, 3]), Unchanged);
+	assert_eq!(heap(vec![3, 1, 2, 1]).describe(), vec![1, 1, 2, 3]);
+	assert_changes!(
+		&heap(vec![1, 2, 2, 3]),
+		&heap(vec![4, 2, 1, 1]),
+		Changed(vec![SetChange::Added(1), SetChange::Added(4), SetChange::Removed(2), SetChange::Removed(3)]),
+	);
+
+	let mut patched = heap(vec![2, 2, 5]);
+	patched.apply(vec![SetChange::Removed(2), SetChange::Added(7)]).unwrap();
+	assert_eq!(patched.into_sorted_vec(), vec![2, 5, 7]);
+	assert_eq!(
+		heap(vec![1]).apply(vec![SetChange::Removed(2)]),
+		Err(PatchError::Mismatch("2 is not present".to_string()))
+	);
+
+	// A change that cannot be applied leaves the heap as it was.
+	let mut patched = heap(vec![3, 1, 2]);
+	assert_eq!(
+		patched.apply(vec![SetChange::Added(4), SetChange::Removed(1), SetChange::Removed(1)]),
+		Err(PatchError::Mismatch("1 is not present".to_string()))
+	);
+	assert_eq!(patched.into_sorted_vec(), vec![1, 2, 3]);
+	let mut patched = heap(vec![3, 1, 1, 2]);
+	patched
+		.apply(vec![SetChange::Removed(1), SetChange::Removed(3), SetChange::Added(3), SetChange::Removed(1)])
+		.unwrap();
+	assert_eq!(patched.into_sorted_vec(), vec![2, 3]);
+	assert_eq!(render::<BinaryHeap<i32>>(&heap(vec![1, 1]).comparison(&heap(vec![1]))).to_string(), "removed 1");
+}
+
+#[test]
+fn test_binaryheap_merge_compose() {
+	let merged = merge(&heap(vec![1, 2]), &heap(vec![1, 2, 3]), &heap(vec![2, 3, 3])).unwrap();
+	assert_eq!(merged.into_sorted_vec(), vec![2, 3, 3]);
+
+	assert_eq!(
+		BinaryHeap::<i32>::compose(vec![SetChange::Added(1), SetChange::Added(1)], vec![SetChange::Removed(1)]),
+		Ok(Changed(vec![SetChange::Added(1)]))
+	);
+	assert_eq!(BinaryHeap::<i32>::compose(vec![SetChange::Removed(1)], vec![SetChange::Added(1)]), Ok(Unchanged));
+	assert_eq!(
+		BinaryHeap::<i32>::invert(vec![SetChange::Added(1), SetChange::Removed(2)]),
+		Ok(vec![SetChange::Removed(1), SetChange::Added(2)])
+	);
+}
+
+proptest! {
+
+#[test]
+fn test_queue_patch_proptest(a in prop::collection::vec(0u8..4, 0..8), b in prop::collection::vec(0u8..4, 0..8)) {
+	let (deque_a, deque_b): (VecDeque<u8>, VecDeque<u8>) = (a.iter().copied().collect(), b.iter().copied().collect());
+	let mut patched = deque_a.clone();
+	if let Changed(change) = deque_a.comparison(&deque_b) {
+		patched.apply(change).unwrap();
+	}
+	prop_assert_eq!(patched, deque_b);
+
+	let (heap_a, heap_b): (BinaryHeap<u8>, BinaryHeap<u8>) = (a.into_iter().collect(), b.into_iter().collect());
+	let mut patched = heap_a.clone();
+	if let Changed(change) = heap_a.comparison(&heap_b) {
+		patched.apply(change).unwrap();
+	}
+	prop_assert_eq!(patched.into_sorted_vec(), heap_b.into_sorted_vec());
+}
+
+#[test]
+fn test_binaryheap_compose_proptest(
+	a in prop::collection::vec(0u8..4, 0..8),
+	b in prop::collection::vec(0u8..4, 0..8),
+	c in prop::collection::vec(0u8..4, 0..8),
+) {
+	let (a, b, c): (BinaryHeap<u8>, BinaryHeap<u8>, BinaryHeap<u8>) =
+		(a.into_iter().collect(), b.into_iter().collect(), c.into_iter().collect());
+	let composed = compose_changed::<BinaryHeap<u8>>(a.comparison(&b), b.comparison(&c)).unwrap();
+	let mut patched = a.clone();
+	if let Changed(change) = composed {
+		patched.apply(change).unwrap();
+	}
+	prop_assert_eq!(patched.into_sorted_vec(), c.into_sorted_vec());
+}
+
+}
@@ -0,0 +1,69 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+use std::ops::{Range, RangeInclusive};
+
+#[test]
+fn test_range() {
+	assert_changes!(&(1..5), &(1..5), Unchanged);
+	assert_changes!(&(1..5), &(2..5), Changed(vec![RangeChange::Start(I32Change(1, 2))]));
+	assert_changes!(
+		&(1..5),
+		&(0..9),
+		Changed(vec![RangeChange::Start(I32Change(1, 0)), RangeChange::End(I32Change(5, 9))])
+	);
+	assert_eq!((1u8..=3).describe(), 1u8..=3);
+	assert_changes!(&(1u8..=3), &(1u8..=4), Changed(vec![RangeChange::End(U8Change(3, 4))]));
+
+	assert_eq!(render::<Range<i32>>(&(1..5).comparison(&(0..9))).to_string(), ".start: 1 -> 0\n.end: 5 -> 9");
+	let options = CompareOptions::default().ignoring(".end");
+	assert_eq!((1..5).comparison_with(&(1..9), &options), Unchanged);
+}
+
+#[test]
+fn test_range_patch_merge() {
+	let mut range = 1..=3;
+	range.apply(vec![RangeChange::Start(I32Change(1, 0))]).unwrap();
+	assert_eq!(range, 0..=3);
+	assert_eq!(
+		range.apply(vec![RangeChange::End(I32Change(4, 5))]),
+		Err(PatchError::Mismatch("expected 4, found 3".to_string()))
+	);
+
+	// Changes to different ends merge, while changes to the same end conflict.
+	assert_eq!(merge(&(1..5), &(2..5), &(1..6)), Ok(2..6));
+	assert!(merge(&(1..5), &(2..5), &(3..5)).is_err());
+	assert_eq!(
+		Range::<i32>::invert(vec![RangeChange::End(I32Change(5, 6))]),
+		Ok(vec![RangeChange::End(I32Change(6, 5))])
+	);
+}
+
+#[test]
+fn test_range_derive() {
+	#[derive(Clone, PartialEq, Debug, Comparable)]
+	struct Selection {
+		lines: RangeInclusive<u32>,
+		columns: Range<u32>,
+	}
+
+	assert_changes!(
+		&Selection { lines: 1..=2, columns: 0..4 },
+		&Selection { lines: 1..=3, columns: 0..4 },
+		Changed(vec![SelectionChange::Lines(vec![RangeChange::End(U32Change(2, 3))])])
+	);
+}
+
+proptest! {
+
+#[test]
+fn test_range_compose_proptest(a in 0u8..4, b in 0u8..4, c in 0u8..4, d in 0u8..4, e in 0u8..4, f in 0u8..4) {
+	let (first, second, third) = (a..b, c..d, e..f);
+	let composed = compose_changed::<Range<u8>>(first.comparison(&second), second.comparison(&third)).unwrap();
+	let mut patched = first.clone();
+	if let Changed(change) = composed {
+		patched.apply(change).unwrap();
+	}
+	prop_assert_eq!(patched, third);
+}
+
+}
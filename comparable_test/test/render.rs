@@ -0,0 +1,173 @@
+use comparable::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+fn rendered<T: Render>(a: &T, b: &T) -> String {
+	render::<T>(&a.comparison(b)).to_string()
+}
+
+#[test]
+fn test_render_scalars() {
+	assert_eq!(rendered(&1, &2), "1 -> 2");
+	assert_eq!(rendered(&true, &true), "");
+	assert_eq!(rendered(&"a".to_string(), &"b".to_string()), "\"a\" -> \"b\"");
+	assert_eq!(rendered(&"a", &"b"), "\"a\" -> \"b\"");
+	assert_eq!(rendered(&Some(1), &None), "Some(1) -> None");
+	assert_eq!(rendered(&Some(1), &Some(2)), "1 -> 2");
+	assert_eq!(rendered(&Box::new('a'), &Box::new('b')), "'a' -> 'b'");
+}
+
+#[test]
+fn test_render_tuples_and_arrays() {
+	assert_eq!(rendered(&(1,), &(2,)), ".0: 1 -> 2");
+	assert_eq!(rendered(&(1, 'a', true), &(2, 'a', false)), ".0: 1 -> 2\n.2: true -> false");
+	assert_eq!(rendered(&[1, 2, 3], &[1, 5, 3]), "[1]: 2 -> 5");
+}
+
+#[test]
+fn test_render_collections() {
+	assert_eq!(rendered(&vec![1, 2, 3], &vec![1, 5]), "[1]: 2 -> 5\n[2]: removed 3");
+	assert_eq!(rendered(&vec![1], &vec![1, 2]), "[1]: added 2");
+	assert_eq!(rendered(&Aligned(vec![1, 2, 3]), &Aligned(vec![2, 3, 1])), "[0]: moved from 0 to 2");
+
+	let set = |v: Vec<i32>| v.into_iter().collect::<BTreeSet<_>>();
+	assert_eq!(rendered(&set(vec![1, 2]), &set(vec![2, 3])), "added 3\nremoved 1");
+
+	let map = |v: Vec<(&str, i32)>| v.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<BTreeMap<_, _>>();
+	assert_eq!(
+		rendered(&map(vec![("a", 1), ("b", 2)]), &map(vec![("a", 3), ("c", 4)])),
+		"[\"a\"]: 1 -> 3\n[\"c\"]: added 4\n[\"b\"]: removed"
+	);
+	let hash_map = |v: Vec<(u8, i32)>| v.into_iter().collect::<HashMap<_, _>>();
+	assert_eq!(rendered(&hash_map(vec![(0, 1)]), &hash_map(vec![])), "[0]: removed");
+	assert_eq!(rendered(&Reversible(map(vec![("a", 1)])), &Reversible(map(vec![]))), "[\"a\"]: removed 1");
+}
+
+#[derive(Comparable)]
+#[comparable_render]
+struct Inner {
+	flags: Vec<bool>,
+	label: String,
+}
+
+#[derive(Comparable)]
+#[comparable_render]
+struct Outer {
+	inner: Inner,
+	attributes: BTreeMap<String, String>,
+	#[comparable_key(|t| -> u8 { t.0 })]
+	tagged: Vec<(u8, char)>,
+}
+
+#[derive(Comparable)]
+#[comparable_render]
+struct Single {
+	inner: Inner,
+}
+
+#[derive(Comparable)]
+#[comparable_render]
+struct Tuple(u8, Single);
+
+#[test]
+fn test_render_derived_structs() {
+	let outer = |flag: bool, value: &str, tag: char| Outer {
+		inner: Inner { flags: vec![true, true, false, flag], label: "x".to_string() },
+		attributes: vec![("k".to_string(), value.to_string())].into_iter().filter(|(_, v)| !v.is_empty()).collect(),
+		tagged: vec![(1, tag)],
+	};
+	assert_eq!(
+		rendered(&outer(true, "", 'a'), &outer(false, "v", 'b')),
+		".inner.flags[3]: true -> false\n.attributes[\"k\"]: added \"v\"\n.tagged[1].1: 'a' -> 'b'"
+	);
+
+	let single = |flag| Single { inner: Inner { flags: vec![flag], label: "x".to_string() } };
+	assert_eq!(rendered(&single(true), &single(false)), ".inner.flags[0]: true -> false");
+	assert_eq!(
+		rendered(&Tuple(1, single(true)), &Tuple(2, single(false))),
+		".0: 1 -> 2\n.1.inner.flags[0]: true -> false"
+	);
+}
+
+#[derive(Comparable)]
+#[comparable_render]
+enum Shape {
+	Empty,
+	Circle(f64),
+	Rect { width: u32, height: u32 },
+}
+
+#[derive(Comparable)]
+#[comparable_render]
+#[variant_struct_fields]
+enum Fields {
+	Pair { left: u8, right: u8 },
+	Nothing,
+}
+
+#[test]
+fn test_render_derived_enums() {
+	assert_eq!(rendered(&Shape::Circle(1.0), &Shape::Circle(2.0)), "::Circle: 1.0 -> 2.0");
+	assert_eq!(
+		rendered(&Shape::Rect { width: 1, height: 2 }, &Shape::Rect { width: 3, height: 4 }),
+		"::Rect.width: 1 -> 3\n::Rect.height: 2 -> 4"
+	);
+	assert_eq!(rendered(&Shape::Empty, &Shape::Circle(1.0)), "Empty -> Circle(1.0)");
+	assert_eq!(
+		rendered(&Fields::Pair { left: 1, right: 2 }, &Fields::Pair { left: 1, right: 3 }),
+		"::Pair.right: 2 -> 3"
+	);
+	assert_eq!(rendered(&(Shape::Empty,), &(Shape::Empty,)), "");
+}
+
+#[test]
+fn test_change_report() {
+	let changes = |a: Vec<i32>, b: Vec<i32>| a.comparison(&b);
+	assert_eq!(ChangeReport::between::<Vec<i32>>(&changes(vec![1], vec![2]), &changes(vec![1], vec![2])), None);
+
+	let report =
+		ChangeReport::between::<Vec<i32>>(&changes(vec![1, 2], vec![1, 3]), &changes(vec![1, 2], vec![4, 2, 5]))
+			.unwrap();
+	let leaf = |index, change| Leaf { path: ChangePath(vec![PathSegment::Index(index)]), change };
+	assert_eq!(report.missing, vec![leaf(1, LeafChange::Changed("2".to_string(), "3".to_string()))]);
+	assert_eq!(report.unexpected, report.actual);
+	assert_eq!(
+		report.to_string(),
+		"actual changes:\n\
+		 \x20   [0]: 1 -> 4\n\
+		 \x20   [2]: added 5\n\
+		 missing changes, expected but not found:\n\
+		 \x20 - [1]: 2 -> 3\n\
+		 unexpected changes, found but not expected:\n\
+		 \x20 + [0]: 1 -> 4\n\
+		 \x20 + [2]: added 5\n"
+	);
+	assert!(report.report(true).contains("[0]: \x1b[31m1\x1b[0m -> \x1b[32m4\x1b[0m"));
+
+	// Changes that render the same, but are not equal.
+	let report = ChangeReport::between::<Vec<i32>>(
+		&Changed::Changed(vec![VecChange::Added(0, 1), VecChange::Added(1, 2)]),
+		&Changed::Changed(vec![VecChange::Added(1, 2), VecChange::Added(0, 1)]),
+	)
+	.unwrap();
+	assert!(report.missing.is_empty() && report.unexpected.is_empty());
+	assert!(report.to_string().ends_with("differ only in their order or representation)\n"));
+}
+
+#[test]
+fn test_assert_changes_rendered() {
+	assert_changes_rendered!(
+		&(1, 'a'),
+		&(1, 'b'),
+		Changed::Changed((Changed::Unchanged, Changed::Changed(CharChange('a', 'b'))))
+	);
+}
+
+#[test]
+#[should_panic(expected = "unexpected changes, found but not expected:\n  + .0: 1 -> 2")]
+fn test_assert_changes_rendered_failure() {
+	assert_changes_rendered!(
+		&(1, 'a'),
+		&(2, 'b'),
+		Changed::Changed((Changed::Unchanged, Changed::Changed(CharChange('a', 'b'))))
+	);
+}
@@ -0,0 +1,81 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+
+type Outcome = Result<i32, String>;
+
+#[test]
+fn test_result() {
+	assert_changes!(&(Ok(1) as Outcome), &Ok(1), Unchanged);
+	assert_changes!(&(Ok(1) as Outcome), &Ok(2), Changed(ResultChange::BothOk(I32Change(1, 2))));
+	assert_changes!(
+		&(Err("a".to_string()) as Outcome),
+		&Err("b".to_string()),
+		Changed(ResultChange::BothErr(StringChange("a".to_string(), "b".to_string()))),
+	);
+	assert_changes!(
+		&(Ok(1) as Outcome),
+		&Err("b".to_string()),
+		Changed(ResultChange::Different(Ok(1), Err("b".to_string()))),
+	);
+}
+
+#[test]
+fn test_result_patch() {
+	let mut value: Outcome = Ok(1);
+	value.apply(ResultChange::Different(Ok(1), Err("x".to_string()))).unwrap();
+	assert_eq!(value, Err("x".to_string()));
+	assert_eq!(
+		value.apply(ResultChange::BothOk(I32Change(1, 2))),
+		Err(PatchError::Mismatch("expected Ok, found Err".to_string()))
+	);
+	assert_eq!(
+		Outcome::invert(ResultChange::BothErr(StringChange("a".to_string(), "b".to_string()))),
+		Ok(ResultChange::BothErr(StringChange("b".to_string(), "a".to_string())))
+	);
+	assert_eq!(merge(&(Ok(1) as Outcome), &Ok(2), &Ok(2)), Ok(Ok(2)));
+	assert!(merge(&(Ok(1) as Outcome), &Ok(2), &Err("x".to_string())).is_err());
+}
+
+#[test]
+fn test_result_compose() {
+	let chain = |a: &Outcome, b: &Outcome, c: &Outcome| compose_changed::<Outcome>(a.comparison(b), b.comparison(c));
+	assert_eq!(chain(&Ok(1), &Ok(2), &Ok(3)), Ok(Changed(ResultChange::BothOk(I32Change(1, 3)))));
+	assert_eq!(chain(&Ok(1), &Err("x".to_string()), &Ok(1)), Ok(Unchanged));
+	assert_eq!(
+		chain(&Ok(1), &Err("x".to_string()), &Err("y".to_string())),
+		Ok(Changed(ResultChange::Different(Ok(1), Err("y".to_string()))))
+	);
+}
+
+#[derive(Comparable)]
+#[comparable_render]
+struct Cached {
+	key: String,
+	outcome: Result<u32, String>,
+}
+
+#[test]
+fn test_result_derive_render() {
+	let old = Cached { key: "k".to_string(), outcome: Ok(1) };
+	let new = Cached { key: "k".to_string(), outcome: Ok(2) };
+	assert_changes!(&old, &new, Changed(vec![CachedChange::Outcome(ResultChange::BothOk(U32Change(1, 2)))]));
+	assert_eq!(render::<Cached>(&old.comparison(&new)).to_string(), ".outcome::Ok: 1 -> 2");
+
+	let options = CompareOptions::default().ignoring(".outcome::Ok");
+	assert_eq!(old.comparison_with(&new, &options), Unchanged);
+	let failed = Cached { key: "k".to_string(), outcome: Err("gone".to_string()) };
+	assert!(!old.comparison_with(&failed, &options).is_unchanged());
+}
+
+proptest! {
+
+#[test]
+fn test_result_proptest(a in any::<Result<u8, bool>>(), b in any::<Result<u8, bool>>()) {
+	let mut patched = a;
+	if let Changed(change) = a.comparison(&b) {
+		patched.apply(change).unwrap();
+	}
+	prop_assert_eq!(patched, b);
+}
+
+}
@@ -2,6 +2,8 @@
 #![allow(clippy::unnecessary_cast)]
 
 use comparable::{Changed::*, *};
+use std::num::{NonZeroI64, NonZeroU32};
+use std::time::{Duration, Instant, SystemTime};
 
 #[test]
 fn test_unit() {
@@ -90,3 +92,94 @@ fn test_char() {
 	assert_changes!(&'a', &'a', Unchanged);
 	assert_changes!(&'a', &'b', Changed(CharChange('a', 'b')));
 }
+
+#[test]
+fn test_duration() {
+	let ms = Duration::from_millis;
+	assert_changes!(&ms(5), &ms(5), Unchanged);
+	assert_changes!(&ms(5), &ms(8), Changed(DurationChange(ms(5), ms(8), TimeDelta::positive(ms(3)))));
+	assert_changes!(&ms(8), &ms(5), Changed(DurationChange(ms(8), ms(5), TimeDelta::negative(ms(3)))));
+	assert_eq!(TimeDelta::negative(ms(3)).to_string(), "-3ms");
+	assert_eq!(TimeDelta::negative(ms(0)), TimeDelta::positive(ms(0)));
+
+	let options = CompareOptions { time_tolerance: ms(5), ..CompareOptions::default() };
+	assert_eq!(ms(100).comparison_with(&ms(105), &options), Unchanged);
+	assert_eq!(ms(105).comparison_with(&ms(100), &options), Unchanged);
+	assert!(!ms(100).comparison_with(&ms(106), &options).is_unchanged());
+}
+
+#[test]
+fn test_system_time_instant() {
+	let epoch = SystemTime::UNIX_EPOCH;
+	let later = epoch + Duration::from_secs(60);
+	assert_changes!(&epoch, &epoch, Unchanged);
+	assert_changes!(
+		&later,
+		&epoch,
+		Changed(SystemTimeChange(later, epoch, TimeDelta::negative(Duration::from_secs(60))))
+	);
+
+	let start = Instant::now();
+	let end = start + Duration::from_millis(2);
+	assert_changes!(&start, &start, Unchanged);
+	assert_changes!(
+		&start,
+		&end,
+		Changed(InstantChange(InstantDesc(start), InstantDesc(end), TimeDelta::positive(Duration::from_millis(2))))
+	);
+	let options = CompareOptions { time_tolerance: Duration::from_millis(5), ..CompareOptions::default() };
+	assert_eq!(end.comparison_with(&start, &options), Unchanged);
+
+	let mut patched = start;
+	if let Changed(change) = start.comparison(&end) {
+		patched.apply(change).unwrap();
+	}
+	assert_eq!(patched, end);
+	assert_changes_match!(&start, &end, Matcher::changed(Matcher::increased()));
+}
+
+#[test]
+fn test_time_changes() {
+	let s = Duration::from_secs;
+	assert_eq!(
+		Duration::invert(DurationChange(s(1), s(3), TimeDelta::positive(s(2)))),
+		Ok(DurationChange(s(3), s(1), TimeDelta::negative(s(2))))
+	);
+	let chain = |a: Duration, b: Duration, c: Duration| compose_changed::<Duration>(a.comparison(&b), b.comparison(&c));
+	assert_eq!(chain(s(1), s(3), s(2)), Ok(Changed(DurationChange(s(1), s(2), TimeDelta::positive(s(1))))));
+	assert_eq!(chain(s(1), s(3), s(1)), Ok(Unchanged));
+	assert_eq!(render::<Duration>(&s(1).comparison(&s(2))).to_string(), "1s -> 2s");
+}
+
+#[test]
+fn test_nonzero() {
+	let n = |value: u32| NonZeroU32::new(value).unwrap();
+	assert_changes!(&n(1), &n(1), Unchanged);
+	assert_changes!(&n(1), &n(2), Changed(NonZeroU32Change(n(1), n(2))));
+	let i = |value: i64| NonZeroI64::new(value).unwrap();
+	assert_changes!(&i(-1), &i(1), Changed(NonZeroI64Change(i(-1), i(1))));
+	assert_changes_match!(&n(1), &n(2), Matcher::changed(Matcher::increased()));
+}
+
+#[test]
+fn test_ordering() {
+	use std::cmp::Ordering;
+
+	assert_changes!(&Ordering::Less, &Ordering::Less, Unchanged);
+	assert_changes!(&Ordering::Less, &Ordering::Greater, Changed(OrderingChange(Ordering::Less, Ordering::Greater)));
+	assert_eq!(Ordering::Less.describe(), -1);
+	assert_eq!(Ordering::from_desc(1), Ok(Ordering::Greater));
+	assert_eq!(Ordering::from_desc(2), Err(PatchError::Unsupported("2 does not describe an ordering".to_string())));
+	assert_eq!(render::<Ordering>(&Ordering::Equal.comparison(&Ordering::Less)).to_string(), "0 -> -1");
+	assert_changes_match!(&Ordering::Less, &Ordering::Equal, Matcher::changed(Matcher::increased()));
+}
+
+#[test]
+fn test_wrapping() {
+	use std::num::Wrapping;
+
+	assert_changes!(&Wrapping(255u8), &(Wrapping(255u8) + Wrapping(1)), Changed(U8Change(255, 0)));
+	let mut patched = Wrapping(1u8);
+	patched.apply(U8Change(1, 2)).unwrap();
+	assert_eq!(patched, Wrapping(2));
+}
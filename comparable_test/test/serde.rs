@@ -18,7 +18,7 @@
 
 #![allow(dead_code)]
 
-use comparable::{Changed, Comparable};
+use comparable::{Changed, Comparable, OrderingChange};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -46,6 +46,7 @@ fn builtin_desc_and_change_are_serde() {
 	assert_change_is_serde::<f64>();
 	assert_change_is_serde::<String>();
 	assert_change_is_serde::<Option<u32>>();
+	assert_change_is_serde::<Result<u32, String>>();
 	assert_change_is_serde::<Vec<u32>>();
 	assert_change_is_serde::<std::collections::BTreeSet<u32>>();
 	assert_change_is_serde::<std::collections::HashSet<u32>>();
@@ -56,6 +57,18 @@ fn builtin_desc_and_change_are_serde() {
 	assert_change_is_serde::<(u8, u16, u32)>();
 	assert_change_is_serde::<Box<u32>>();
 	assert_change_is_serde::<std::path::PathBuf>();
+	assert_change_is_serde::<std::ffi::OsString>();
+	assert_change_is_serde::<std::net::IpAddr>();
+	assert_change_is_serde::<std::net::SocketAddr>();
+	assert_change_is_serde::<std::num::NonZeroU32>();
+	assert_change_is_serde::<std::time::Duration>();
+	assert_change_is_serde::<std::time::SystemTime>();
+	assert_change_is_serde::<std::time::Instant>();
+	assert_change_is_serde::<std::ops::Range<u32>>();
+	assert_change_is_serde::<std::ops::RangeInclusive<u32>>();
+	assert_change_is_serde::<std::cmp::Ordering>();
+	assert_change_is_serde::<std::num::Wrapping<u32>>();
+	assert_change_is_serde::<std::marker::PhantomData<u32>>();
 	// A generic projection: the bound holds for *any* `T: Comparable`.
 	assert_serialize::<<Vec<i32> as Comparable>::Change>();
 }
@@ -143,3 +156,179 @@ fn generic_struct_change_round_trips() {
 	let back: <Wrapper<u32> as Comparable>::Change = serde_json::from_str(&json).expect("Change should deserialize");
 	assert_eq!(change, back);
 }
+
+// --- JSON Patch ---------------------------------------------------------------
+
+// The JSON Patch for the change between two values, checking that applying it
+// to the JSON of the first value yields that of the second.
+fn patch_between<T: comparable::Render + Serialize>(a: &T, b: &T) -> serde_json::Value {
+	check_patch(a, b, serde_json::to_value(a).unwrap(), serde_json::to_value(b).unwrap())
+}
+
+fn check_patch<T: comparable::Render>(
+	a: &T,
+	b: &T,
+	mut json: serde_json::Value,
+	expected: serde_json::Value,
+) -> serde_json::Value {
+	let patch = comparable::json_patch::<T>(&a.comparison(b)).expect("change should be expressible");
+	json_patch::patch(&mut json, &serde_json::from_value::<json_patch::Patch>(patch.clone()).unwrap())
+		.expect("patch should apply");
+	assert_eq!(json, expected);
+	patch
+}
+
+fn patch_between_aligned<T: Clone + PartialEq + comparable::Render + Serialize>(a: &[T], b: &[T]) -> serde_json::Value {
+	let json = |v: &[T]| serde_json::to_value(v).unwrap();
+	check_patch(&comparable::Aligned(a.to_vec()), &comparable::Aligned(b.to_vec()), json(a), json(b))
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Comparable)]
+#[comparable_render]
+struct Settings {
+	name: String,
+	sizes: Vec<u32>,
+	labels: std::collections::BTreeMap<String, String>,
+	limit: Option<u8>,
+	shape: Shape,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Comparable)]
+#[comparable_render]
+enum Shape {
+	Empty,
+	Circle(f64),
+	Rect { width: u32, height: u32 },
+}
+
+fn settings(name: &str, sizes: Vec<u32>, labels: &[(&str, &str)], shape: Shape) -> Settings {
+	Settings {
+		name: name.to_string(),
+		sizes,
+		labels: labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+		limit: None,
+		shape,
+	}
+}
+
+#[test]
+fn json_patch_of_derived_struct() {
+	use serde_json::json;
+
+	let a = settings("a", vec![1, 2, 3], &[("k", "v"), ("a/b", "x")], Shape::Rect { width: 1, height: 2 });
+	let b = settings("b", vec![1, 5], &[("k", "w"), ("new", "y")], Shape::Rect { width: 3, height: 2 });
+	assert_eq!(
+		patch_between(&a, &b),
+		json!([
+			{ "op": "replace", "path": "/name", "value": "b" },
+			{ "op": "replace", "path": "/sizes/1", "value": 5 },
+			{ "op": "remove", "path": "/sizes/2" },
+			{ "op": "replace", "path": "/labels/k", "value": "w" },
+			{ "op": "add", "path": "/labels/new", "value": "y" },
+			{ "op": "remove", "path": "/labels/a~1b" },
+			{ "op": "replace", "path": "/shape/Rect/width", "value": 3 },
+		])
+	);
+
+	let c = Settings { limit: Some(3), shape: Shape::Circle(1.0), ..a.clone() };
+	assert_eq!(
+		patch_between(&a, &c),
+		json!([
+			{ "op": "replace", "path": "/limit", "value": 3 },
+			{ "op": "replace", "path": "/shape", "value": { "Circle": 1.0 } },
+		])
+	);
+	let d = Settings { shape: Shape::Circle(2.0), ..c.clone() };
+	assert_eq!(patch_between(&c, &d), json!([{ "op": "replace", "path": "/shape/Circle", "value": 2.0 }]));
+	assert_eq!(patch_between(&d, &d), json!([]));
+}
+
+#[test]
+fn json_patch_of_collections() {
+	use serde_json::json;
+
+	assert_eq!(patch_between(&1, &2), json!([{ "op": "replace", "path": "", "value": 2 }]));
+	assert_eq!(patch_between_aligned(&[1, 2, 3], &[2, 3, 1]), json!([{ "op": "move", "from": "/0", "path": "/2" }]));
+	let mut numbers = std::collections::BTreeMap::new();
+	numbers.insert(1u8, vec![true]);
+	let mut more = numbers.clone();
+	more.insert(2, vec![]);
+	more.get_mut(&1).unwrap().push(false);
+	assert_eq!(
+		patch_between(&numbers, &more),
+		json!([
+			{ "op": "add", "path": "/1/1", "value": false },
+			{ "op": "add", "path": "/2", "value": [] },
+		])
+	);
+
+	let set = |v: Vec<u8>| v.into_iter().collect::<std::collections::BTreeSet<_>>();
+	assert_eq!(patch_between(&set(vec![1]), &set(vec![1, 2])), json!([{ "op": "add", "path": "/-", "value": 2 }]));
+	assert_eq!(
+		comparable::json_patch::<std::collections::BTreeSet<u8>>(&set(vec![1]).comparison(&set(vec![]))),
+		Err(comparable::JsonPatchError("the member 1 of the set at \"\" has no index".to_string()))
+	);
+}
+
+#[test]
+fn json_patch_of_keyed_vector_is_an_error() {
+	#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Comparable)]
+	#[comparable_render]
+	struct Keys {
+		#[comparable_key(|t| -> u8 { t.0 })]
+		tagged: Vec<(u8, char)>,
+	}
+
+	let result =
+		comparable::json_patch::<Keys>(&Keys { tagged: vec![(1, 'a')] }.comparison(&Keys { tagged: vec![(1, 'b')] }));
+	assert!(result.unwrap_err().to_string().contains("keyed by 1"));
+}
+
+proptest::proptest! {
+
+#[test]
+fn json_patch_of_vectors_applies(
+	a in proptest::collection::vec(0u8..4, 0..8),
+	b in proptest::collection::vec(0u8..4, 0..8),
+) {
+	patch_between(&a, &b);
+	patch_between_aligned(&a, &b);
+}
+
+#[test]
+fn json_patch_of_nested_vectors_applies(
+	a in proptest::collection::vec(proptest::collection::vec(0u8..3, 0..3), 0..4),
+	b in proptest::collection::vec(proptest::collection::vec(0u8..3, 0..3), 0..4),
+) {
+	patch_between(&a, &b);
+}
+
+}
+
+// An `Instant` is written relative to an instant fixed by the process, so it
+// reads back as the same instant within the process.
+#[test]
+fn instant_change_round_trips() {
+	let start = std::time::Instant::now();
+	let change = match start.comparison(&(start + std::time::Duration::from_millis(5))) {
+		Changed::Changed(c) => c,
+		Changed::Unchanged => panic!("expected a change"),
+	};
+
+	let json = serde_json::to_string(&change).expect("Change should serialize");
+	let back: <std::time::Instant as Comparable>::Change =
+		serde_json::from_str(&json).expect("Change should deserialize");
+	assert_eq!(change, back);
+}
+
+// An `Ordering` is written as the number -1, 0 or 1.
+#[test]
+fn ordering_change_round_trips() {
+	use std::cmp::Ordering;
+
+	let change = OrderingChange(Ordering::Less, Ordering::Greater);
+	let json = serde_json::to_string(&change).expect("Change should serialize");
+	assert_eq!(json, "[-1,1]");
+	assert_eq!(serde_json::from_str::<OrderingChange>(&json).expect("Change should deserialize"), change);
+	assert!(serde_json::from_str::<OrderingChange>("[-1,2]").is_err());
+}
@@ -3,7 +3,9 @@
 
 use std::collections::{BTreeSet, HashSet};
 
-use comparable::{assert_changes, Changed::*, I32Change, SetChange, VecChange};
+use comparable::{
+	assert_changes, render, Changed::*, Comparable, I32Change, Patch, PatchError, SetChange, U8Change, VecChange,
+};
 
 #[test]
 fn test_vec() {
@@ -37,6 +39,35 @@ fn test_vec() {
 	);
 }
 
+#[test]
+fn test_slice() {
+	let (a, b): (&[i32], &[i32]) = (&[1, 2, 3], &[1, 4]);
+	assert_changes!(&a, &a, Unchanged);
+	assert_changes!(&a, &b, Changed(vec![VecChange::Changed(1, I32Change(2, 4)), VecChange::Removed(2, 3)]));
+	assert_eq!(a[..2].comparison(&b[..1]), Changed(vec![VecChange::Removed(1, 2)]));
+	assert_eq!(a.describe(), vec![1, 2, 3]);
+	assert_eq!(render::<&[i32]>(&<&[i32]>::comparison(&a, &b)).to_string(), "[1]: 2 -> 4\n[2]: removed 3");
+
+	// A borrowed slice can be compared but not changed.
+	let mut c = a;
+	assert_eq!(
+		c.apply(vec![VecChange::Removed(2, 3)]),
+		Err(PatchError::Unsupported("a borrowed slice cannot be changed".to_string()))
+	);
+
+	#[derive(PartialEq, Debug, Comparable)]
+	struct Packet {
+		header: &'static [u8],
+		body: Vec<u8>,
+	}
+
+	assert_changes!(
+		&Packet { header: &[1, 2], body: vec![] },
+		&Packet { header: &[1, 3], body: vec![] },
+		Changed(vec![PacketChange::Header(vec![VecChange::Changed(1, U8Change(2, 3))])]),
+	);
+}
+
 #[test]
 fn test_hashset() {
 	assert_changes!(&(vec![] as Vec<i32>), &vec![], Unchanged);
@@ -0,0 +1,175 @@
+use comparable::{Changed::*, *};
+use proptest::prelude::*;
+
+use StringSpan::*;
+
+fn equal(text: &str) -> StringSpan {
+	Equal(text.to_string())
+}
+
+fn delete(text: &str) -> StringSpan {
+	Delete(text.to_string())
+}
+
+fn insert(text: &str) -> StringSpan {
+	Insert(text.to_string())
+}
+
+fn graphemes(text: &str) -> Graphemes<String> {
+	Graphemes(text.to_string())
+}
+
+fn words(text: &str) -> Words<String> {
+	Words(text.to_string())
+}
+
+#[test]
+fn test_graphemes_comparison() {
+	assert_changes!(&graphemes("abc"), &graphemes("abc"), Unchanged);
+	assert_changes!(
+		&graphemes("Jon Smith"),
+		&graphemes("John Smyth"),
+		Changed(vec![equal("Jo"), insert("h"), equal("n Sm"), delete("i"), insert("y"), equal("th")])
+	);
+	// A base letter and a combining accent are one grapheme.
+	assert_changes!(
+		&graphemes("cafe\u{301}"),
+		&graphemes("cafe"),
+		Changed(vec![equal("caf"), delete("e\u{301}"), insert("e")])
+	);
+	assert_changes!(&graphemes(""), &graphemes("ab"), Changed(vec![insert("ab")]));
+}
+
+#[test]
+fn test_words_comparison() {
+	assert_changes!(
+		&words("The quick brown fox"),
+		&words("The slow brown fox, again"),
+		Changed(vec![equal("The "), delete("quick"), insert("slow"), equal(" brown fox"), insert(", again")])
+	);
+	assert_eq!(
+		render::<Words<String>>(&words("user_id = 10").comparison(&words("user_name = 12"))).to_string(),
+		"[-user_id-]{+user_name+} = [-10-]{+12+}"
+	);
+}
+
+#[derive(Clone, PartialEq, Debug, Comparable)]
+#[comparable_patch]
+#[comparable_render]
+struct Person {
+	#[comparable_graphemes]
+	name: String,
+	#[comparable_words]
+	title: String,
+}
+
+#[test]
+fn test_spans_attributes() {
+	let person = |name: &str, title: &str| Person { name: name.to_string(), title: title.to_string() };
+	let (old, new) = (person("Jon", "Senior Engineer"), person("John", "Staff Engineer"));
+	assert_changes!(
+		&old,
+		&new,
+		Changed(vec![
+			PersonChange::Name(vec![equal("Jo"), insert("h"), equal("n")]),
+			PersonChange::Title(vec![delete("Senior"), insert("Staff"), equal(" Engineer")]),
+		])
+	);
+	assert_eq!(
+		render::<Person>(&old.comparison(&new)).to_string(),
+		".name: Jo{+h+}n\n.title: [-Senior-]{+Staff+} Engineer"
+	);
+
+	let expected = old.comparison(&person("Jon", "Staff Engineer"));
+	let report = ChangeReport::between::<Person>(&expected, &old.comparison(&new)).unwrap();
+	assert_eq!(
+		report.report(true),
+		"actual changes:\n    \
+		 .name: Jo\x1b[32m{+h+}\x1b[0mn\n    \
+		 .title: \x1b[31m[-Senior-]\x1b[0m\x1b[32m{+Staff+}\x1b[0m Engineer\n\
+		 missing changes, expected but not found:\n    \
+		 (none)\n\
+		 unexpected changes, found but not expected:\n  \
+		 + .name: Jo\x1b[32m{+h+}\x1b[0mn\n"
+	);
+
+	let mut patched = old.clone();
+	patched.apply(old.comparison(&new).unwrap_or_default()).unwrap();
+	assert_eq!(patched, new);
+}
+
+#[test]
+fn test_spans_patch_and_invert() {
+	let change = graphemes("kitten").comparison(&graphemes("sitting")).unwrap_or_default();
+	assert_eq!(
+		Graphemes::<String>::invert(change.clone()),
+		Ok(vec![delete("s"), insert("k"), equal("itt"), delete("i"), insert("e"), equal("n"), delete("g")])
+	);
+	let mut value = graphemes("kitten");
+	value.apply(change.clone()).unwrap();
+	assert_eq!(value, graphemes("sitting"));
+	assert!(graphemes("mitten").apply(change).is_err());
+}
+
+#[test]
+fn test_spans_merge() {
+	let base = words("one two three four");
+	assert_eq!(merge(&base, &words("One two three four"), &words("one two three 4")), Ok(words("One two three 4")));
+	assert_eq!(merge(&base, &words("one 2 three four"), &words("one 2 three four")), Ok(words("one 2 three four")));
+	assert_eq!(
+		merge(&base, &words("one 2 three four"), &words("one II three 4")),
+		Err(MergeError::Conflicts(Conflicts::between(
+			vec![equal("one "), delete("two"), insert("2"), equal(" three four")],
+			vec![equal("one "), delete("two"), insert("II"), equal(" three four")]
+		)))
+	);
+}
+
+#[test]
+fn test_spans_compose() {
+	let (a, b, c) = (graphemes("abc"), graphemes("aXc"), graphemes("abc"));
+	assert_eq!(
+		Graphemes::<String>::compose(a.comparison(&b).unwrap_or_default(), b.comparison(&c).unwrap_or_default()),
+		Ok(Unchanged)
+	);
+	let c = graphemes("aXcd");
+	assert_eq!(
+		Graphemes::<String>::compose(a.comparison(&b).unwrap_or_default(), b.comparison(&c).unwrap_or_default()),
+		Ok(a.comparison(&c))
+	);
+	assert!(Graphemes::<String>::compose(a.comparison(&b).unwrap_or_default(), a.comparison(&c).unwrap_or_default())
+		.is_err());
+}
+
+fn text() -> impl Strategy<Value = String> {
+	prop::collection::vec(prop::sample::select(vec!["a", "b", " ", "é", "cd"]), 0..8).prop_map(|parts| parts.concat())
+}
+
+proptest! {
+
+#[test]
+fn test_spans_proptest(a in text(), b in text(), c in text()) {
+	let (a, b, c) = (graphemes(&a), graphemes(&b), graphemes(&c));
+	let mut value = a.clone();
+	if let Changed(change) = a.comparison(&b) {
+		value.apply(Graphemes::<String>::invert(Graphemes::<String>::invert(change).unwrap()).unwrap()).unwrap();
+	}
+	prop_assert_eq!(&value, &b);
+	let mut value = a.clone();
+	if let Changed(change) = compose_changed::<Graphemes<String>>(a.comparison(&b), b.comparison(&c)).unwrap() {
+		value.apply(change).unwrap();
+	}
+	prop_assert_eq!(&value, &c);
+	prop_assert_eq!(merge(&a, &b, &a), Ok(b.clone()));
+	prop_assert_eq!(merge(&a, &b, &c).is_ok(), merge(&a, &c, &b).is_ok());
+
+	let (a, b) = (words(&a), words(&b));
+	let mut value = b.clone();
+	if let Changed(change) = a.comparison(&b) {
+		value.apply(Words::<String>::invert(change).unwrap()).unwrap();
+	}
+	prop_assert_eq!(&value, &a);
+	prop_assert_eq!(merge(&a, &a, &b), Ok(b.clone()));
+}
+
+}
@@ -3,15 +3,34 @@
 // #[comparable_ignore] and the various derive shapes.
 #![allow(dead_code)]
 
+mod align;
+mod approx;
 mod array;
 mod boxes;
+mod cell;
+mod compose;
+mod delta;
 mod empty;
 mod enums;
+mod ffi;
+mod invert;
+mod keyed;
+mod lines;
 mod map;
+mod matcher;
+mod merge;
+mod net;
 mod opt;
+mod options;
+mod patch;
 mod path;
+mod queue;
+mod range;
+mod render;
+mod result;
 mod scalar;
 mod set;
+mod spans;
 mod string;
 mod structs;
 mod tuple;
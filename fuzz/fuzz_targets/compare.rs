@@ -2,10 +2,13 @@
 #![allow(clippy::derive_partial_eq_without_eq)]
 
 use arbitrary::Arbitrary;
-use comparable::Comparable;
+use comparable::{compose_changed, merge, render, Changed, Comparable, Invert, Patch};
 use libfuzzer_sys::fuzz_target;
 
 #[derive(Arbitrary, Clone, Debug, comparable::Comparable)]
+#[comparable_merge]
+#[comparable_compose]
+#[comparable_render]
 struct Inner {
     a: i64,
     b: Vec<u8>,
@@ -13,11 +16,17 @@ struct Inner {
 }
 
 #[derive(Arbitrary, Clone, Debug, comparable::Comparable)]
+#[comparable_merge]
+#[comparable_compose]
+#[comparable_render]
 struct Sample {
     id: u64,
     name: String,
     tags: Vec<i32>,
     nested: Option<Box<Inner>>,
+    // NaN must compare as unchanged from itself.
+    #[comparable_tolerance(nan_equal)]
+    score: f64,
 }
 
 fuzz_target!(|pair: (Sample, Sample)| {
@@ -31,4 +40,26 @@ fuzz_target!(|pair: (Sample, Sample)| {
     let _ = x.describe();
     let _ = y.describe();
     let _ = x.comparison(&y);
+    let _ = render::<Sample>(&x.comparison(&y)).to_string();
+
+    // Invariant: applying the change from x to y to x yields y.
+    let mut patched = x.clone();
+    if let Changed::Changed(change) = x.comparison(&y) {
+        patched.apply(change).unwrap();
+    }
+    assert!(patched.comparison(&y).is_unchanged());
+
+    // Invariant: applying the inverse of that change to y yields x.
+    let mut undone = y.clone();
+    if let Changed::Changed(change) = x.comparison(&y) {
+        undone.apply(Sample::invert(change).unwrap()).unwrap();
+    }
+    assert!(undone.comparison(&x).is_unchanged());
+
+    // Invariant: merging a change with no change yields the changed value.
+    assert!(merge(&x, &x, &y).unwrap().comparison(&y).is_unchanged());
+    assert!(merge(&x, &y, &x).unwrap().comparison(&y).is_unchanged());
+
+    // Invariant: a change composed with the change that undoes it vanishes.
+    assert!(compose_changed::<Sample>(x.comparison(&y), y.comparison(&x)).unwrap().is_unchanged());
 });